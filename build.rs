@@ -1,5 +1,11 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Protokol dosyasının yolu proto/orchestrator.proto olmalı
-    tonic_build::compile_protos("proto/orchestrator.proto")?;
+    // proto/orchestrator.proto sadece dokümantasyondur (trafik HTTP/WS JSON) ve derlenmez.
+    // grpc.health.v1: sadece sunucu tarafı gerekli. Descriptor seti GRPC_REFLECTION için kullanılır.
+    let descriptor_path =
+        std::path::PathBuf::from(std::env::var("OUT_DIR")?).join("grpc_descriptor.bin");
+    tonic_build::configure()
+        .build_client(false)
+        .file_descriptor_set_path(descriptor_path)
+        .compile_protos(&["proto/health.proto"], &["proto"])?;
     Ok(())
 }
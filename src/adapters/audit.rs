@@ -0,0 +1,121 @@
+// src/adapters/audit.rs
+use serde::Serialize;
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AuditEntry {
+    pub timestamp: String,
+    pub action: String,
+    pub service: Option<String>,
+    pub node: String,
+    pub source_ip: Option<String>,
+    pub scope: Option<String>,
+    pub result: String,
+}
+
+// [ARCH-COMPLIANCE]: Değiştiren tüm aksiyonlar için JSON-lines denetim kaydı. Kayıtlar asla silinmez:
+// dosya `max_bytes` sınırını aşınca `<path>.<UTC zaman damgası>` adıyla kenara alınır; eski
+// nesillerin temizliği operatöre (logrotate vb.) bırakılır.
+pub struct AuditLog {
+    path: Option<String>,
+    max_bytes: u64,
+    write_lock: Mutex<()>,
+}
+
+impl AuditLog {
+    pub fn new(path: Option<String>, max_bytes: u64) -> Self {
+        Self {
+            path,
+            max_bytes,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    pub async fn record(&self, entry: AuditEntry) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let _guard = self.write_lock.lock().await;
+        if let Err(e) = self.append(path, &entry).await {
+            warn!(event="AUDIT_WRITE_FAIL", path=%path, action=%entry.action, error=%e, "⚠️ Failed to write audit entry.");
+        }
+    }
+
+    async fn append(&self, path: &str, entry: &AuditEntry) -> anyhow::Result<()> {
+        if let Some(parent) = Path::new(path).parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        if let Ok(meta) = tokio::fs::metadata(path).await {
+            if self.max_bytes > 0 && meta.len() >= self.max_bytes {
+                tokio::fs::rename(path, Self::rotated_path(path).await).await?;
+            }
+        }
+
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        file.write_all(&line).await?;
+        // tokio dosyası yazımı arka planda tamamlar; kilit bırakılmadan (ve olası bir sonraki
+        // döndürmeden) önce satırın diske ulaştığı garanti edilir.
+        file.flush().await?;
+        Ok(())
+    }
+
+    // Aynı saniyede birden fazla döndürmede mevcut dosyanın üzerine yazılmaz, sıra eki eklenir.
+    async fn rotated_path(path: &str) -> String {
+        let base = format!("{}.{}", path, chrono::Utc::now().format("%Y%m%dT%H%M%SZ"));
+        let mut candidate = base.clone();
+        let mut n = 1;
+        while tokio::fs::try_exists(&candidate).await.unwrap_or(false) {
+            candidate = format!("{}-{}", base, n);
+            n += 1;
+        }
+        candidate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(action: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp: String::new(),
+            action: action.into(),
+            service: None,
+            node: "edge-01".into(),
+            source_ip: None,
+            scope: None,
+            result: "ok".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn rotation_never_overwrites_previous_generations() {
+        let dir = std::env::temp_dir().join(format!("audit-rotate-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("audit.log").to_string_lossy().to_string();
+        let log = AuditLog::new(Some(path), 1);
+        for action in ["first", "second", "third"] {
+            log.record(entry(action)).await;
+        }
+
+        let mut contents: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| std::fs::read_to_string(e.unwrap().path()).unwrap())
+            .collect();
+        contents.sort();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(contents.len(), 3);
+        for action in ["first", "second", "third"] {
+            assert!(contents.iter().any(|c| c.contains(action)), "{}", action);
+        }
+    }
+}
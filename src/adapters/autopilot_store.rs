@@ -0,0 +1,88 @@
+// src/adapters/autopilot_store.rs
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use tracing::{info, warn};
+
+// [ARCH-COMPLIANCE]: UI'dan yapılan auto-pilot tercihleri restart/self-update sonrası kaybolmasın.
+pub struct AutoPilotStore;
+
+impl AutoPilotStore {
+    // Dosyadaki kullanıcı tercihi önceliklidir; env sadece dosyada olmayan servisleri tohumlar.
+    pub fn load(path: &str, env_defaults: &[String]) -> HashMap<String, bool> {
+        let mut map: HashMap<String, bool> = match std::fs::read_to_string(path) {
+            Ok(raw) => match serde_json::from_str(&raw) {
+                Ok(m) => {
+                    info!(event="AUTOPILOT_STATE_LOADED", path=%path, "📂 Auto-pilot state loaded from disk.");
+                    m
+                }
+                Err(e) => {
+                    warn!(event="AUTOPILOT_STATE_CORRUPT", path=%path, error=%e, "⚠️ Auto-pilot state file is corrupt, falling back to env config.");
+                    HashMap::new()
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => {
+                warn!(event="AUTOPILOT_STATE_UNREADABLE", path=%path, error=%e, "⚠️ Auto-pilot state file could not be read, falling back to env config.");
+                HashMap::new()
+            }
+        };
+
+        for svc in env_defaults {
+            map.entry(svc.clone()).or_insert(true);
+        }
+        map
+    }
+
+    pub async fn save(path: &str, map: &HashMap<String, bool>) -> Result<()> {
+        Self::write_atomic(path, map).await
+    }
+
+    // Bakım penceresi dışında bulunup bekletilen güncellemeler; dosya yoksa kuyruk boştur.
+    pub fn load_pending(path: &str) -> BTreeSet<String> {
+        match std::fs::read_to_string(path) {
+            Ok(raw) => serde_json::from_str(&raw).unwrap_or_else(|e| {
+                warn!(event="AUTOPILOT_PENDING_CORRUPT", path=%path, error=%e, "⚠️ Pending update queue is corrupt, starting empty.");
+                BTreeSet::new()
+            }),
+            Err(_) => BTreeSet::new(),
+        }
+    }
+
+    pub async fn save_pending(path: &str, pending: &BTreeSet<String>) -> Result<()> {
+        Self::write_atomic(path, pending).await
+    }
+
+    // Yarım yazılmış dosya bırakmamak için geçici dosyaya yazılıp rename edilir.
+    async fn write_atomic(path: &str, value: &impl Serialize) -> Result<()> {
+        if let Some(parent) = Path::new(path).parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let tmp = format!("{}.tmp", path);
+        tokio::fs::write(&tmp, serde_json::to_vec_pretty(value)?).await?;
+        tokio::fs::rename(&tmp, path).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_preference_survives_env_seed() {
+        let dir = std::env::temp_dir().join(format!("autopilot-store-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("autopilot.json").to_string_lossy().to_string();
+        std::fs::write(&path, r#"{"web-service": false}"#).unwrap();
+
+        let map = AutoPilotStore::load(
+            &path,
+            &["web-service".to_string(), "api-service".to_string()],
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(map.get("web-service"), Some(&false));
+        assert_eq!(map.get("api-service"), Some(&true));
+    }
+}
@@ -1,31 +1,141 @@
 // src/adapters/docker.rs
 use anyhow::Result;
 use bollard::container::{
-    Config, CreateContainerOptions, InspectContainerOptions, LogOutput, LogsOptions,
-    PruneContainersOptions, RemoveContainerOptions, RestartContainerOptions, StartContainerOptions,
-    Stats, StatsOptions, StopContainerOptions,
+    Config, CreateContainerOptions, InspectContainerOptions, ListContainersOptions, LogOutput,
+    LogsOptions, NetworkingConfig, PruneContainersOptions, RemoveContainerOptions,
+    RenameContainerOptions, RestartContainerOptions, StartContainerOptions, Stats, StatsOptions,
+    StopContainerOptions,
 };
-use bollard::image::{CreateImageOptions, PruneImagesOptions};
+use bollard::exec::{CreateExecOptions, StartExecResults};
+use bollard::image::{CreateImageOptions, ListImagesOptions, PruneImagesOptions};
+use bollard::models::{
+    ContainerInspectResponse, EndpointSettings, EventMessage, HealthStatusEnum, HostConfig, Mount,
+    MountPointTypeEnum, MountTypeEnum, Port, PortBinding,
+};
+use bollard::network::{ConnectNetworkOptions, DisconnectNetworkOptions};
+use bollard::system::EventsOptions;
+use bollard::volume::{ListVolumesOptions, PruneVolumesOptions};
 use bollard::Docker;
 use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::default::Default;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 
+use crate::adapters::registry::RegistryAuth;
+use crate::core::broadcast::Broadcaster;
+use crate::core::domain::{
+    BatchUpdateResult, EnvPatchResult, ExecOutput, ImageInfo, LifecycleEvent, PortMapping,
+    PruneParams, PruneReport, PrunedItem, ServiceSpec, UpdateProgressData, WsMessage,
+};
+use crate::core::governor::Governor;
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct DockerDaemonInfo {
+    pub version: String,
+    pub api_version: String,
+    pub min_api_version: String,
+    pub negotiated_api_version: String,
+    pub os: String,
+    pub arch: String,
+    // Pull/create'te sabitlenen platform (ör. `linux/arm64`); FORCE_PLATFORM ile ezilebilir
+    pub platform: String,
+}
+
 #[derive(Clone)]
 pub struct DockerAdapter {
     client: Docker,
     node_name: String,
-    tx: Arc<broadcast::Sender<String>>,
+    tx: Arc<Broadcaster>,
+    daemon: DockerDaemonInfo,
+    backup_dir: Option<PathBuf>,
+    backup_keep: usize,
+    prehook: Option<String>,
+    posthook: Option<String>,
+    health_timeout: Duration,
+    registry_auth: RegistryAuth,
+    stop_timeout: i64,
+    pull_max_attempts: u32,
+    // Orchestrator'ın kendi container ID'si (tespit edilemezse isim sezgiseline düşülür)
+    self_container_id: Option<String>,
+    // ALLOW_SELF_UPDATE: kendi güncellemesi yardımcı container'a devredilir
+    allow_self_update: bool,
+    // Manuel, disk eşiği ve zamanlanmış prune'lar aynı anda çalışmasın diye paylaşılan kilit
+    prune_lock: Arc<tokio::sync::Mutex<()>>,
+    // Edge -> upstream komut kanalına aktarılan yaşam döngüsü olayları
+    lifecycle_tx: broadcast::Sender<LifecycleEvent>,
+}
+
+/// İmaj çekme hatasının sınıfı; API katmanı bunu HTTP durum koduna çevirir.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PullErrorKind {
+    // 401/403: registry kimlik bilgileri eksik ya da geçersiz
+    Auth,
+    // İmaj/manifest yok veya referans geçersiz
+    NotFound,
+    // Ağ, zaman aşımı veya registry 5xx
+    Registry,
 }
 
+#[derive(Debug)]
+pub struct PullError {
+    pub kind: PullErrorKind,
+    message: String,
+}
+
+impl std::fmt::Display for PullError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for PullError {}
+
+/// Başka bir prune sürerken dönen hata; API 409'a çevirir, otomatik tetikleyiciler alarm üretmeden atlar.
+#[derive(Debug)]
+pub struct PruneBusy;
+
+impl std::fmt::Display for PruneBusy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("A system prune is already in progress")
+    }
+}
+
+impl std::error::Error for PruneBusy {}
+
+// Tek bir güncellemenin ayrıntılı ilerleme olaylarını (pull katmanları + yaşam döngüsü) taşır.
+pub type UpdateProgressTx = tokio::sync::mpsc::UnboundedSender<serde_json::Value>;
+
+const PREHOOK_LABEL: &str = "orch.prehook";
+const POSTHOOK_LABEL: &str = "orch.posthook";
+const STOP_TIMEOUT_LABEL: &str = "sentiric.stop_timeout";
+// Virgülle ayrılmış servis isimleri: sıralı restart'ta bu servisler önce hazır olmalıdır
+const DEPENDS_ON_LABEL: &str = "sentiric.depends_on";
+// `recreate` (varsayılan) veya `bluegreen`: yeni container sağlıklı olmadan eskisine dokunulmaz
+const UPDATE_STRATEGY_LABEL: &str = "sentiric.update_strategy";
+// Label yoksa güncelleme akışında uygulanan drain süresi
+const UPDATE_DRAIN_TIMEOUT: i64 = 60;
+const HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+const PULL_RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+// Blue-green sonunda aday container'ın asıl isme taşınması için deneme sayısı (1 sn arayla)
+const BLUEGREEN_RENAME_ATTEMPTS: u32 = 3;
+const EXEC_TIMEOUT: Duration = Duration::from_secs(30);
+const EXEC_MAX_OUTPUT: usize = 1024 * 1024;
+// Daemon'ın anonim volume'lara koyduğu label; API 1.42+ volume prune sadece bunları siler
+const ANONYMOUS_VOLUME_LABEL: &str = "com.docker.volume.anonymous";
+// Upstream bağlantısı yokken biriken olaylar bu sınırdan sonra düşer.
+const LIFECYCLE_QUEUE_LEN: usize = 64;
+const SELF_UPDATE_DISABLED: &str = "Self-update is disabled (ALLOW_SELF_UPDATE=false)";
+
+// Yardımcı container bu argümanla başlatılır: `<binary> self-update-helper <container_id> <image>`
+pub const SELF_UPDATE_HELPER_ARG: &str = "self-update-helper";
+
 impl DockerAdapter {
-    pub fn new(
-        socket: &str,
-        node_name: String,
-        tx: Arc<broadcast::Sender<String>>,
-    ) -> Result<Self> {
+    pub fn new(socket: &str, node_name: String, tx: Arc<Broadcaster>) -> Result<Self> {
         let client = Docker::connect_with_unix(socket, 120, bollard::API_DEFAULT_VERSION)
             .or_else(|_| Docker::connect_with_local_defaults())
             .map_err(|e| anyhow::anyhow!("Docker Bağlantı Hatası: {}", e))?;
@@ -34,42 +144,449 @@ impl DockerAdapter {
             client,
             node_name,
             tx,
+            daemon: DockerDaemonInfo::default(),
+            backup_dir: None,
+            backup_keep: 0,
+            prehook: None,
+            posthook: None,
+            health_timeout: Duration::from_secs(15),
+            registry_auth: RegistryAuth::default(),
+            stop_timeout: 10,
+            pull_max_attempts: 3,
+            self_container_id: None,
+            allow_self_update: false,
+            prune_lock: Arc::new(tokio::sync::Mutex::new(())),
+            lifecycle_tx: broadcast::channel(LIFECYCLE_QUEUE_LEN).0,
         })
     }
 
+    pub fn with_update_hooks(mut self, prehook: Option<String>, posthook: Option<String>) -> Self {
+        self.prehook = prehook;
+        self.posthook = posthook;
+        self
+    }
+
+    pub fn with_update_health_timeout(mut self, secs: u64) -> Self {
+        self.health_timeout = Duration::from_secs(secs);
+        self
+    }
+
+    pub fn with_stop_timeout(mut self, secs: i64) -> Self {
+        self.stop_timeout = secs;
+        self
+    }
+
+    pub fn with_pull_retries(mut self, max_attempts: u32) -> Self {
+        self.pull_max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_registry_auth(mut self, auth: RegistryAuth) -> Self {
+        self.registry_auth = auth;
+        self
+    }
+
+    pub fn with_platform(mut self, forced: Option<String>) -> Self {
+        if let Some(p) = forced {
+            self.daemon.platform = p;
+        }
+        self
+    }
+
+    pub fn with_self_update(mut self, allowed: bool) -> Self {
+        self.allow_self_update = allowed;
+        self
+    }
+
+    pub fn with_config_backups(mut self, dir: Option<String>, keep: usize) -> Self {
+        self.backup_dir = dir.filter(|_| keep > 0).map(PathBuf::from);
+        self.backup_keep = keep;
+        self
+    }
+
+    // [ARCH-COMPLIANCE FIX]: Eski daemon'larda API_DEFAULT_VERSION uyuşmazlığından doğan
+    // anlaşılmaz hataları önlemek için client sürümü daemon ile pazarlık edilerek düşürülür.
+    pub async fn negotiate_version(mut self) -> Self {
+        match self.client.clone().negotiate_version().await {
+            Ok(client) => self.client = client,
+            Err(e) => {
+                warn!(event="DOCKER_API_NEGOTIATION_FAIL", error=%e, "⚠️ Docker API version negotiation failed, using default client version.");
+            }
+        }
+
+        self.daemon.negotiated_api_version = self.client.client_version().to_string();
+        match self.client.version().await {
+            Ok(v) => {
+                self.daemon.version = v.version.unwrap_or_default();
+                self.daemon.api_version = v.api_version.unwrap_or_default();
+                self.daemon.min_api_version = v.min_api_version.unwrap_or_default();
+                self.daemon.os = v.os.unwrap_or_default();
+                self.daemon.arch = v.arch.unwrap_or_default();
+                info!(
+                    event = "DOCKER_API_NEGOTIATED",
+                    node.name = %self.node_name,
+                    docker.version = %self.daemon.version,
+                    docker.api_version = %self.daemon.api_version,
+                    docker.negotiated_api_version = %self.daemon.negotiated_api_version,
+                    "🐳 Connected to Docker daemon v{} (API v{})",
+                    self.daemon.version,
+                    self.daemon.negotiated_api_version
+                );
+            }
+            Err(e) => {
+                warn!(event="DOCKER_VERSION_FAIL", error=%e, "⚠️ Could not read Docker daemon version.");
+            }
+        }
+        // Karışık (arm64/amd64) cluster'larda yanlış mimari imaj çekilip "exec format error" ile
+        // çökmemesi için platform daemon'dan (yoksa derlendiği mimariden) türetilir.
+        if self.daemon.platform.is_empty() {
+            self.daemon.platform = Self::detect_platform(&self.daemon.os, &self.daemon.arch);
+        }
+        info!(event="DOCKER_PLATFORM", platform=%self.daemon.platform, "🧩 Image platform pinned to {}", self.daemon.platform);
+        self
+    }
+
+    fn detect_platform(os: &str, arch: &str) -> String {
+        let os = if os.is_empty() { "linux" } else { os };
+        let arch = match arch {
+            "" => match std::env::consts::ARCH {
+                "x86_64" => "amd64",
+                "aarch64" => "arm64",
+                "arm" => "arm",
+                other => other,
+            },
+            other => other,
+        };
+        format!("{}/{}", os, arch)
+    }
+
+    /// Kendi container ID'si `/proc/self/cgroup`, `/proc/self/mountinfo` veya `HOSTNAME`
+    /// (Docker varsayılanı kısa ID'dir) üzerinden bulunur ve daemon'a sorularak doğrulanır.
+    pub async fn identify_self(mut self) -> Self {
+        for candidate in Self::self_id_candidates() {
+            if let Ok(inspect) = self
+                .client
+                .inspect_container(&candidate, None::<InspectContainerOptions>)
+                .await
+            {
+                self.self_container_id = inspect.id;
+                info!(event="SELF_IDENTIFIED", container.id=%self.self_container_id.as_deref().unwrap_or_default(), "🪪 Running inside container, self-protection uses container ID.");
+                return self;
+            }
+        }
+        warn!(
+            event = "SELF_IDENTIFY_FAIL",
+            "⚠️ Own container ID not found, falling back to name heuristic for self-protection."
+        );
+        self
+    }
+
+    fn self_id_candidates() -> Vec<String> {
+        let is_id = |s: &str| s.len() >= 12 && s.chars().all(|c| c.is_ascii_hexdigit());
+        let mut candidates = Vec::new();
+        // cgroup v1: `.../docker/<id>`, cgroup v2 (mountinfo): `.../containers/<id>/hostname`
+        for path in ["/proc/self/cgroup", "/proc/self/mountinfo"] {
+            let Ok(raw) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            for segment in raw.split(['/', '\n', ' ', '-', '.']) {
+                if segment.len() == 64 && is_id(segment) && !candidates.iter().any(|c| c == segment)
+                {
+                    candidates.push(segment.to_string());
+                }
+            }
+        }
+        if let Ok(host) = std::env::var("HOSTNAME") {
+            if is_id(&host) {
+                candidates.push(host);
+            }
+        }
+        candidates
+    }
+
+    // [ARCH-COMPLIANCE]: İntihar Yasağı kontrolü. ID biliniyorsa ID ile, değilse isimle karşılaştırılır.
+    fn is_self(&self, inspect: &ContainerInspectResponse, svc_name: &str) -> bool {
+        match &self.self_container_id {
+            Some(own) => inspect.id.as_deref() == Some(own.as_str()),
+            None => svc_name.contains("orchestrator"),
+        }
+    }
+
+    pub async fn is_self_container(&self, svc_id: &str) -> bool {
+        match self.inspect_service(svc_id).await {
+            Ok(inspect) => self.is_self(&inspect, svc_id),
+            Err(_) => false,
+        }
+    }
+
+    fn broadcast_progress(&self, service: &str, progress: Option<String>) {
+        self.tx.send(
+            WsMessage::UpdateProgress {
+                data: UpdateProgressData {
+                    service: service.to_string(),
+                    progress,
+                },
+            }
+            .encode(),
+        );
+    }
+
+    // Yerel UI'a yayınlanır ve komut kanalı açıksa upstream'e de iletilir.
+    fn emit_lifecycle(&self, service: &str, kind: &str, message: Option<String>) {
+        let event = LifecycleEvent {
+            node: self.node_name.clone(),
+            service: service.to_string(),
+            kind: kind.to_string(),
+            message,
+            ts: chrono::Utc::now().to_rfc3339(),
+        };
+        self.tx.send(
+            WsMessage::Lifecycle {
+                data: event.clone(),
+            }
+            .encode(),
+        );
+        let _ = self.lifecycle_tx.send(event);
+    }
+
+    pub fn subscribe_lifecycle(&self) -> broadcast::Receiver<LifecycleEvent> {
+        self.lifecycle_tx.subscribe()
+    }
+
+    pub async fn ping(&self) -> Result<()> {
+        self.client
+            .ping()
+            .await
+            .map_err(|e| anyhow::anyhow!("Docker ping error: {}", e))?;
+        Ok(())
+    }
+
+    pub fn get_event_stream(
+        &self,
+    ) -> impl Stream<Item = Result<EventMessage, bollard::errors::Error>> {
+        let mut filters = HashMap::new();
+        filters.insert("type".to_string(), vec!["container".to_string()]);
+        self.client.events(Some(EventsOptions::<String> {
+            filters,
+            ..Default::default()
+        }))
+    }
+
     pub fn get_client(&self) -> Docker {
         self.client.clone()
     }
 
+    pub fn daemon_info(&self) -> &DockerDaemonInfo {
+        &self.daemon
+    }
+
     // --- LIFECYCLE ---
     pub async fn start_service(&self, svc_id: &str) -> Result<()> {
         info!(event="CONTAINER_START", node.name=%self.node_name, container.id=%svc_id, "▶️ Starting container: {}", svc_id);
         self.client
             .start_container(svc_id, None::<StartContainerOptions<String>>)
             .await?;
+        self.emit_lifecycle(svc_id, "started", None);
         Ok(())
     }
 
     pub async fn stop_service(&self, svc_id: &str) -> Result<()> {
-        info!(event="CONTAINER_STOP", node.name=%self.node_name, container.id=%svc_id, "🛑 Stopping container: {}", svc_id);
+        let t = self.resolve_stop_timeout(svc_id, self.stop_timeout).await;
+        info!(event="CONTAINER_STOP", node.name=%self.node_name, container.id=%svc_id, stop_timeout=t, "🛑 Stopping container: {}", svc_id);
         self.client
-            .stop_container(svc_id, Some(StopContainerOptions { t: 10 }))
+            .stop_container(svc_id, Some(StopContainerOptions { t }))
             .await?;
+        self.emit_lifecycle(svc_id, "stopped", None);
         Ok(())
     }
 
     pub async fn restart_service(&self, svc_id: &str) -> Result<()> {
-        info!(event="CONTAINER_RESTART", node.name=%self.node_name, container.id=%svc_id, "🔄 Restarting container: {}", svc_id);
+        let t = self.resolve_stop_timeout(svc_id, self.stop_timeout).await;
+        info!(event="CONTAINER_RESTART", node.name=%self.node_name, container.id=%svc_id, stop_timeout=t, "🔄 Restarting container: {}", svc_id);
         self.client
-            .restart_container(svc_id, Some(RestartContainerOptions { t: 10 }))
+            .restart_container(svc_id, Some(RestartContainerOptions { t: t as isize }))
             .await?;
+        self.emit_lifecycle(svc_id, "restarted", None);
+        Ok(())
+    }
+
+    /// Servisleri `sentiric.depends_on` label'larına göre katmanlara ayırıp sırayla restart eder;
+    /// bir katmandaki tüm servisler hazır (running + healthy) olmadan sonrakine geçilmez.
+    /// Bir katman başarısız olursa kalan servisler atlanır. Döngü varsa hiçbir şeye dokunulmaz.
+    pub async fn restart_ordered(&self, services: &[String]) -> Result<Vec<BatchUpdateResult>> {
+        let mut deps = HashMap::new();
+        for svc in services {
+            let inspect = self.inspect_service(svc).await?;
+            let depends_on = inspect
+                .config
+                .and_then(|c| c.labels)
+                .and_then(|l| l.get(DEPENDS_ON_LABEL).cloned())
+                .unwrap_or_default()
+                .split(',')
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty())
+                .collect::<Vec<_>>();
+            deps.insert(svc.clone(), depends_on);
+        }
+        let tiers = Governor::dependency_tiers(&deps)?;
+        info!(event="ORDERED_RESTART_START", node.name=%self.node_name, tiers=tiers.len(), "🔗 Restarting {} services in {} dependency tiers", services.len(), tiers.len());
+
+        let mut results = Vec::with_capacity(services.len());
+        let mut failed_tier = None;
+        for (idx, tier) in tiers.iter().enumerate() {
+            if let Some(failed) = failed_tier {
+                results.extend(tier.iter().map(|svc| BatchUpdateResult {
+                    service: svc.clone(),
+                    result: None,
+                    error: Some(format!("Skipped: dependency tier {} failed", failed)),
+                }));
+                continue;
+            }
+            let outcomes = futures_util::future::join_all(tier.iter().map(|svc| async move {
+                self.restart_service(svc).await?;
+                self.verify_health(svc, self.health_timeout).await
+            }))
+            .await;
+            for (svc, outcome) in tier.iter().zip(outcomes) {
+                if outcome.is_err() {
+                    failed_tier = Some(idx + 1);
+                }
+                results.push(match outcome {
+                    Ok(()) => BatchUpdateResult {
+                        service: svc.clone(),
+                        result: Some(format!("Restarted (tier {})", idx + 1)),
+                        error: None,
+                    },
+                    Err(e) => BatchUpdateResult {
+                        service: svc.clone(),
+                        result: None,
+                        error: Some(e.to_string()),
+                    },
+                });
+            }
+            if let Some(failed) = failed_tier {
+                warn!(event="ORDERED_RESTART_ABORTED", node.name=%self.node_name, tier=failed, "⚠️ Dependency tier {} not ready, skipping remaining tiers.", failed);
+            }
+        }
+        Ok(results)
+    }
+
+    pub async fn pause_service(&self, svc_id: &str) -> Result<()> {
+        info!(event="CONTAINER_PAUSE", node.name=%self.node_name, container.id=%svc_id, "⏸️ Pausing container: {}", svc_id);
+        self.client.pause_container(svc_id).await?;
+        Ok(())
+    }
+
+    pub async fn unpause_service(&self, svc_id: &str) -> Result<()> {
+        info!(event="CONTAINER_UNPAUSE", node.name=%self.node_name, container.id=%svc_id, "⏯️ Unpausing container: {}", svc_id);
+        self.client.unpause_container(svc_id).await?;
         Ok(())
     }
 
+    async fn resolve_stop_timeout(&self, svc_id: &str, fallback: i64) -> i64 {
+        match self
+            .client
+            .inspect_container(svc_id, None::<InspectContainerOptions>)
+            .await
+        {
+            Ok(inspect) => Self::stop_timeout_from_labels(svc_id, &inspect, fallback),
+            Err(_) => fallback,
+        }
+    }
+
+    // `sentiric.stop_timeout` label'ı servis bazında graceful stop süresini belirler.
+    fn stop_timeout_from_labels(
+        svc_id: &str,
+        inspect: &ContainerInspectResponse,
+        fallback: i64,
+    ) -> i64 {
+        let raw = inspect
+            .config
+            .as_ref()
+            .and_then(|c| c.labels.as_ref())
+            .and_then(|l| l.get(STOP_TIMEOUT_LABEL));
+        match raw {
+            None => fallback,
+            Some(v) => match v.trim().parse::<i64>() {
+                Ok(t) if t >= 0 => t,
+                _ => {
+                    warn!(event="STOP_TIMEOUT_LABEL_INVALID", container.id=%svc_id, label.value=%v, fallback, "⚠️ Malformed {} label, using default", STOP_TIMEOUT_LABEL);
+                    fallback
+                }
+            },
+        }
+    }
+
+    // Tek seferlik komut: stdout/stderr toplanır, çıktı 1MB ve süre 30s ile sınırlanır.
+    pub async fn exec_command(&self, svc_id: &str, cmd: Vec<String>) -> Result<ExecOutput> {
+        info!(event="CONTAINER_EXEC", node.name=%self.node_name, container.id=%svc_id, cmd=?cmd, "💻 Executing command in container: {}", svc_id);
+
+        let exec = self
+            .client
+            .create_exec(
+                svc_id,
+                CreateExecOptions {
+                    cmd: Some(cmd),
+                    attach_stdout: Some(true),
+                    attach_stderr: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        let mut result = ExecOutput {
+            exit_code: None,
+            stdout: String::new(),
+            stderr: String::new(),
+            truncated: false,
+            timed_out: false,
+        };
+
+        if let StartExecResults::Attached { mut output, .. } =
+            self.client.start_exec(&exec.id, None).await?
+        {
+            let mut stdout = Vec::new();
+            let mut stderr = Vec::new();
+            let collect = async {
+                while let Some(chunk) = output.next().await {
+                    let room = EXEC_MAX_OUTPUT.saturating_sub(stdout.len() + stderr.len());
+                    let (buf, bytes) = match chunk? {
+                        LogOutput::StdOut { message } => (&mut stdout, message),
+                        LogOutput::StdErr { message } => (&mut stderr, message),
+                        _ => continue,
+                    };
+                    let take = bytes.len().min(room);
+                    buf.extend_from_slice(&bytes[..take]);
+                    if take < bytes.len() {
+                        result.truncated = true;
+                        break;
+                    }
+                }
+                Ok::<(), bollard::errors::Error>(())
+            };
+            match tokio::time::timeout(EXEC_TIMEOUT, collect).await {
+                Ok(res) => res?,
+                Err(_) => {
+                    warn!(event="CONTAINER_EXEC_TIMEOUT", container.id=%svc_id, "⚠️ Exec timed out after {}s", EXEC_TIMEOUT.as_secs());
+                    result.timed_out = true;
+                }
+            }
+            result.stdout = String::from_utf8_lossy(&stdout).to_string();
+            result.stderr = String::from_utf8_lossy(&stderr).to_string();
+        }
+
+        if !result.timed_out {
+            result.exit_code = self.client.inspect_exec(&exec.id).await?.exit_code;
+        }
+        Ok(result)
+    }
+
     // --- INFO & LOGS ---
+    // `since`: Unix epoch (saniye); verilirse backfill bu andan itibaren (yine en fazla 200 satır) başlar.
     pub fn get_log_stream(
         &self,
         svc_id: &str,
+        since: Option<i64>,
     ) -> impl Stream<Item = Result<LogOutput, bollard::errors::Error>> {
         debug!(event="STREAM_LOGS", node.name=%self.node_name, container.id=%svc_id, "📡 Opening live log stream for container: {}", svc_id);
         let options = Some(LogsOptions::<String> {
@@ -77,34 +594,43 @@ impl DockerAdapter {
             stdout: true,
             stderr: true,
             tail: "200".to_string(),
+            since: since.unwrap_or(0),
             ..Default::default()
         });
         self.client.logs(svc_id, options)
     }
 
-    pub async fn get_logs_snapshot(&self, svc_id: &str) -> String {
-        debug!(event="SNAPSHOT_LOGS", node.name=%self.node_name, container.id=%svc_id, "📸 Fetching log snapshot for container: {}", svc_id);
+    pub async fn get_logs_snapshot(&self, svc_id: &str, lines: usize, timestamps: bool) -> String {
+        debug!(event="SNAPSHOT_LOGS", node.name=%self.node_name, container.id=%svc_id, lines, timestamps, "📸 Fetching log snapshot for container: {}", svc_id);
+        let mut stream = self.get_logs_tail(svc_id, &lines.to_string(), timestamps);
+        let mut buffer = String::new();
+
+        while let Some(Ok(bytes)) = stream.next().await {
+            buffer.push_str(&String::from_utf8_lossy(&bytes));
+        }
+        buffer
+    }
+
+    // Takip etmeyen (follow=false) log akışı; `tail` satır sayısı ya da "all".
+    // stdout/stderr Docker'ın gönderdiği sırayla tek bir metin akışında birleştirilir.
+    // `timestamps`: her satırın başına Docker'ın RFC3339Nano zaman damgası eklenir.
+    pub fn get_logs_tail(
+        &self,
+        svc_id: &str,
+        tail: &str,
+        timestamps: bool,
+    ) -> impl Stream<Item = Result<bytes::Bytes, bollard::errors::Error>> {
         let options = Some(LogsOptions::<String> {
             follow: false,
             stdout: true,
             stderr: true,
-            tail: "50".to_string(),
+            timestamps,
+            tail: tail.to_string(),
             ..Default::default()
         });
-
-        let mut stream = self.client.logs(svc_id, options);
-        let mut buffer = String::new();
-
-        while let Some(Ok(output)) = stream.next().await {
-            let bytes: Vec<u8> = match output {
-                LogOutput::StdOut { message } => message.into(),
-                LogOutput::StdErr { message } => message.into(),
-                LogOutput::Console { message } => message.into(),
-                LogOutput::StdIn { message } => message.into(),
-            };
-            buffer.push_str(&String::from_utf8_lossy(&bytes));
-        }
-        buffer
+        self.client
+            .logs(svc_id, options)
+            .map(|res| res.map(LogOutput::into_bytes))
     }
 
     pub async fn get_container_stats(&self, svc_id: &str) -> Result<Stats> {
@@ -121,53 +647,1037 @@ impl DockerAdapter {
         Err(anyhow::anyhow!("No stats received"))
     }
 
-    pub async fn inspect_service(
-        &self,
-        svc_id: &str,
-    ) -> Result<bollard::models::ContainerInspectResponse> {
-        debug!(event="INSPECT_CONTAINER", node.name=%self.node_name, container.id=%svc_id, "🔎 Inspecting container: {}", svc_id);
-        self.client
-            .inspect_container(svc_id, None::<InspectContainerOptions>)
-            .await
-            .map_err(|e| anyhow::anyhow!("Inspect error: {}", e))
+    /// Listeleme özetindeki yayınlanmış portlar; IPv4/IPv6 için ayrı gelen aynı bağlama tekilleştirilir.
+    pub fn published_ports(ports: &[Port]) -> Vec<PortMapping> {
+        let mut mapped: Vec<PortMapping> = ports
+            .iter()
+            .filter_map(|p| {
+                Some(PortMapping {
+                    container_port: p.private_port,
+                    host_port: p.public_port.filter(|hp| *hp > 0)?,
+                    protocol: p
+                        .typ
+                        .map(|t| t.to_string())
+                        .filter(|t| !t.is_empty())
+                        .unwrap_or_else(|| "tcp".into()),
+                })
+            })
+            .collect();
+        mapped.sort_by_key(|m| (m.host_port, m.container_port));
+        mapped.dedup();
+        mapped
+    }
+
+    /// `--gpus` / `deploy.resources.reservations.devices` ile GPU isteyen ya da nvidia runtime'ı
+    /// kullanan container'lar GPU'lu sayılır.
+    pub fn requests_gpu(host: &HostConfig) -> bool {
+        let nvidia_runtime = host.runtime.as_deref() == Some("nvidia");
+        let device_request = host.device_requests.iter().flatten().any(|r| {
+            r.driver.as_deref() == Some("nvidia")
+                || r.capabilities
+                    .iter()
+                    .flatten()
+                    .flatten()
+                    .any(|c| c == "gpu")
+        });
+        nvidia_runtime || device_request
+    }
+
+    pub fn get_stats_stream(
+        &self,
+        svc_id: &str,
+    ) -> impl Stream<Item = Result<Stats, bollard::errors::Error>> {
+        debug!(event="STREAM_STATS", node.name=%self.node_name, container.id=%svc_id, "📈 Opening live stats stream for container: {}", svc_id);
+        self.client.stats(
+            svc_id,
+            Some(StatsOptions {
+                stream: true,
+                one_shot: false,
+            }),
+        )
+    }
+
+    // Docker CLI formülü: (cpu_delta / system_delta) * online_cpus * 100, precpu_stats referans alınır.
+    pub fn cpu_percent(stats: &Stats) -> f64 {
+        let cpu_delta = stats
+            .cpu_stats
+            .cpu_usage
+            .total_usage
+            .saturating_sub(stats.precpu_stats.cpu_usage.total_usage)
+            as f64;
+        let system_delta = stats
+            .cpu_stats
+            .system_cpu_usage
+            .unwrap_or(0)
+            .saturating_sub(stats.precpu_stats.system_cpu_usage.unwrap_or(0))
+            as f64;
+        let online_cpus = stats.cpu_stats.online_cpus.unwrap_or_else(|| {
+            stats
+                .cpu_stats
+                .cpu_usage
+                .percpu_usage
+                .as_ref()
+                .map(|p| p.len() as u64)
+                .unwrap_or(1)
+        }) as f64;
+
+        if system_delta > 0.0 && cpu_delta > 0.0 {
+            (cpu_delta / system_delta) * online_cpus * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    pub async fn inspect_service(&self, svc_id: &str) -> Result<ContainerInspectResponse> {
+        debug!(event="INSPECT_CONTAINER", node.name=%self.node_name, container.id=%svc_id, "🔎 Inspecting container: {}", svc_id);
+        self.client
+            .inspect_container(svc_id, None::<InspectContainerOptions>)
+            .await
+            .map_err(|e| anyhow::anyhow!("Inspect error: {}", e))
+    }
+
+    /// Tüm imajlar, boyutları ve çalışan bir container tarafından kullanılıp kullanılmadıkları
+    /// (büyükten küçüğe sıralı).
+    pub async fn list_images(&self) -> Result<Vec<ImageInfo>> {
+        let running = self
+            .client
+            .list_containers(None::<ListContainersOptions<String>>)
+            .await?;
+        let used: std::collections::HashSet<String> =
+            running.into_iter().filter_map(|c| c.image_id).collect();
+
+        let mut images: Vec<ImageInfo> = self
+            .client
+            .list_images(Some(ListImagesOptions::<String> {
+                all: false,
+                ..Default::default()
+            }))
+            .await?
+            .into_iter()
+            .map(|i| {
+                let repo_tags: Vec<String> = i
+                    .repo_tags
+                    .into_iter()
+                    .filter(|t| t != "<none>:<none>")
+                    .collect();
+                ImageInfo {
+                    in_use: used.contains(&i.id),
+                    dangling: repo_tags.is_empty(),
+                    repo_tags,
+                    size_bytes: i.size.max(0) as u64,
+                    created: chrono::DateTime::from_timestamp(i.created, 0)
+                        .map(|d| d.to_rfc3339())
+                        .unwrap_or_default(),
+                    id: i.id,
+                }
+            })
+            .collect();
+        images.sort_by_key(|i| std::cmp::Reverse(i.size_bytes));
+        Ok(images)
+    }
+
+    // --- THE JANITOR ---
+    pub async fn prune_system(&self) -> Result<String> {
+        Ok(self.prune_with(&PruneParams::default()).await?.summary)
+    }
+
+    // Daemon 1.42 öncesi API'de `prune_volumes` isimli volume'ları da siler.
+    fn prunes_anonymous_volumes_only(&self) -> bool {
+        let mut parts = self.daemon.negotiated_api_version.split('.');
+        let major = parts
+            .next()
+            .and_then(|p| p.parse::<u32>().ok())
+            .unwrap_or(0);
+        let minor = parts
+            .next()
+            .and_then(|p| p.parse::<u32>().ok())
+            .unwrap_or(0);
+        (major, minor) >= (1, 42)
+    }
+
+    /// Durmuş container'ları ve dangling imajları (istenirse kullanılmayan volume'ları) temizler.
+    /// `dry_run` iken aynı filtrelerle adaylar listelenir, hiçbir şey silinmez. `keep_recent`
+    /// Docker'ın `until` filtresine çevrilir (volume prune bu filtreyi desteklemez).
+    pub async fn prune_with(&self, opts: &PruneParams) -> Result<PruneReport> {
+        let Ok(_guard) = self.prune_lock.try_lock() else {
+            return Err(PruneBusy.into());
+        };
+        let (dry_run, keep_recent_hours) = (opts.dry_run, opts.keep_recent);
+        info!(event="SYSTEM_PRUNE_START", node.name=%self.node_name, dry_run, keep_recent_hours, volumes_included=opts.volumes, "🧹 Starting system prune...");
+        if opts.volumes && !dry_run {
+            warn!(event="SYSTEM_PRUNE_VOLUMES", node.name=%self.node_name, "⚠️ Volume pruning requested: unused anonymous volumes will be deleted.");
+        }
+        let mut filters: HashMap<String, Vec<String>> = HashMap::new();
+        if let Some(h) = keep_recent_hours {
+            filters.insert("until".into(), vec![format!("{}h", h)]);
+        }
+
+        let mut report = if dry_run {
+            self.prune_candidates(keep_recent_hours, opts.volumes)
+                .await?
+        } else {
+            let c_prune = self
+                .client
+                .prune_containers(Some(PruneContainersOptions {
+                    filters: filters.clone(),
+                }))
+                .await?;
+            let i_prune = self
+                .client
+                .prune_images(Some(PruneImagesOptions { filters }))
+                .await?;
+            let v_prune = if opts.volumes {
+                Some(
+                    self.client
+                        .prune_volumes(None::<PruneVolumesOptions<String>>)
+                        .await?,
+                )
+            } else {
+                None
+            };
+            let item = |id: String| PrunedItem {
+                name: id.chars().take(12).collect(),
+                id,
+                size_bytes: 0,
+            };
+            PruneReport {
+                dry_run: false,
+                volumes_included: opts.volumes,
+                volumes: v_prune
+                    .as_ref()
+                    .and_then(|v| v.volumes_deleted.clone())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|name| PrunedItem {
+                        id: name.clone(),
+                        name,
+                        size_bytes: 0,
+                    })
+                    .collect(),
+                containers: c_prune
+                    .containers_deleted
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(item)
+                    .collect(),
+                images: i_prune
+                    .images_deleted
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|i| i.deleted.or(i.untagged))
+                    .map(item)
+                    .collect(),
+                reclaimed_bytes: c_prune.space_reclaimed.unwrap_or(0).max(0) as u64
+                    + i_prune.space_reclaimed.unwrap_or(0).max(0) as u64
+                    + v_prune.and_then(|v| v.space_reclaimed).unwrap_or(0).max(0) as u64,
+                summary: String::new(),
+            }
+        };
+
+        let mb = report.reclaimed_bytes as f64 / 1024.0 / 1024.0;
+        let volumes = if opts.volumes {
+            format!(", {} Volumes", report.volumes.len())
+        } else {
+            String::new()
+        };
+        report.summary = if dry_run {
+            format!(
+                "Would delete {} Containers, {} Images{}. Would reclaim up to {:.2} MB",
+                report.containers.len(),
+                report.images.len(),
+                volumes,
+                mb
+            )
+        } else {
+            format!(
+                "Deleted {} Containers, {} Images{}. Reclaimed {:.2} MB",
+                report.containers.len(),
+                report.images.len(),
+                volumes,
+                mb
+            )
+        };
+
+        info!(
+            event = "SYSTEM_PRUNE_DONE",
+            node.name = %self.node_name,
+            dry_run,
+            deleted.containers = report.containers.len(),
+            deleted.images = report.images.len(),
+            volumes_included = opts.volumes,
+            deleted.volumes = report.volumes.len(),
+            reclaimed.mb = mb,
+            "♻️ JANITOR REPORT: {}", report.summary
+        );
+        Ok(report)
+    }
+
+    // Prune'un sileceklerini aynı kurallarla listeler: durmuş container'lar ve dangling imajlar.
+    async fn prune_candidates(
+        &self,
+        keep_recent_hours: Option<u64>,
+        include_volumes: bool,
+    ) -> Result<PruneReport> {
+        let cutoff = keep_recent_hours
+            .map(|h| chrono::Utc::now().timestamp() - (h as i64) * 3600)
+            .unwrap_or(i64::MAX);
+
+        let mut c_filters = HashMap::new();
+        c_filters.insert("status", vec!["exited", "created", "dead"]);
+        let containers: Vec<PrunedItem> = self
+            .client
+            .list_containers(Some(ListContainersOptions {
+                all: true,
+                size: true,
+                filters: c_filters,
+                ..Default::default()
+            }))
+            .await?
+            .into_iter()
+            .filter(|c| c.created.unwrap_or(0) < cutoff)
+            .map(|c| PrunedItem {
+                id: c.id.unwrap_or_default(),
+                name: c
+                    .names
+                    .and_then(|n| n.first().cloned())
+                    .unwrap_or_default()
+                    .trim_start_matches('/')
+                    .to_string(),
+                size_bytes: c.size_rw.unwrap_or(0).max(0) as u64,
+            })
+            .collect();
+
+        let mut i_filters = HashMap::new();
+        i_filters.insert("dangling", vec!["true"]);
+        let images: Vec<PrunedItem> = self
+            .client
+            .list_images(Some(ListImagesOptions {
+                filters: i_filters,
+                ..Default::default()
+            }))
+            .await?
+            .into_iter()
+            .filter(|i| i.created < cutoff)
+            .map(|i| PrunedItem {
+                name: i
+                    .repo_digests
+                    .first()
+                    .cloned()
+                    .unwrap_or_else(|| i.id.chars().take(19).collect()),
+                id: i.id,
+                size_bytes: i.size.max(0) as u64,
+            })
+            .collect();
+
+        // Volume boyutu `docker system df` gerektirdiği için dry-run'da 0 raporlanır. API 1.42+ ile
+        // `prune_volumes` varsayılan olarak sadece anonim volume'ları siler; önizleme de aynı süzgeci
+        // kullanır ki isimli volume'lar silinecekmiş gibi görünmesin.
+        let volumes: Vec<PrunedItem> = if include_volumes {
+            let mut v_filters = HashMap::new();
+            v_filters.insert("dangling", vec!["true"]);
+            if self.prunes_anonymous_volumes_only() {
+                v_filters.insert("label", vec![ANONYMOUS_VOLUME_LABEL]);
+            }
+            self.client
+                .list_volumes(Some(ListVolumesOptions { filters: v_filters }))
+                .await?
+                .volumes
+                .unwrap_or_default()
+                .into_iter()
+                .map(|v| PrunedItem {
+                    id: v.name.clone(),
+                    name: v.name,
+                    size_bytes: v.usage_data.map(|u| u.size.max(0) as u64).unwrap_or(0),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let reclaimed_bytes = containers
+            .iter()
+            .chain(images.iter())
+            .chain(volumes.iter())
+            .map(|i| i.size_bytes)
+            .sum();
+        Ok(PruneReport {
+            dry_run: true,
+            containers,
+            images,
+            volumes_included: include_volumes,
+            volumes,
+            reclaimed_bytes,
+            summary: String::new(),
+        })
+    }
+
+    // [ARCH-COMPLIANCE FIX]: Recreate sırasında Cmd/Entrypoint/Port/Healthcheck gibi alanlar
+    // kaybolmasın diye ContainerConfig bütünüyle taşınır, sadece imaj değiştirilir.
+    fn recreate_config(inspect: &ContainerInspectResponse, image: String) -> Config<String> {
+        let mut config: Config<String> =
+            inspect.config.clone().map(Config::from).unwrap_or_default();
+
+        // Docker varsayılan hostname olarak container ID'sini atar; eski ID yeni container'a taşınmamalı.
+        let old_id = inspect.id.clone().unwrap_or_default();
+        if config
+            .hostname
+            .as_ref()
+            .is_some_and(|h| !h.is_empty() && old_id.starts_with(h.as_str()))
+        {
+            config.hostname = None;
+        }
+
+        config.image = Some(image);
+        config.host_config = Some(Self::preserve_mounts(inspect));
+        config.networking_config = inspect.network_settings.as_ref().map(|n| NetworkingConfig {
+            endpoints_config: n.networks.clone().unwrap_or_default(),
+        });
+        config
+    }
+
+    // [ARCH-COMPLIANCE FIX]: host_config kopyası yalnızca create anında beyan edilen Binds/Mounts/Tmpfs'i
+    // taşır. İmajın VOLUME beyanından doğan anonim volume'lar burada görünmez ve recreate sonrası
+    // boş bir volume ile değiştirilir. Bu yüzden container'ın gerçek mount listesi ile kıyaslanıp
+    // beyan edilmemiş volume'lar açık bir Mount olarak eklenir.
+    fn preserve_mounts(inspect: &ContainerInspectResponse) -> HostConfig {
+        let mut host_config = inspect.host_config.clone().unwrap_or_default();
+        let binds = host_config.binds.clone().unwrap_or_default();
+        let mut mounts = host_config.mounts.clone().unwrap_or_default();
+
+        for mp in inspect.mounts.iter().flatten() {
+            let Some(dest) = mp.destination.clone().filter(|d| !d.is_empty()) else {
+                continue;
+            };
+            let declared = mounts.iter().any(|m| m.target.as_deref() == Some(&dest))
+                || binds
+                    .iter()
+                    .any(|b| b.split(':').nth(1) == Some(dest.as_str()))
+                || host_config
+                    .tmpfs
+                    .as_ref()
+                    .is_some_and(|t| t.contains_key(&dest));
+            if declared {
+                continue;
+            }
+
+            match mp.typ {
+                Some(MountPointTypeEnum::VOLUME) => mounts.push(Mount {
+                    target: Some(dest),
+                    source: mp.name.clone(),
+                    typ: Some(MountTypeEnum::VOLUME),
+                    read_only: mp.rw.map(|rw| !rw),
+                    ..Default::default()
+                }),
+                _ => {
+                    warn!(event="MOUNT_NOT_PRESERVED", mount.destination=%dest, mount.type=?mp.typ, "⚠️ Undeclared mount cannot be carried over on recreate.")
+                }
+            }
+        }
+
+        if !mounts.is_empty() {
+            host_config.mounts = Some(mounts);
+        }
+        host_config
+    }
+
+    // --- UPDATE HOOKS ---
+    // Hook bir HTTP(S) URL ise JSON POST edilir, değilse `sh -c` ile komut olarak çalıştırılır.
+    async fn run_update_hook(&self, svc_name: &str, phase: &str, hook: &str) -> Result<()> {
+        info!(event="UPDATE_HOOK_RUN", service=%svc_name, hook.phase=%phase, "🪝 Running {}-update hook for [{}]", phase, svc_name);
+
+        if hook.starts_with("http://") || hook.starts_with("https://") {
+            let resp = reqwest::Client::builder()
+                .timeout(HOOK_TIMEOUT)
+                .build()?
+                .post(hook)
+                .json(&serde_json::json!({
+                    "service": svc_name,
+                    "phase": phase,
+                    "node": self.node_name,
+                }))
+                .send()
+                .await?;
+            if !resp.status().is_success() {
+                return Err(anyhow::anyhow!("Hook returned HTTP {}", resp.status()));
+            }
+            return Ok(());
+        }
+
+        let output = tokio::time::timeout(
+            HOOK_TIMEOUT,
+            tokio::process::Command::new("sh")
+                .arg("-c")
+                .arg(hook)
+                .env("SERVICE_NAME", svc_name)
+                .env("HOOK_PHASE", phase)
+                .env("NODE_NAME", &self.node_name)
+                .kill_on_drop(true)
+                .output(),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("Hook timed out after {}s", HOOK_TIMEOUT.as_secs()))??;
+
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "Hook exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+
+    // --- CONFIG BACKUP ---
+    async fn backup_container_config(&self, svc_name: &str, inspect: &ContainerInspectResponse) {
+        let Some(root) = &self.backup_dir else {
+            return;
+        };
+        let dir = root.join(svc_name);
+        let file = dir.join(format!(
+            "{}-{}.json",
+            svc_name,
+            chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+        ));
+
+        let result: Result<()> = async {
+            tokio::fs::create_dir_all(&dir).await?;
+            tokio::fs::write(&file, serde_json::to_vec_pretty(inspect)?).await?;
+
+            // Rotasyon: dosya adları zaman damgalı olduğundan alfabetik sıra kronolojiktir.
+            let mut backups = Vec::new();
+            let mut entries = tokio::fs::read_dir(&dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                if entry.path().extension().is_some_and(|e| e == "json") {
+                    backups.push(entry.path());
+                }
+            }
+            backups.sort();
+            let excess = backups.len().saturating_sub(self.backup_keep);
+            for old in backups.into_iter().take(excess) {
+                tokio::fs::remove_file(old).await?;
+            }
+            Ok(())
+        }
+        .await;
+
+        match result {
+            Ok(_) => {
+                info!(event="CONFIG_BACKUP_SAVED", service=%svc_name, path=%file.display(), "💾 Container config backed up before update.")
+            }
+            Err(e) => {
+                warn!(event="CONFIG_BACKUP_FAIL", service=%svc_name, error=%e, "⚠️ Failed to back up container config, continuing update.")
+            }
+        }
+    }
+
+    // --- ENV PATCH (RECREATE) ---
+    pub async fn update_service_env(
+        &self,
+        svc_id: &str,
+        set: &HashMap<String, String>,
+        unset: &[String],
+        redact_patterns: &[String],
+    ) -> Result<EnvPatchResult> {
+        let inspect = self.inspect_service(svc_id).await?;
+        let svc_name = inspect
+            .name
+            .clone()
+            .unwrap_or_else(|| svc_id.to_string())
+            .trim_start_matches('/')
+            .to_string();
+
+        // [ARCH-COMPLIANCE]: İntihar Yasağı. Orchestrator kendi container'ını yeniden yaratamaz.
+        if self.is_self(&inspect, &svc_name) {
+            warn!(event="SELF_UPDATE_PREVENTED", service=%svc_name, "⚠️ Orchestrator cannot recreate itself.");
+            return Err(anyhow::anyhow!("Orchestrator cannot recreate itself"));
+        }
+
+        let current_env = inspect
+            .config
+            .as_ref()
+            .and_then(|c| c.env.clone())
+            .unwrap_or_default();
+        let (new_env, diff) = Governor::apply_env_patch(&current_env, set, unset, redact_patterns);
+
+        if diff.is_empty() {
+            return Ok(EnvPatchResult {
+                container_id: inspect.id.clone().unwrap_or_default(),
+                diff,
+            });
+        }
+
+        let image = inspect.image.clone().unwrap_or_default();
+        let old_config = Self::recreate_config(&inspect, image.clone());
+        let mut new_config = Self::recreate_config(&inspect, image);
+        new_config.env = Some(new_env);
+
+        // Secret sızıntısını önlemek için loglara sadece anahtar isimleri yazılır.
+        info!(
+            event = "ENV_PATCH_START",
+            node.name = %self.node_name,
+            service = %svc_name,
+            env.keys = %diff.keys().join(","),
+            "✏️ Recreating container with patched environment: [{}]", svc_name
+        );
+
+        let t = Self::stop_timeout_from_labels(&svc_name, &inspect, self.stop_timeout);
+        if let Err(e) = self
+            .client
+            .stop_container(&svc_name, Some(StopContainerOptions { t }))
+            .await
+        {
+            warn!(event="CONTAINER_STOP_ERROR", service=%svc_name, error=%e, "⚠️ Error while stopping container (maybe already stopped): {}", e)
+        }
+        let remove_opts = Some(RemoveContainerOptions {
+            force: true,
+            ..Default::default()
+        });
+        self.client.remove_container(&svc_name, remove_opts).await?;
+
+        let create_opts = Some(CreateContainerOptions {
+            name: svc_name.clone(),
+            platform: None,
+        });
+        // Oluşturma veya başlatma başarısızsa servis kapalı kalmasın: güncelleme yolundaki gibi eski
+        // config ile geri kurulur.
+        let recreated = match self.client.create_container(create_opts, new_config).await {
+            Ok(c) => self
+                .client
+                .start_container(&svc_name, None::<StartContainerOptions<String>>)
+                .await
+                .map(|_| c)
+                .map_err(|e| anyhow::anyhow!("Container start failed: {}", e)),
+            Err(e) => Err(anyhow::anyhow!("Container create failed: {}", e)),
+        };
+        let created = match recreated {
+            Ok(c) => c,
+            Err(e) => {
+                error!(event="ENV_PATCH_RECREATE_ERROR", service=%svc_name, error=%e, "❌ Failed to recreate patched container, restoring previous config.");
+                self.rollback_container(&svc_name, old_config, None).await;
+                return Err(e);
+            }
+        };
+
+        info!(event="ENV_PATCH_DONE", node.name=%self.node_name, service=%svc_name, container.id=%created.id, "✅ [{}] recreated with patched environment.", svc_name);
+        Ok(EnvPatchResult {
+            container_id: created.id,
+            diff,
+        })
+    }
+
+    /// Sıfırdan servis kurulumu: imaj çekilir, container oluşturulup başlatılır. Başlatma başarısız
+    /// olursa yarım kalan container silinir. Spec'in doğrulanması ve isim çakışması çağıranın işidir.
+    pub async fn create_service(&self, spec: &ServiceSpec) -> Result<String> {
+        info!(event="SERVICE_CREATE_START", node.name=%self.node_name, service=%spec.name, image=%spec.image, "🆕 Deploying new service [{}] from {}", spec.name, spec.image);
+
+        let pulled = self.pull_with_retries(&spec.name, &spec.image, None).await;
+        self.broadcast_progress(&spec.name, None);
+        // Bağlam eklenir ama PullError korunur; handler downcast ile durum kodunu seçer.
+        if let Err(e) = pulled {
+            let context = format!("Image pull failed for '{}': {}", spec.image, e);
+            return Err(e.context(context));
+        }
+
+        let mut exposed_ports = HashMap::new();
+        let mut port_bindings = HashMap::new();
+        for p in &spec.ports {
+            let proto = if p.protocol.is_empty() {
+                "tcp"
+            } else {
+                p.protocol.as_str()
+            };
+            let key = format!("{}/{}", p.container_port, proto);
+            exposed_ports.insert(key.clone(), HashMap::new());
+            port_bindings
+                .entry(key)
+                .or_insert_with(|| Some(Vec::new()))
+                .get_or_insert_with(Vec::new)
+                .push(PortBinding {
+                    host_ip: None,
+                    host_port: Some(p.host_port.to_string()),
+                });
+        }
+
+        let mut env: Vec<String> = spec
+            .env
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect();
+        env.sort();
+        let config = Config {
+            image: Some(spec.image.clone()),
+            env: Some(env),
+            exposed_ports: Some(exposed_ports),
+            host_config: Some(HostConfig {
+                binds: Some(spec.volumes.clone()),
+                port_bindings: Some(port_bindings),
+                network_mode: spec.network.clone().filter(|n| !n.is_empty()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let created = self
+            .client
+            .create_container(
+                Some(CreateContainerOptions {
+                    name: spec.name.clone(),
+                    platform: Some(self.daemon.platform.clone()),
+                }),
+                config,
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Container create failed: {}", e))?;
+
+        if let Err(e) = self
+            .client
+            .start_container(&spec.name, None::<StartContainerOptions<String>>)
+            .await
+        {
+            error!(event="SERVICE_CREATE_START_FAIL", service=%spec.name, error=%e, "❌ New container failed to start, removing it.");
+            let _ = self
+                .client
+                .remove_container(
+                    &spec.name,
+                    Some(RemoveContainerOptions {
+                        force: true,
+                        ..Default::default()
+                    }),
+                )
+                .await;
+            return Err(anyhow::anyhow!("Container start failed: {}", e));
+        }
+
+        info!(event="SERVICE_CREATE_DONE", node.name=%self.node_name, service=%spec.name, container.id=%created.id, "✅ [{}] deployed.", spec.name);
+        self.emit_lifecycle(&spec.name, "created", Some(spec.image.clone()));
+        Ok(created.id)
+    }
+
+    // --- UPDATE ENGINE & SRE AUTO-ROLLBACK ---
+    // Tek bir pull denemesi; akışın bildirdiği manifest digest'i (varsa) döner.
+    async fn pull_image(
+        &self,
+        svc_name: &str,
+        image_name: &str,
+        progress: Option<&UpdateProgressTx>,
+    ) -> std::result::Result<Option<String>, bollard::errors::Error> {
+        let mut stream = self.client.create_image(
+            Some(CreateImageOptions {
+                from_image: image_name.to_string(),
+                platform: self.daemon.platform.clone(),
+                ..Default::default()
+            }),
+            None,
+            self.registry_auth.for_image(image_name),
+        );
+
+        let mut pulled_digest: Option<String> = None;
+        while let Some(res) = stream.next().await {
+            match res {
+                Ok(info) => {
+                    let status = info.status.unwrap_or_default();
+                    Self::emit_progress(
+                        progress,
+                        serde_json::json!({
+                            "type": "pull",
+                            "status": status,
+                            "id": info.id,
+                            "progress": info.progress,
+                            "current": info.progress_detail.as_ref().and_then(|d| d.current),
+                            "total": info.progress_detail.as_ref().and_then(|d| d.total),
+                        }),
+                    );
+                    if let Some(d) = status.strip_prefix("Digest: ") {
+                        pulled_digest = Some(d.trim().to_string());
+                    }
+                    let progress = if let Some(det) = info.progress_detail {
+                        if let (Some(curr), Some(tot)) = (det.current, det.total) {
+                            if tot > 0 {
+                                format!(
+                                    "{} ({}%)",
+                                    status,
+                                    (curr as f64 / tot as f64 * 100.0) as u32
+                                )
+                            } else {
+                                status.clone()
+                            }
+                        } else {
+                            status.clone()
+                        }
+                    } else {
+                        status.clone()
+                    }
+                    .replace("\n", "");
+
+                    self.broadcast_progress(svc_name, Some(progress));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(pulled_digest)
+    }
+
+    // Geçici ağ/registry hataları backoff ile yeniden denenir; 401/404 gibi kalıcı hatalarda hemen vazgeçilir.
+    async fn pull_with_retries(
+        &self,
+        svc_name: &str,
+        image_name: &str,
+        progress: Option<&UpdateProgressTx>,
+    ) -> Result<Option<String>> {
+        let mut attempt = 1;
+        let mut backoff = PULL_RETRY_BASE_DELAY;
+        loop {
+            debug!(event="IMAGE_PULL_ATTEMPT", service=%svc_name, image=%image_name, attempt, max_attempts=self.pull_max_attempts, "Pulling image for [{}] (attempt {}/{})", svc_name, attempt, self.pull_max_attempts);
+            match self.pull_image(svc_name, image_name, progress).await {
+                Ok(digest) => return Ok(digest),
+                Err(e) if attempt < self.pull_max_attempts && Self::is_retryable_pull_error(&e) => {
+                    debug!(event="IMAGE_PULL_RETRY", service=%svc_name, attempt, error=%e, backoff_secs=backoff.as_secs(), "Transient pull error for [{}], retrying", svc_name);
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                Err(e) => return Err(Self::classify_pull_error(image_name, &e)),
+            }
+        }
+    }
+
+    // RepoDigests girdileri `repo@sha256:...` biçimindedir; sadece digest kısmı kıyaslanır.
+    fn digest_matches(repo_digests: &[String], digest: &str) -> bool {
+        repo_digests
+            .iter()
+            .any(|d| d.rsplit('@').next() == Some(digest))
+    }
+
+    // Timeout, 5xx, 429 ve bağlantı kopmaları geçicidir; kimlik doğrulama ve bulunamayan imaj/manifest kalıcıdır.
+    fn is_retryable_pull_error(e: &bollard::errors::Error) -> bool {
+        if let bollard::errors::Error::DockerResponseServerError { status_code, .. } = e {
+            return *status_code >= 500 || *status_code == 429;
+        }
+        let msg = e.to_string().to_lowercase();
+        let permanent = [
+            "unauthorized",
+            "denied",
+            "authentication required",
+            "manifest unknown",
+            "not found",
+            "does not exist",
+            "invalid reference",
+        ];
+        !permanent.iter().any(|p| msg.contains(p))
+    }
+
+    // Token yenileme gerekip gerekmediği anlaşılsın diye 401/403 hataları ağ hatalarından ayrılır;
+    // bulunamayan imaj da ayrı sınıflanır ki API istemci hatasını (4xx) registry hatasından (502) ayırsın.
+    fn classify_pull_error(image: &str, e: &bollard::errors::Error) -> anyhow::Error {
+        let msg = e.to_string().to_lowercase();
+        let kind = match e {
+            bollard::errors::Error::DockerResponseServerError { status_code, .. }
+                if *status_code == 401 || *status_code == 403 =>
+            {
+                PullErrorKind::Auth
+            }
+            bollard::errors::Error::DockerResponseServerError { status_code, .. }
+                if *status_code == 404 =>
+            {
+                PullErrorKind::NotFound
+            }
+            bollard::errors::Error::DockerResponseServerError { .. } => PullErrorKind::Registry,
+            _ if ["unauthorized", "denied", "authentication required"]
+                .iter()
+                .any(|p| msg.contains(p)) =>
+            {
+                PullErrorKind::Auth
+            }
+            _ if [
+                "manifest unknown",
+                "not found",
+                "does not exist",
+                "invalid reference",
+            ]
+            .iter()
+            .any(|p| msg.contains(p)) =>
+            {
+                PullErrorKind::NotFound
+            }
+            _ => PullErrorKind::Registry,
+        };
+
+        let message = match kind {
+            PullErrorKind::Auth => {
+                error!(event="REGISTRY_AUTH_FAIL", image=%image, error=%e, "🔐 Registry authentication failed for [{}]. Check/rotate registry credentials.", image);
+                format!("Registry authentication failed: {}", e)
+            }
+            PullErrorKind::NotFound => {
+                error!(event="IMAGE_NOT_FOUND", image=%image, error=%e, "❌ Image not found: {}", image);
+                format!("Image not found: {}", e)
+            }
+            PullErrorKind::Registry => {
+                error!(event="IMAGE_PULL_FAIL", image=%image, error=%e, "❌ Pull Error (network/registry): {}", e);
+                format!("Registry error: {}", e)
+            }
+        };
+        anyhow::Error::new(PullError { kind, message })
+    }
+
+    // Yeni container saniyede bir yoklanır: healthcheck varsa `State.Health.Status`,
+    // yoksa `State.Running` esas alınır. Süre dolduğunda sağlıklı değilse hata döner.
+    async fn verify_health(&self, svc_name: &str, timeout: Duration) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let verdict = match self
+                .client
+                .inspect_container(svc_name, None::<InspectContainerOptions>)
+                .await
+            {
+                Ok(inspect) => {
+                    let state = inspect.state.unwrap_or_default();
+                    let running = state.running == Some(true);
+                    match state.health.and_then(|h| h.status) {
+                        Some(HealthStatusEnum::HEALTHY) if running => Ok(()),
+                        Some(HealthStatusEnum::NONE) | Some(HealthStatusEnum::EMPTY) | None
+                            if running =>
+                        {
+                            Ok(())
+                        }
+                        Some(status) if running => {
+                            Err(anyhow::anyhow!("container health is '{}'", status))
+                        }
+                        _ => Err(anyhow::anyhow!(
+                            "container is not running (status: {})",
+                            state
+                                .status
+                                .map(|s| s.to_string())
+                                .unwrap_or_else(|| "unknown".into())
+                        )),
+                    }
+                }
+                Err(e) => Err(anyhow::anyhow!("inspect failed: {}", e)),
+            };
+
+            if verdict.is_ok() || tokio::time::Instant::now() >= deadline {
+                return verdict;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    }
+
+    // Başarısız güncellemede container'ı eski Image ID ile yeniden kurar; başarıyı döner.
+    async fn rollback_container(
+        &self,
+        svc_name: &str,
+        old_config: Config<String>,
+        progress: Option<&UpdateProgressTx>,
+    ) -> bool {
+        Self::emit_progress(
+            progress,
+            serde_json::json!({ "type": "lifecycle", "phase": "rollback" }),
+        );
+        self.broadcast_progress(svc_name, Some("ROLLBACK IN PROGRESS🚨".into()));
+
+        let _ = self
+            .client
+            .remove_container(
+                svc_name,
+                Some(RemoveContainerOptions {
+                    force: true,
+                    ..Default::default()
+                }),
+            )
+            .await;
+
+        let restored = match self
+            .client
+            .create_container(
+                Some(CreateContainerOptions {
+                    name: svc_name.to_string(),
+                    platform: None,
+                }),
+                old_config,
+            )
+            .await
+        {
+            Ok(_) => self
+                .client
+                .start_container(svc_name, None::<StartContainerOptions<String>>)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+
+        match &restored {
+            Ok(_) => {
+                info!(event="AUTO_ROLLBACK_SUCCESS", service=%svc_name, "♻️ Service rolled back to previous stable image.")
+            }
+            Err(e) => {
+                error!(event="AUTO_ROLLBACK_FAILED", service=%svc_name, error=%e, "❌ Fatal Error: Failed to rollback service.")
+            }
+        }
+
+        self.broadcast_progress(svc_name, None);
+        restored.is_ok()
+    }
+
+    fn emit_progress(progress: Option<&UpdateProgressTx>, event: serde_json::Value) {
+        if let Some(p) = progress {
+            let _ = p.send(event);
+        }
     }
 
-    // --- THE JANITOR ---
-    pub async fn prune_system(&self) -> Result<String> {
-        info!(event="SYSTEM_PRUNE_START", node.name=%self.node_name, "🧹 Starting system prune...");
-        let c_prune = self
+    /// Bakım penceresi dışındaki auto-pilot turu: container'a dokunmadan yeni imaj olup olmadığını söyler.
+    /// Registry HEAD desteklenmiyorsa imaj önceden çekilir; pencere açıldığında sadece recreate kalır.
+    pub async fn update_available(&self, svc_name: &str) -> Result<bool> {
+        let inspect = self.inspect_service(svc_name).await?;
+        let current_image_id = inspect.image.clone().unwrap_or_default();
+        let image_name = inspect
+            .config
+            .as_ref()
+            .and_then(|c| c.image.clone())
+            .ok_or_else(|| anyhow::anyhow!("No image defined"))?;
+        let current_digests = self
             .client
-            .prune_containers(None::<PruneContainersOptions<String>>)
-            .await?;
-        let c_deleted = c_prune.containers_deleted.unwrap_or_default().len();
+            .inspect_image(&current_image_id)
+            .await
+            .ok()
+            .and_then(|i| i.repo_digests)
+            .unwrap_or_default();
 
-        let i_prune = self
-            .client
-            .prune_images(None::<PruneImagesOptions<String>>)
-            .await?;
-        let i_deleted = i_prune.images_deleted.unwrap_or_default().len();
-        let space = i_prune.space_reclaimed.unwrap_or(0);
-
-        let msg = format!(
-            "Deleted {} Containers, {} Images. Reclaimed {:.2} MB",
-            c_deleted,
-            i_deleted,
-            (space as f64 / 1024.0 / 1024.0)
-        );
+        if let Ok(remote) = self.registry_auth.remote_digest(&image_name).await {
+            return Ok(!Self::digest_matches(&current_digests, &remote));
+        }
 
-        info!(
-            event = "SYSTEM_PRUNE_DONE",
-            node.name = %self.node_name,
-            deleted.containers = c_deleted,
-            deleted.images = i_deleted,
-            reclaimed.mb = (space as f64 / 1024.0 / 1024.0),
-            "♻️ JANITOR REPORT: {}", msg
-        );
-        Ok(msg)
+        let pulled = self.pull_image(svc_name, &image_name, None).await;
+        self.broadcast_progress(svc_name, None);
+        let pulled_digest = pulled.map_err(|e| Self::classify_pull_error(&image_name, &e))?;
+        Ok(match pulled_digest {
+            Some(digest) if !current_digests.is_empty() => {
+                !Self::digest_matches(&current_digests, &digest)
+            }
+            _ => {
+                let new_image = self.client.inspect_image(&image_name).await?;
+                new_image.id.unwrap_or_default() != current_image_id
+            }
+        })
     }
 
-    // --- UPDATE ENGINE & SRE AUTO-ROLLBACK ---
-    pub async fn check_and_update_service(&self, svc_name: &str) -> Result<bool> {
+    pub async fn check_and_update_service(
+        &self,
+        svc_name: &str,
+        progress: Option<&UpdateProgressTx>,
+    ) -> Result<bool> {
+        let result = self.check_and_update_inner(svc_name, progress).await;
+        match &result {
+            Ok(true) => self.emit_lifecycle(svc_name, "update_applied", None),
+            Ok(false) => {}
+            Err(e) => self.emit_lifecycle(svc_name, "update_failed", Some(e.to_string())),
+        }
+        result
+    }
+
+    async fn check_and_update_inner(
+        &self,
+        svc_name: &str,
+        progress: Option<&UpdateProgressTx>,
+    ) -> Result<bool> {
         debug!(
             event="CHECK_UPDATES",
             node.name=%self.node_name,
@@ -188,115 +1698,160 @@ impl DockerAdapter {
             .and_then(|c| c.image.clone())
             .ok_or_else(|| anyhow::anyhow!("No image defined"))?;
 
-        let is_self = svc_name.contains("orchestrator");
+        let is_self = self.is_self(&inspect, svc_name);
 
         // [ARCH-COMPLIANCE FIX]: Eski konfigürasyonu Rollback için sakla
-        let old_config = Config {
-            image: Some(current_image_id.clone()), // Rollback'te eski Image ID kullanılır
-            env: inspect.config.as_ref().and_then(|c| c.env.clone()),
-            labels: inspect.config.as_ref().and_then(|c| c.labels.clone()),
-            host_config: inspect.host_config.clone(),
-            networking_config: inspect.network_settings.as_ref().map(|n| {
-                bollard::container::NetworkingConfig {
-                    endpoints_config: n.networks.clone().unwrap_or_default(),
-                }
-            }),
-            ..Default::default()
-        };
-
-        // 1. PULL (Yeni imajı çek ve Progress bildir)
-        let mut stream = docker.create_image(
-            Some(CreateImageOptions {
-                from_image: image_name.clone(),
-                ..Default::default()
-            }),
-            None,
-            None,
-        );
+        // Rollback'te eski Image ID kullanılır
+        let old_config = Self::recreate_config(&inspect, current_image_id.clone());
 
-        while let Some(res) = stream.next().await {
-            match res {
-                Ok(info) => {
-                    let status = info.status.unwrap_or_default();
-                    let progress = if let Some(det) = info.progress_detail {
-                        if let (Some(curr), Some(tot)) = (det.current, det.total) {
-                            if tot > 0 {
-                                format!(
-                                    "{} ({}%)",
-                                    status,
-                                    (curr as f64 / tot as f64 * 100.0) as u32
-                                )
-                            } else {
-                                status.clone()
-                            }
-                        } else {
-                            status.clone()
-                        }
-                    } else {
-                        status.clone()
-                    }
-                    .replace("\n", "");
+        let current_digests = docker
+            .inspect_image(&current_image_id)
+            .await
+            .ok()
+            .and_then(|i| i.repo_digests)
+            .unwrap_or_default();
 
-                    let _ = self.tx.send(
-                        serde_json::json!({
-                            "type": "update_progress",
-                            "data": { "service": svc_name, "progress": progress }
-                        })
-                        .to_string(),
-                    );
-                }
-                Err(e) => {
-                    error!(event="IMAGE_PULL_FAIL", error=%e, "❌ Pull Error: {}", e);
-                    let _ = self.tx.send(
-                        serde_json::json!({ "type": "update_progress", "data": { "service": svc_name, "progress": null } }).to_string(),
-                    );
-                    return Err(anyhow::anyhow!("Registry error"));
-                }
+        // 0. DIGEST WATCH: Registry'deki manifest digest'i zaten çalışan imajınsa pull hiç yapılmaz.
+        // HEAD desteklenmiyor/erişilemiyorsa sessizce aşağıdaki pull-and-compare akışına düşülür.
+        match self.registry_auth.remote_digest(&image_name).await {
+            Ok(remote) if Self::digest_matches(&current_digests, &remote) => {
+                debug!(event="IMAGE_DIGEST_UNCHANGED", service=%svc_name, digest=%remote, "Registry digest unchanged for [{}], skipping pull", svc_name);
+                self.broadcast_progress(svc_name, None);
+                return Ok(false);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                debug!(event="REGISTRY_HEAD_UNSUPPORTED", service=%svc_name, image=%image_name, error=%e, "Manifest HEAD unavailable, falling back to pull-and-compare");
             }
         }
 
+        // 1. PULL (Yeni imajı çek ve Progress bildir)
+        let pulled_digest = match self
+            .pull_with_retries(svc_name, &image_name, progress)
+            .await
+        {
+            Ok(digest) => digest,
+            Err(e) => {
+                self.broadcast_progress(svc_name, None);
+                return Err(e);
+            }
+        };
+
+        Self::emit_progress(
+            progress,
+            serde_json::json!({ "type": "lifecycle", "phase": "pull_complete" }),
+        );
+
         // 2. COMPARE (Versiyon karşılaştır)
+        // Multi-arch manifest ve yeniden tag'lenen imajlarda Image ID yanıltıcı olabilir;
+        // mümkünse pull akışının bildirdiği digest, mevcut imajın RepoDigests'i ile kıyaslanır.
         let new_image_inspect = docker.inspect_image(&image_name).await?;
         let new_image_id = new_image_inspect.id.clone().unwrap_or_default();
 
-        if current_image_id == new_image_id {
-            let _ = self.tx.send(
-                serde_json::json!({ "type": "update_progress", "data": { "service": svc_name, "progress": null } }).to_string(),
-            );
+        debug!(
+            event="IMAGE_DIGEST_COMPARE",
+            service=%svc_name,
+            current_digests=?current_digests,
+            pulled_digest=?pulled_digest,
+            "Comparing image digests for [{}]", svc_name
+        );
+
+        let up_to_date = match &pulled_digest {
+            Some(digest) if !current_digests.is_empty() => {
+                Self::digest_matches(&current_digests, digest)
+            }
+            _ => current_image_id == new_image_id,
+        };
+
+        if up_to_date {
+            self.broadcast_progress(svc_name, None);
             return Ok(false);
         }
 
         info!(event="AUTO_PILOT_UPDATE_FOUND", service=%svc_name, "🚀 UPDATE FOUND for service: [{}]", svc_name);
+        self.emit_lifecycle(svc_name, "update_found", Some(image_name.clone()));
 
         if is_self {
-            warn!(
-                event = "SELF_UPDATE_PREVENTED",
-                "⚠️ Orchestrator cannot restart itself."
-            );
-            let _ = self.tx.send(
-                serde_json::json!({ "type": "update_progress", "data": { "service": svc_name, "progress": null } }).to_string(),
-            );
+            // ALLOW_SELF_UPDATE kapalıysa güncelleme uygulanmış sayılmaz; ayrı bir olay yayınlanır.
+            if !self.allow_self_update {
+                warn!(
+                    event = "SELF_UPDATE_PREVENTED",
+                    "⚠️ Orchestrator cannot restart itself."
+                );
+                self.broadcast_progress(svc_name, None);
+                self.emit_lifecycle(
+                    svc_name,
+                    "update_blocked",
+                    Some(SELF_UPDATE_DISABLED.to_string()),
+                );
+                return Ok(false);
+            }
+            let handoff = self.spawn_self_update_helper(&inspect, &image_name).await;
+            self.broadcast_progress(svc_name, None);
+            handoff?;
             return Ok(true);
         }
 
-        let new_config = Config {
-            image: Some(image_name.clone()),
-            env: inspect.config.as_ref().and_then(|c| c.env.clone()),
-            labels: inspect.config.as_ref().and_then(|c| c.labels.clone()),
-            host_config: inspect.host_config.clone(),
-            networking_config: inspect.network_settings.as_ref().map(|n| {
-                bollard::container::NetworkingConfig {
-                    endpoints_config: n.networks.clone().unwrap_or_default(),
+        let new_config = Self::recreate_config(&inspect, image_name.clone());
+
+        // Yıkıcı stop/remove/create akışından önce elle kurtarma için konfigürasyon yedeği alınır.
+        self.backup_container_config(svc_name, &inspect).await;
+
+        // Pre-hook başarısız olursa container'a dokunulmadan güncelleme iptal edilir.
+        let labels = inspect
+            .config
+            .as_ref()
+            .and_then(|c| c.labels.clone())
+            .unwrap_or_default();
+        let prehook = labels
+            .get(PREHOOK_LABEL)
+            .cloned()
+            .or_else(|| self.prehook.clone());
+        let posthook = labels
+            .get(POSTHOOK_LABEL)
+            .cloned()
+            .or_else(|| self.posthook.clone());
+
+        if let Some(hook) = &prehook {
+            self.broadcast_progress(svc_name, Some("PRE-HOOK...".into()));
+            if let Err(e) = self.run_update_hook(svc_name, "pre", hook).await {
+                error!(event="UPDATE_PREHOOK_FAIL", service=%svc_name, error=%e, "❌ Pre-update hook failed, aborting update.");
+                self.broadcast_progress(svc_name, None);
+                return Err(anyhow::anyhow!("Pre-update hook failed: {}", e));
+            }
+        }
+
+        if labels
+            .get(UPDATE_STRATEGY_LABEL)
+            .is_some_and(|s| s.trim().eq_ignore_ascii_case("bluegreen"))
+        {
+            // Aynı host portu iki container'a bağlanamaz; bu durumda klasik recreate'e düşülür.
+            if Self::publishes_host_ports(&inspect) {
+                warn!(event="BLUEGREEN_UNSUPPORTED", service=%svc_name, "⚠️ [{}] publishes host ports, falling back to recreate strategy.", svc_name);
+            } else {
+                if let Err(e) = self
+                    .bluegreen_update(svc_name, &inspect, new_config, old_config, progress)
+                    .await
+                {
+                    self.broadcast_progress(svc_name, None);
+                    return Err(e);
                 }
-            }),
-            ..Default::default()
-        };
+                self.finish_update(svc_name, posthook.as_deref(), progress)
+                    .await;
+                return Ok(true);
+            }
+        }
 
         // 3. ZERO-DOWNTIME GRACEFUL SHUTDOWN (Dökülme/Drain)
-        info!(event="CONTAINER_DRAINING", service=%svc_name, "🛑 Sending SIGTERM for graceful drain: [{}]", svc_name);
-        let _ = self.tx.send(serde_json::json!({ "type": "update_progress", "data": { "service": svc_name, "progress": "DRAINING (60s)" } }).to_string());
+        Self::emit_progress(
+            progress,
+            serde_json::json!({ "type": "lifecycle", "phase": "stop" }),
+        );
+        let drain_secs = Self::stop_timeout_from_labels(svc_name, &inspect, UPDATE_DRAIN_TIMEOUT);
+        info!(event="CONTAINER_DRAINING", service=%svc_name, stop_timeout=drain_secs, "🛑 Sending SIGTERM for graceful drain: [{}]", svc_name);
+        self.broadcast_progress(svc_name, Some(format!("DRAINING ({}s)", drain_secs)));
 
-        let stop_opts = Some(StopContainerOptions { t: 60 });
+        let stop_opts = Some(StopContainerOptions { t: drain_secs });
         match docker.stop_container(svc_name, stop_opts).await {
             Ok(_) => {
                 info!(event="CONTAINER_STOP_SIGNALED", service=%svc_name, "🛑 Stop signal sent.")
@@ -315,7 +1870,7 @@ impl DockerAdapter {
             _ = wait_stream.next() => {
                 debug!(event="CONTAINER_HALTED", service=%svc_name, "Container execution halted completely.");
             }
-            _ = tokio::time::sleep(std::time::Duration::from_secs(65)) => {
+            _ = tokio::time::sleep(Duration::from_secs(drain_secs as u64 + 5)) => {
                 warn!(event="CONTAINER_WAIT_TIMEOUT", service=%svc_name, "Timeout waiting for container to stop. Forcing removal.");
             }
         }
@@ -324,6 +1879,10 @@ impl DockerAdapter {
             force: true,
             ..Default::default()
         });
+        Self::emit_progress(
+            progress,
+            serde_json::json!({ "type": "lifecycle", "phase": "remove" }),
+        );
         match docker.remove_container(svc_name, remove_opts).await {
             Ok(_) => {
                 info!(event="CONTAINER_REMOVED", service=%svc_name, "💀 Old container completely removed.")
@@ -333,81 +1892,364 @@ impl DockerAdapter {
             }
         }
 
+        Self::emit_progress(
+            progress,
+            serde_json::json!({ "type": "lifecycle", "phase": "create" }),
+        );
         info!(event="CONTAINER_RECREATING", service=%svc_name, "✨ Creating updated container: [{}]", svc_name);
-        let _ = self.tx.send(serde_json::json!({ "type": "update_progress", "data": { "service": svc_name, "progress": "STARTING..." } }).to_string());
+        self.broadcast_progress(svc_name, Some("STARTING...".into()));
 
         if let Err(e) = docker
             .create_container(
                 Some(CreateContainerOptions {
                     name: svc_name.to_string(),
-                    platform: None,
+                    platform: Some(self.daemon.platform.clone()),
                 }),
                 new_config,
             )
             .await
         {
             error!(event="CONTAINER_CREATE_ERROR", service=%svc_name, error=%e, "❌ Failed to create container: {}", e);
-            let _ = self.tx.send(serde_json::json!({ "type": "update_progress", "data": { "service": svc_name, "progress": null } }).to_string());
-            return Err(anyhow::anyhow!("Container create failed"));
+            self.rollback_container(svc_name, old_config, progress)
+                .await;
+            return Err(anyhow::anyhow!(
+                "Container create failed ({}), rolled back to previous image",
+                e
+            ));
         }
 
+        Self::emit_progress(
+            progress,
+            serde_json::json!({ "type": "lifecycle", "phase": "start" }),
+        );
         if let Err(e) = docker
             .start_container(svc_name, None::<StartContainerOptions<String>>)
             .await
         {
             error!(event="CONTAINER_START_ERROR", service=%svc_name, error=%e, "❌ Failed to start container: {}", e);
-            let _ = self.tx.send(serde_json::json!({ "type": "update_progress", "data": { "service": svc_name, "progress": null } }).to_string());
-            return Err(anyhow::anyhow!("Container start failed"));
+            self.rollback_container(svc_name, old_config, progress)
+                .await;
+            return Err(anyhow::anyhow!(
+                "Container start failed ({}), rolled back to previous image",
+                e
+            ));
         }
 
         // [ARCH-COMPLIANCE FIX]: SRE Auto-Rollback Mekanizması
-        let _ = self.tx.send(serde_json::json!({ "type": "update_progress", "data": { "service": svc_name, "progress": "HEALTH CHECK (5s)..." } }).to_string());
-        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        Self::emit_progress(
+            progress,
+            serde_json::json!({ "type": "lifecycle", "phase": "health_check" }),
+        );
+        self.broadcast_progress(
+            svc_name,
+            Some(format!(
+                "HEALTH CHECK ({}s)...",
+                self.health_timeout.as_secs()
+            )),
+        );
 
-        if let Ok(verify_inspect) = docker
-            .inspect_container(svc_name, None::<InspectContainerOptions>)
+        if let Err(e) = self.verify_health(svc_name, self.health_timeout).await {
+            error!(event="AUTO_ROLLBACK_TRIGGERED", service=%svc_name, error=%e, "🚨 New version failed health verification! Initiating Auto-Rollback to previous stable state.");
+            self.rollback_container(svc_name, old_config, progress)
+                .await;
+            return Err(anyhow::anyhow!(
+                "Health verification failed ({}), rolled back to previous image",
+                e
+            ));
+        }
+
+        self.finish_update(svc_name, posthook.as_deref(), progress)
+            .await;
+        Ok(true)
+    }
+
+    async fn finish_update(
+        &self,
+        svc_name: &str,
+        posthook: Option<&str>,
+        progress: Option<&UpdateProgressTx>,
+    ) {
+        if let Some(hook) = posthook {
+            self.broadcast_progress(svc_name, Some("POST-HOOK...".into()));
+            if let Err(e) = self.run_update_hook(svc_name, "post", hook).await {
+                warn!(event="UPDATE_POSTHOOK_FAIL", service=%svc_name, error=%e, "⚠️ Post-update hook failed. New version stays in place.");
+            }
+        }
+
+        Self::emit_progress(
+            progress,
+            serde_json::json!({ "type": "lifecycle", "phase": "done" }),
+        );
+        info!(event="AUTO_PILOT_SUCCESS", service=%svc_name, "✅ [{}] updated and verified successfully.", svc_name);
+        self.broadcast_progress(svc_name, None);
+    }
+
+    fn publishes_host_ports(inspect: &ContainerInspectResponse) -> bool {
+        inspect
+            .host_config
+            .as_ref()
+            .and_then(|h| h.port_bindings.as_ref())
+            .is_some_and(|bindings| {
+                bindings.values().flatten().flatten().any(|b| {
+                    b.host_port
+                        .as_deref()
+                        .is_some_and(|p| !p.is_empty() && p != "0")
+                })
+            })
+    }
+
+    // Blue-green: yeni imaj `<isim>-next` olarak alias'sız başlatılır, sağlığı doğrulanınca ağ
+    // alias'ları ona taşınır, eski container drain edilip silinir ve yenisi asıl isme alınır.
+    // Sağlık doğrulaması başarısız olursa aday silinir; eski container hiç durdurulmamıştır.
+    async fn bluegreen_update(
+        &self,
+        svc_name: &str,
+        inspect: &ContainerInspectResponse,
+        mut config: Config<String>,
+        old_config: Config<String>,
+        progress: Option<&UpdateProgressTx>,
+    ) -> Result<()> {
+        let docker = &self.client;
+        let candidate = format!("{}-next", svc_name);
+        let networks = inspect
+            .network_settings
+            .as_ref()
+            .and_then(|n| n.networks.clone())
+            .unwrap_or_default();
+        let force_remove = || {
+            Some(RemoveContainerOptions {
+                force: true,
+                ..Default::default()
+            })
+        };
+
+        // Önceki yarım kalmış denemeden kalan aday temizlenir.
+        let _ = docker.remove_container(&candidate, force_remove()).await;
+
+        // Aday trafik almasın: endpoint'ler alias ve sabit adres olmadan bağlanır.
+        config.hostname = None;
+        config.networking_config = Some(NetworkingConfig {
+            endpoints_config: networks
+                .iter()
+                .map(|(net, ep)| {
+                    (
+                        net.clone(),
+                        EndpointSettings {
+                            network_id: ep.network_id.clone(),
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect(),
+        });
+
+        Self::emit_progress(
+            progress,
+            serde_json::json!({ "type": "lifecycle", "phase": "create", "strategy": "bluegreen" }),
+        );
+        info!(event="BLUEGREEN_CANDIDATE_CREATE", service=%svc_name, candidate=%candidate, "✨ Creating blue-green candidate: [{}]", candidate);
+        self.broadcast_progress(svc_name, Some("STARTING CANDIDATE...".into()));
+        docker
+            .create_container(
+                Some(CreateContainerOptions {
+                    name: candidate.clone(),
+                    platform: Some(self.daemon.platform.clone()),
+                }),
+                config,
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Blue-green candidate create failed: {}", e))?;
+        if let Err(e) = docker
+            .start_container(&candidate, None::<StartContainerOptions<String>>)
             .await
         {
-            if let Some(state) = verify_inspect.state {
-                if state.running != Some(true) {
-                    error!(event="AUTO_ROLLBACK_TRIGGERED", service=%svc_name, "🚨 New version crashed instantly! Initiating Auto-Rollback to previous stable state.");
-                    let _ = self.tx.send(serde_json::json!({ "type": "update_progress", "data": { "service": svc_name, "progress": "ROLLBACK IN PROGRESS🚨" } }).to_string());
-
-                    let _ = docker.remove_container(svc_name, remove_opts).await;
-                    if docker
-                        .create_container(
-                            Some(CreateContainerOptions {
-                                name: svc_name.to_string(),
-                                platform: None,
-                            }),
-                            old_config,
-                        )
-                        .await
-                        .is_ok()
-                    {
-                        let _ = docker
-                            .start_container(svc_name, None::<StartContainerOptions<String>>)
-                            .await;
-                        info!(event="AUTO_ROLLBACK_SUCCESS", service=%svc_name, "♻️ Service rolled back to previous stable image.");
-                    } else {
-                        error!(event="AUTO_ROLLBACK_FAILED", service=%svc_name, "❌ Fatal Error: Failed to rollback service.");
-                    }
+            let _ = docker.remove_container(&candidate, force_remove()).await;
+            return Err(anyhow::anyhow!("Blue-green candidate start failed: {}", e));
+        }
+
+        Self::emit_progress(
+            progress,
+            serde_json::json!({ "type": "lifecycle", "phase": "health_check" }),
+        );
+        self.broadcast_progress(
+            svc_name,
+            Some(format!(
+                "HEALTH CHECK ({}s)...",
+                self.health_timeout.as_secs()
+            )),
+        );
+        if let Err(e) = self.verify_health(&candidate, self.health_timeout).await {
+            error!(event="BLUEGREEN_CANDIDATE_UNHEALTHY", service=%svc_name, error=%e, "🚨 Blue-green candidate failed health verification, old container left untouched.");
+            let _ = docker.remove_container(&candidate, force_remove()).await;
+            return Err(anyhow::anyhow!(
+                "Health verification failed ({}), old container kept running",
+                e
+            ));
+        }
+
+        // Alias takası: aday önce eski alias'larla yeniden bağlanır, ardından eski container ağdan çıkarılır.
+        Self::emit_progress(
+            progress,
+            serde_json::json!({ "type": "lifecycle", "phase": "swap" }),
+        );
+        self.broadcast_progress(svc_name, Some("SWAPPING ALIASES...".into()));
+        let old_id = inspect.id.clone().unwrap_or_default();
+        let mut swapped: Vec<(&String, &EndpointSettings)> = Vec::new();
+        for (net, ep) in &networks {
+            // Varsayılan bridge ağı alias desteklemez; orada sadece container adı geçerlidir.
+            if matches!(net.as_str(), "bridge" | "host" | "none") {
+                continue;
+            }
+            let mut aliases: Vec<String> = ep
+                .aliases
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|a| !old_id.starts_with(a.as_str()))
+                .collect();
+            if !aliases.iter().any(|a| a == svc_name) {
+                aliases.push(svc_name.to_string());
+            }
 
-                    let _ = self.tx.send(serde_json::json!({ "type": "update_progress", "data": { "service": svc_name, "progress": null } }).to_string());
-                    return Ok(false);
+            let swap = async {
+                docker
+                    .disconnect_network(
+                        net,
+                        DisconnectNetworkOptions {
+                            container: candidate.clone(),
+                            force: true,
+                        },
+                    )
+                    .await?;
+                docker
+                    .connect_network(
+                        net,
+                        ConnectNetworkOptions {
+                            container: candidate.clone(),
+                            endpoint_config: EndpointSettings {
+                                aliases: Some(aliases.clone()),
+                                ..Default::default()
+                            },
+                        },
+                    )
+                    .await?;
+                docker
+                    .disconnect_network(
+                        net,
+                        DisconnectNetworkOptions {
+                            container: svc_name.to_string(),
+                            force: true,
+                        },
+                    )
+                    .await
+            };
+            if let Err(e) = swap.await {
+                error!(event="BLUEGREEN_SWAP_FAIL", service=%svc_name, network=%net, error=%e, "❌ Network alias swap failed.");
+                let _ = docker.remove_container(&candidate, force_remove()).await;
+                // Eski container'ın alias'larıyla bağlantısı geri verilir (zaten bağlıysa hata yok sayılır).
+                swapped.push((net, ep));
+                for (net, ep) in swapped {
+                    let _ = docker
+                        .connect_network(
+                            net,
+                            ConnectNetworkOptions {
+                                container: svc_name.to_string(),
+                                endpoint_config: EndpointSettings {
+                                    aliases: ep.aliases.clone(),
+                                    ..Default::default()
+                                },
+                            },
+                        )
+                        .await;
                 }
+                return Err(anyhow::anyhow!(
+                    "Network alias swap failed on '{}': {}",
+                    net,
+                    e
+                ));
             }
+            swapped.push((net, ep));
+            debug!(event="BLUEGREEN_ALIAS_SWAPPED", service=%svc_name, network=%net, aliases=?aliases, "Aliases moved to candidate.");
         }
 
-        info!(event="AUTO_PILOT_SUCCESS", service=%svc_name, "✅ [{}] updated and verified successfully.", svc_name);
-        let _ = self.tx.send(serde_json::json!({ "type": "update_progress", "data": { "service": svc_name, "progress": null } }).to_string());
+        // Eski container artık trafik almıyor; açık bağlantıları için drain süresi tanınır.
+        Self::emit_progress(
+            progress,
+            serde_json::json!({ "type": "lifecycle", "phase": "remove" }),
+        );
+        let drain_secs = Self::stop_timeout_from_labels(svc_name, inspect, UPDATE_DRAIN_TIMEOUT);
+        self.broadcast_progress(svc_name, Some(format!("DRAINING ({}s)", drain_secs)));
+        if let Err(e) = docker
+            .stop_container(svc_name, Some(StopContainerOptions { t: drain_secs }))
+            .await
+        {
+            warn!(event="CONTAINER_STOP_ERROR", service=%svc_name, error=%e, "⚠️ Error while stopping container (maybe already stopped): {}", e)
+        }
+        if let Err(e) = docker.remove_container(svc_name, force_remove()).await {
+            warn!(event="CONTAINER_REMOVE_ERROR", service=%svc_name, error=%e, "⚠️ Error while removing container: {}", e)
+        }
 
-        Ok(true)
+        // Eski container silindi; isim boşta kalmasın diye rename birkaç kez denenir.
+        let mut attempt = 1;
+        let rename_err = loop {
+            match docker
+                .rename_container(
+                    &candidate,
+                    RenameContainerOptions {
+                        name: svc_name.to_string(),
+                    },
+                )
+                .await
+            {
+                Ok(_) => {
+                    info!(event="BLUEGREEN_SWAPPED", service=%svc_name, "🔀 Blue-green swap completed for [{}]", svc_name);
+                    return Ok(());
+                }
+                Err(e) if attempt < BLUEGREEN_RENAME_ATTEMPTS => {
+                    warn!(event="BLUEGREEN_RENAME_RETRY", service=%svc_name, candidate=%candidate, attempt, error=%e, "⚠️ Renaming candidate failed, retrying.");
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    attempt += 1;
+                }
+                Err(e) => break e,
+            }
+        };
+
+        // Rename kalıcı olarak başarısız: eski sürüm asıl isimle geri kurulur, aday ancak bundan
+        // sonra silinir. Geri kurulum da başarısızsa aday trafiği taşımaya devam eder.
+        error!(event="BLUEGREEN_RENAME_FAIL", service=%svc_name, candidate=%candidate, error=%rename_err, "❌ Candidate could not take the service name, rolling back.");
+        if self
+            .rollback_container(svc_name, old_config, progress)
+            .await
+        {
+            let _ = docker.remove_container(&candidate, force_remove()).await;
+            return Err(anyhow::anyhow!(
+                "Rename of '{}' failed ({}), previous version restored",
+                candidate,
+                rename_err
+            ));
+        }
+        error!(event="BLUEGREEN_ORPHAN", service=%svc_name, candidate=%candidate, "🚨 [{}] is gone and the new version keeps serving as orphan container [{}]. Rename it manually.", svc_name, candidate);
+        Err(anyhow::anyhow!(
+            "New version is serving as orphan '{}': rename failed ({}) and rollback failed",
+            candidate,
+            rename_err
+        ))
     }
 
-    pub async fn force_update_service(&self, svc_name: &str) -> Result<String> {
+    pub async fn force_update_service(
+        &self,
+        svc_name: &str,
+        progress: Option<&UpdateProgressTx>,
+    ) -> Result<String> {
         info!(event="FORCE_UPDATE_TRIGGERED", node.name=%self.node_name, service=%svc_name, "⚡ Force update triggered for: [{}]", svc_name);
-        match self.check_and_update_service(svc_name).await {
+        // Engellenen self-update "güncel" gibi raporlanmasın diye elle tetiklemede hata döner.
+        if !self.allow_self_update {
+            let inspect = self.inspect_service(svc_name).await?;
+            if self.is_self(&inspect, svc_name) {
+                return Err(anyhow::anyhow!(SELF_UPDATE_DISABLED));
+            }
+        }
+        match self.check_and_update_service(svc_name, progress).await {
             Ok(updated) => Ok(if updated {
                 "Updated.".into()
             } else {
@@ -422,7 +2264,10 @@ impl DockerAdapter {
 
     // [ARCH-COMPLIANCE]: Güvenli Nexus Güncelleme (İntihar Yasağı Korumalı)
     pub async fn pull_nexus_image(&self) -> Result<String> {
-        let svc_name = "orchestrator-service";
+        let svc_name = self
+            .self_container_id
+            .as_deref()
+            .unwrap_or("orchestrator-service");
         info!(
             event = "NEXUS_SELF_UPDATE",
             "Initiating Nexus safe pull sequence."
@@ -440,17 +2285,18 @@ impl DockerAdapter {
 
         let mut stream = self.client.create_image(
             Some(CreateImageOptions {
-                from_image: image_name,
+                from_image: image_name.clone(),
+                platform: self.daemon.platform.clone(),
                 ..Default::default()
             }),
             None,
-            None,
+            self.registry_auth.for_image(&image_name),
         );
 
         while let Some(res) = stream.next().await {
             if let Err(e) = res {
                 error!(event="NEXUS_PULL_ERROR", error=%e, "Failed to pull Nexus image.");
-                return Err(anyhow::anyhow!("Pull error: {}", e));
+                return Err(Self::classify_pull_error(&image_name, &e));
             }
         }
 
@@ -458,6 +2304,241 @@ impl DockerAdapter {
             event = "NEXUS_PULL_SUCCESS",
             "Nexus image downloaded safely."
         );
+        if self.allow_self_update && self.self_container_id.is_some() {
+            self.spawn_self_update_helper(&inspect, &image_name).await?;
+            return Ok("✅ Nexus Governor Update Downloaded!\n\nA helper container is now recreating the Orchestrator from the new image. The dashboard will reconnect once it is back.".to_string());
+        }
         Ok("✅ Nexus Governor Update Downloaded!\n\nTo prevent cluster brain-death, Orchestrator cannot kill itself.\nPlease run 'make start' on the host terminal to apply the update safely.".to_string())
     }
+
+    // [ARCH-COMPLIANCE]: İki fazlı kendi güncellemesi. Recreate'i yapan süreç recreate edilen süreç
+    // olamayacağı için yeni imajdan tek seferlik bir yardımcı container başlatılır; aynı env ve
+    // mount'ları (Docker socket dahil) taşır, işi bitince `auto_remove` ile kendini siler.
+    async fn spawn_self_update_helper(
+        &self,
+        inspect: &ContainerInspectResponse,
+        image: &str,
+    ) -> Result<String> {
+        let target = inspect
+            .id
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Own container has no ID"))?;
+        let own_name = inspect
+            .name
+            .clone()
+            .unwrap_or_default()
+            .trim_start_matches('/')
+            .to_string();
+        let helper_name = format!("{}-updater", own_name);
+        let own_host = inspect.host_config.clone().unwrap_or_default();
+
+        // Önceki yarım kalmış denemeden artakalan yardımcı varsa temizlenir.
+        let _ = self
+            .client
+            .remove_container(
+                &helper_name,
+                Some(RemoveContainerOptions {
+                    force: true,
+                    ..Default::default()
+                }),
+            )
+            .await;
+
+        let config = Config {
+            image: Some(image.to_string()),
+            cmd: Some(vec![
+                SELF_UPDATE_HELPER_ARG.to_string(),
+                target.clone(),
+                image.to_string(),
+            ]),
+            env: inspect.config.as_ref().and_then(|c| c.env.clone()),
+            host_config: Some(HostConfig {
+                binds: own_host.binds,
+                mounts: own_host.mounts,
+                auto_remove: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let created = self
+            .client
+            .create_container(
+                Some(CreateContainerOptions {
+                    name: helper_name.clone(),
+                    platform: Some(self.daemon.platform.clone()),
+                }),
+                config,
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Self-update helper create failed: {}", e))?;
+        self.client
+            .start_container(&created.id, None::<StartContainerOptions<String>>)
+            .await
+            .map_err(|e| anyhow::anyhow!("Self-update helper start failed: {}", e))?;
+
+        warn!(event="SELF_UPDATE_HANDOFF", helper=%helper_name, image=%image, "🔄 Self-update handed off to helper container. Orchestrator will be stopped and recreated.");
+        Ok(created.id)
+    }
+
+    /// Yardımcı container tarafı: orchestrator'ı durdurur (SIGTERM ile graceful shutdown tetiklenir),
+    /// yeni imajla aynı isim/konfigürasyonla yeniden yaratır. Başlatma veya sağlık doğrulaması
+    /// başarısız olursa eski imaja geri dönülür.
+    pub async fn complete_self_update(&self, target: &str, image: &str) -> Result<()> {
+        let inspect = self.inspect_service(target).await?;
+        let svc_name = inspect
+            .name
+            .clone()
+            .unwrap_or_else(|| target.to_string())
+            .trim_start_matches('/')
+            .to_string();
+        let old_config = Self::recreate_config(&inspect, inspect.image.clone().unwrap_or_default());
+        let new_config = Self::recreate_config(&inspect, image.to_string());
+        self.backup_container_config(&svc_name, &inspect).await;
+
+        let drain_secs = Self::stop_timeout_from_labels(&svc_name, &inspect, UPDATE_DRAIN_TIMEOUT);
+        info!(event="SELF_UPDATE_STOPPING", service=%svc_name, stop_timeout=drain_secs, "🛑 Stopping orchestrator for self-update.");
+        if let Err(e) = self
+            .client
+            .stop_container(target, Some(StopContainerOptions { t: drain_secs }))
+            .await
+        {
+            warn!(event="CONTAINER_STOP_ERROR", service=%svc_name, error=%e, "⚠️ Error while stopping container (maybe already stopped): {}", e);
+        }
+        let mut wait_stream = self.client.wait_container(
+            target,
+            None::<bollard::container::WaitContainerOptions<String>>,
+        );
+        let _ = tokio::time::timeout(
+            Duration::from_secs(drain_secs as u64 + 5),
+            wait_stream.next(),
+        )
+        .await;
+
+        // Bu noktadan sonra erken dönüş orchestrator'ı durmuş bırakır; her hata eski container'ı geri getirir.
+        if let Err(e) = self
+            .client
+            .remove_container(
+                target,
+                Some(RemoveContainerOptions {
+                    force: true,
+                    ..Default::default()
+                }),
+            )
+            .await
+        {
+            error!(event="SELF_UPDATE_ROLLBACK", service=%svc_name, error=%e, "🚨 Could not remove stopped orchestrator, restarting it.");
+            if let Err(start_err) = self
+                .client
+                .start_container(target, None::<StartContainerOptions<String>>)
+                .await
+            {
+                error!(event="SELF_UPDATE_RESTART_FAIL", service=%svc_name, error=%start_err, "🚨 Failed to restart stopped orchestrator, recreating from previous config.");
+                self.rollback_container(&svc_name, old_config, None).await;
+            }
+            return Err(anyhow::anyhow!(
+                "Remove failed ({}), previous container restarted",
+                e
+            ));
+        }
+
+        let started = async {
+            self.client
+                .create_container(
+                    Some(CreateContainerOptions {
+                        name: svc_name.clone(),
+                        platform: Some(self.daemon.platform.clone()),
+                    }),
+                    new_config,
+                )
+                .await?;
+            self.client
+                .start_container(&svc_name, None::<StartContainerOptions<String>>)
+                .await?;
+            anyhow::Ok(())
+        }
+        .await;
+        let verified = match started {
+            Ok(()) => self.verify_health(&svc_name, self.health_timeout).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = verified {
+            error!(event="SELF_UPDATE_ROLLBACK", service=%svc_name, error=%e, "🚨 Updated orchestrator failed to start, rolling back.");
+            self.rollback_container(&svc_name, old_config, None).await;
+            return Err(anyhow::anyhow!("Self-update failed ({}), rolled back", e));
+        }
+
+        info!(event="SELF_UPDATE_SUCCESS", service=%svc_name, image=%image, "✅ Orchestrator recreated from the new image.");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bollard::models::{ContainerConfig, MountPoint};
+
+    fn mount_point(typ: MountPointTypeEnum, name: Option<&str>, dest: &str) -> MountPoint {
+        MountPoint {
+            typ: Some(typ),
+            name: name.map(String::from),
+            destination: Some(dest.into()),
+            rw: Some(true),
+            ..Default::default()
+        }
+    }
+
+    // tmpfs ve bind create anında beyan edilmiştir; imajın VOLUME'undan doğan volume ise sadece
+    // gerçek mount listesinde görünür ve açık bir Mount olarak taşınmalıdır.
+    fn inspect() -> ContainerInspectResponse {
+        ContainerInspectResponse {
+            id: Some("0123456789abcdef".into()),
+            config: Some(ContainerConfig {
+                hostname: Some("0123456789ab".into()),
+                image: Some("app:1.0".into()),
+                cmd: Some(vec!["serve".into(), "--port=80".into()]),
+                ..Default::default()
+            }),
+            host_config: Some(HostConfig {
+                binds: Some(vec!["/srv/data:/data:ro".into()]),
+                tmpfs: Some(HashMap::from([("/run".into(), "size=64m".into())])),
+                ..Default::default()
+            }),
+            mounts: Some(vec![
+                mount_point(MountPointTypeEnum::BIND, None, "/data"),
+                mount_point(MountPointTypeEnum::TMPFS, None, "/run"),
+                mount_point(MountPointTypeEnum::VOLUME, Some("4f1c9e"), "/var/lib/db"),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn recreate_keeps_tmpfs_binds_and_volumes() {
+        let config = DockerAdapter::recreate_config(&inspect(), "app:2.0".into());
+        let host = config.host_config.unwrap();
+
+        assert_eq!(host.binds, Some(vec!["/srv/data:/data:ro".to_string()]));
+        assert_eq!(
+            host.tmpfs.and_then(|t| t.get("/run").cloned()),
+            Some("size=64m".to_string())
+        );
+        // Beyan edilmiş bind/tmpfs ikinci kez Mount olarak eklenmez; sadece volume eklenir.
+        let mounts = host.mounts.unwrap();
+        assert_eq!(mounts.len(), 1);
+        assert_eq!(mounts[0].target.as_deref(), Some("/var/lib/db"));
+        assert_eq!(mounts[0].source.as_deref(), Some("4f1c9e"));
+        assert_eq!(mounts[0].typ, Some(MountTypeEnum::VOLUME));
+        assert_eq!(mounts[0].read_only, Some(false));
+    }
+
+    #[test]
+    fn recreate_swaps_image_and_drops_generated_hostname() {
+        let config = DockerAdapter::recreate_config(&inspect(), "app:2.0".into());
+        assert_eq!(config.image.as_deref(), Some("app:2.0"));
+        assert_eq!(config.hostname, None);
+        assert_eq!(
+            config.cmd,
+            Some(vec!["serve".to_string(), "--port=80".to_string()])
+        );
+    }
 }
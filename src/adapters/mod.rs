@@ -1,2 +1,7 @@
+pub mod audit;
+pub mod autopilot_store;
 pub mod docker;
+pub mod notify;
+pub mod registry;
+pub mod storage;
 pub mod system;
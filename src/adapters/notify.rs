@@ -0,0 +1,232 @@
+// src/adapters/notify.rs
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::core::domain::AlertFormat;
+
+const ALERT_TIMEOUT: Duration = Duration::from_secs(5);
+const ALERT_MAX_ATTEMPTS: u32 = 3;
+
+#[derive(Clone, Copy)]
+enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    fn emoji(self) -> &'static str {
+        match self {
+            Severity::Info => "✅",
+            Severity::Warning => "⚠️",
+            Severity::Critical => "🚨",
+        }
+    }
+
+    // Discord embed rengi (0xRRGGBB)
+    fn color(self) -> u32 {
+        match self {
+            Severity::Info => 0x2ecc71,
+            Severity::Warning => 0xf1c40f,
+            Severity::Critical => 0xe74c3c,
+        }
+    }
+}
+
+struct Alert {
+    severity: Severity,
+    title: String,
+    body: String,
+    // ALERT_FORMAT=raw iken olduğu gibi gönderilen makine okunur gövde
+    raw: Value,
+}
+
+// [ARCH-COMPLIANCE]: Dışa giden alarm webhook'u. Biçimlendirme ve gönderim burada toplanır;
+// gönderim ayrı task'ta yapılır, çağıran döngü asla beklemez.
+#[derive(Clone)]
+pub struct Notifier {
+    url: Option<String>,
+    format: AlertFormat,
+    cluster_name: String,
+    client: Client,
+}
+
+impl Notifier {
+    pub fn new(url: Option<String>, format: AlertFormat, cluster_name: String) -> Self {
+        let client = Client::builder()
+            .timeout(ALERT_TIMEOUT)
+            .build()
+            .expect("Failed to build alert HTTP client");
+        Self {
+            url,
+            format,
+            cluster_name,
+            client,
+        }
+    }
+
+    pub fn node_status_changed(
+        &self,
+        node: &str,
+        old_status: &str,
+        new_status: &str,
+        last_seen: &str,
+    ) {
+        let offline = new_status == "OFFLINE";
+        self.send(Alert {
+            severity: if offline {
+                Severity::Critical
+            } else {
+                Severity::Info
+            },
+            title: format!("Node {} is {}", node, new_status),
+            body: format!(
+                "Status changed {} → {} (last seen {})",
+                old_status, new_status, last_seen
+            ),
+            raw: json!({
+                "event": "node_status",
+                "cluster": self.cluster_name,
+                "node": node,
+                "old_status": old_status,
+                "new_status": new_status,
+                "last_seen": last_seen,
+            }),
+        });
+    }
+
+    pub fn update_result(&self, node: &str, service: &str, result: &anyhow::Result<bool>) {
+        let (severity, title, error) = match result {
+            Ok(false) => return,
+            Ok(true) => (
+                Severity::Info,
+                format!("Auto-pilot updated {}", service),
+                None,
+            ),
+            Err(e) => (
+                Severity::Warning,
+                format!("Auto-pilot update failed for {}", service),
+                Some(e.to_string()),
+            ),
+        };
+        self.send(Alert {
+            severity,
+            body: error.clone().unwrap_or_else(|| {
+                format!(
+                    "Service {} on {} is running the latest image",
+                    service, node
+                )
+            }),
+            title,
+            raw: json!({
+                "event": "auto_pilot_update",
+                "cluster": self.cluster_name,
+                "node": node,
+                "service": service,
+                "success": error.is_none(),
+                "error": error,
+            }),
+        });
+    }
+
+    pub fn oom_killed(&self, node: &str, service: &str, exit_code: Option<i32>) {
+        self.send(Alert {
+            severity: Severity::Critical,
+            title: format!("{} was OOM-killed on {}", service, node),
+            body: match exit_code {
+                Some(code) => format!(
+                    "Kernel OOM killer terminated the container (exit code {})",
+                    code
+                ),
+                None => "Kernel OOM killer terminated a process in the container".to_string(),
+            },
+            raw: json!({
+                "event": "oom_killed",
+                "cluster": self.cluster_name,
+                "node": node,
+                "service": service,
+                "exit_code": exit_code,
+            }),
+        });
+    }
+
+    // `trigger`: "auto" (disk eşiği), "scheduled" (JANITOR_SCHEDULE) veya "manual" (API)
+    pub fn prune_result(&self, node: &str, trigger: &str, result: &anyhow::Result<String>) {
+        let (severity, body, success) = match result {
+            Ok(msg) => (Severity::Info, msg.clone(), true),
+            Err(e) => (Severity::Warning, e.to_string(), false),
+        };
+        self.send(Alert {
+            severity,
+            title: format!("System prune ({}) on {}", trigger, node),
+            raw: json!({
+                "event": "prune",
+                "cluster": self.cluster_name,
+                "node": node,
+                "trigger": trigger,
+                "success": success,
+                "message": body,
+            }),
+            body,
+        });
+    }
+
+    fn payload(&self, alert: Alert) -> Value {
+        let heading = format!(
+            "{} [{}] {}",
+            alert.severity.emoji(),
+            self.cluster_name,
+            alert.title
+        );
+        match self.format {
+            AlertFormat::Raw => alert.raw,
+            AlertFormat::Slack => json!({
+                "text": format!("{}\n{}", heading, alert.body),
+                "blocks": [
+                    { "type": "header", "text": { "type": "plain_text", "text": heading } },
+                    { "type": "section", "text": { "type": "mrkdwn", "text": alert.body } },
+                ],
+            }),
+            AlertFormat::Discord => json!({
+                "content": heading,
+                "embeds": [{
+                    "title": alert.title,
+                    "description": alert.body,
+                    "color": alert.severity.color(),
+                    "footer": { "text": self.cluster_name },
+                }],
+            }),
+        }
+    }
+
+    fn send(&self, alert: Alert) {
+        let Some(url) = self.url.clone() else {
+            return;
+        };
+        let payload = self.payload(alert);
+        let client = self.client.clone();
+        tokio::spawn(async move {
+            let mut backoff = Duration::from_secs(1);
+            for attempt in 1..=ALERT_MAX_ATTEMPTS {
+                match client.post(&url).json(&payload).send().await {
+                    Ok(r) if r.status().is_success() => {
+                        info!(event = "ALERT_SENT", attempt, "📣 Alert webhook delivered.");
+                        return;
+                    }
+                    Ok(r) => {
+                        warn!(event="ALERT_WEBHOOK_FAIL", attempt, status=%r.status(), "⚠️ Alert webhook rejected the payload.")
+                    }
+                    Err(e) => {
+                        warn!(event="ALERT_WEBHOOK_FAIL", attempt, error=%e, "⚠️ Alert webhook unreachable.")
+                    }
+                }
+                if attempt < ALERT_MAX_ATTEMPTS {
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,246 @@
+// src/adapters/registry.rs
+use anyhow::Result;
+use base64::Engine;
+use bollard::auth::DockerCredentials;
+use reqwest::header::{ACCEPT, WWW_AUTHENTICATE};
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+const DOCKER_HUB_HOST: &str = "docker.io";
+// Docker Hub'ın gerçek registry API adresi (imaj adlarında `docker.io` geçer)
+const DOCKER_HUB_API_HOST: &str = "registry-1.docker.io";
+const REGISTRY_TIMEOUT: Duration = Duration::from_secs(10);
+// Manifest list / OCI index dahil: multi-arch imajlarda yerel RepoDigests liste digest'ini tutar.
+const MANIFEST_ACCEPT: &str = "application/vnd.docker.distribution.manifest.list.v2+json, \
+application/vnd.oci.image.index.v1+json, \
+application/vnd.docker.distribution.manifest.v2+json, \
+application/vnd.oci.image.manifest.v1+json";
+
+// [ARCH-COMPLIANCE]: Özel registry kimlik bilgileri. İmaj adındaki registry host'una göre seçilir.
+#[derive(Clone, Default)]
+pub struct RegistryAuth {
+    entries: HashMap<String, DockerCredentials>,
+    http: reqwest::Client,
+}
+
+impl RegistryAuth {
+    // Önce `~/.docker/config.json` okunur; env ile verilen kimlik aynı host için onu ezer.
+    pub fn load(url: Option<String>, username: Option<String>, password: Option<String>) -> Self {
+        let mut auth = Self {
+            http: reqwest::Client::builder()
+                .timeout(REGISTRY_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+        auth.load_docker_config();
+
+        if let (Some(url), Some(username), Some(password)) = (url, username, password) {
+            let host = normalize_host(&url);
+            auth.entries.insert(
+                host.clone(),
+                DockerCredentials {
+                    username: Some(username),
+                    password: Some(password),
+                    serveraddress: Some(host),
+                    ..Default::default()
+                },
+            );
+        }
+
+        if !auth.entries.is_empty() {
+            let mut hosts: Vec<&String> = auth.entries.keys().collect();
+            hosts.sort();
+            info!(event="REGISTRY_AUTH_LOADED", hosts=?hosts, "🔐 Registry credentials loaded for {} host(s)", hosts.len());
+        }
+        auth
+    }
+
+    pub fn for_image(&self, image: &str) -> Option<DockerCredentials> {
+        self.entries.get(&image_registry_host(image)).cloned()
+    }
+
+    /// Digest watch: Registry v2 `HEAD /v2/<repo>/manifests/<tag>` ile uzak manifest digest'i.
+    /// 401'de Bearer (Docker Hub/GHCR token el sıkışması) veya Basic challenge'a göre tekrar denenir.
+    /// Registry `Docker-Content-Digest` dönmüyorsa hata verilir; çağıran pull-and-compare'e düşer.
+    pub async fn remote_digest(&self, image: &str) -> Result<String> {
+        let (host, repo, tag) = parse_reference(image)
+            .ok_or_else(|| anyhow::anyhow!("'{}' is pinned by digest or unparsable", image))?;
+        let api_host = if host == DOCKER_HUB_HOST {
+            DOCKER_HUB_API_HOST
+        } else {
+            host.as_str()
+        };
+        let url = format!("https://{}/v2/{}/manifests/{}", api_host, repo, tag);
+        let creds = self.entries.get(&host);
+
+        let resp = self
+            .http
+            .head(&url)
+            .header(ACCEPT, MANIFEST_ACCEPT)
+            .send()
+            .await?;
+        let resp = if resp.status() == StatusCode::UNAUTHORIZED {
+            let challenge = resp
+                .headers()
+                .get(WWW_AUTHENTICATE)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+            let mut req = self.http.head(&url).header(ACCEPT, MANIFEST_ACCEPT);
+            if let Some(params) = challenge.strip_prefix("Bearer ") {
+                req = req.bearer_auth(self.bearer_token(params, creds).await?);
+            } else if let Some(c) = creds {
+                req = req.basic_auth(c.username.clone().unwrap_or_default(), c.password.clone());
+            }
+            req.send().await?
+        } else {
+            resp
+        };
+
+        if !resp.status().is_success() {
+            anyhow::bail!("manifest HEAD returned {}", resp.status());
+        }
+        let digest = resp
+            .headers()
+            .get("docker-content-digest")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("registry did not return Docker-Content-Digest"))?;
+        debug!(event="REGISTRY_DIGEST", image=%image, digest=%digest, "Remote manifest digest resolved.");
+        Ok(digest)
+    }
+
+    // `realm="...",service="...",scope="..."` -> token uç noktasından kısa ömürlü token alınır.
+    async fn bearer_token(
+        &self,
+        challenge: &str,
+        creds: Option<&DockerCredentials>,
+    ) -> Result<String> {
+        let params: HashMap<&str, &str> = challenge
+            .split(',')
+            .filter_map(|kv| kv.trim().split_once('='))
+            .map(|(k, v)| (k, v.trim_matches('"')))
+            .collect();
+        let realm = params
+            .get("realm")
+            .ok_or_else(|| anyhow::anyhow!("Bearer challenge without realm"))?;
+        let query: Vec<(&str, &str)> = ["service", "scope"]
+            .iter()
+            .filter_map(|k| params.get(k).map(|v| (*k, *v)))
+            .collect();
+
+        let mut req = self.http.get(*realm).query(&query);
+        if let Some(c) = creds {
+            req = req.basic_auth(c.username.clone().unwrap_or_default(), c.password.clone());
+        }
+        let body: serde_json::Value = req.send().await?.error_for_status()?.json().await?;
+        body.get("token")
+            .or_else(|| body.get("access_token"))
+            .and_then(|t| t.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("token endpoint returned no token"))
+    }
+
+    fn load_docker_config(&mut self) {
+        let path = std::env::var("DOCKER_CONFIG")
+            .map(PathBuf::from)
+            .or_else(|_| std::env::var("HOME").map(|h| PathBuf::from(h).join(".docker")))
+            .map(|dir| dir.join("config.json"));
+        let Ok(path) = path else {
+            return;
+        };
+        let Ok(raw) = std::fs::read_to_string(&path) else {
+            return;
+        };
+        let json: serde_json::Value = match serde_json::from_str(&raw) {
+            Ok(j) => j,
+            Err(e) => {
+                warn!(event="REGISTRY_CONFIG_INVALID", path=%path.display(), error=%e, "⚠️ Docker config.json could not be parsed");
+                return;
+            }
+        };
+
+        let Some(auths) = json.get("auths").and_then(|a| a.as_object()) else {
+            return;
+        };
+        for (server, entry) in auths {
+            let Some(encoded) = entry.get("auth").and_then(|a| a.as_str()) else {
+                continue;
+            };
+            let Ok(decoded) = base64::engine::general_purpose::STANDARD.decode(encoded) else {
+                continue;
+            };
+            let decoded = String::from_utf8_lossy(&decoded);
+            let Some((username, password)) = decoded.split_once(':') else {
+                continue;
+            };
+            let host = normalize_host(server);
+            self.entries.insert(
+                host.clone(),
+                DockerCredentials {
+                    username: Some(username.to_string()),
+                    password: Some(password.to_string()),
+                    serveraddress: Some(host),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
+
+// "https://registry.gitlab.com/v2/" -> "registry.gitlab.com", Docker Hub takma adları tekilleştirilir.
+fn normalize_host(server: &str) -> String {
+    let host = server
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .split('/')
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    match host.as_str() {
+        "index.docker.io" | "registry-1.docker.io" | "" => DOCKER_HUB_HOST.to_string(),
+        _ => host,
+    }
+}
+
+// İlk path parçası nokta/port içeriyorsa (veya localhost ise) registry host'udur, aksi halde Docker Hub.
+fn image_registry_host(image: &str) -> String {
+    match image.split_once('/') {
+        Some((first, _)) if is_registry_segment(first) => normalize_host(first),
+        _ => DOCKER_HUB_HOST.to_string(),
+    }
+}
+
+fn is_registry_segment(first: &str) -> bool {
+    first.contains('.') || first.contains(':') || first == "localhost"
+}
+
+// "nginx" -> ("docker.io", "library/nginx", "latest"); digest ile sabitlenmiş referanslarda `None`.
+fn parse_reference(image: &str) -> Option<(String, String, String)> {
+    if image.contains('@') {
+        return None;
+    }
+    let host = image_registry_host(image);
+    let path = match image.split_once('/') {
+        Some((first, rest)) if is_registry_segment(first) => rest,
+        _ => image,
+    };
+    // Port içeren host'lar ayrıldıktan sonra son `:` tag ayırıcısıdır.
+    let (repo, tag) = match path.rsplit_once(':') {
+        Some((repo, tag)) if !tag.contains('/') => (repo, tag),
+        _ => (path, "latest"),
+    };
+    let repo = if host == DOCKER_HUB_HOST && !repo.contains('/') {
+        format!("library/{}", repo)
+    } else {
+        repo.to_string()
+    };
+    if repo.is_empty() {
+        return None;
+    }
+    Some((host, repo, tag.to_string()))
+}
@@ -0,0 +1,215 @@
+// src/adapters/storage.rs
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+use crate::core::domain::NodeStats;
+
+// Sıcak döngüyü asla bekletmemek için kuyruk dolarsa kayıt düşürülür.
+const STORAGE_QUEUE_LEN: usize = 4096;
+const STORAGE_BATCH_MAX: usize = 256;
+
+enum StorageCmd {
+    Node {
+        ts: String,
+        stats: Box<NodeStats>,
+    },
+    ServiceChange {
+        ts: String,
+        node: String,
+        service: String,
+        old_status: Option<String>,
+        new_status: String,
+        health: String,
+    },
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct NodeStatsRow {
+    pub ts: String,
+    pub node: String,
+    pub cpu_usage: f64,
+    pub ram_used: i64,
+    pub ram_total: i64,
+    pub gpu_usage: f64,
+    pub gpu_mem_used: i64,
+    pub disk_used: i64,
+    pub status: String,
+    pub health_score: i64,
+}
+
+// [ARCH-COMPLIANCE]: Opsiyonel SQLite deposu. Yazımlar ayrı bir thread'de toplu (transaction) yapılır.
+#[derive(Clone)]
+pub struct StorageAdapter {
+    path: String,
+    tx: mpsc::Sender<StorageCmd>,
+}
+
+impl StorageAdapter {
+    pub fn open(path: &str) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "PRAGMA journal_mode=WAL;
+             CREATE TABLE IF NOT EXISTS node_stats (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 ts TEXT NOT NULL,
+                 node TEXT NOT NULL,
+                 cpu_usage REAL NOT NULL,
+                 ram_used INTEGER NOT NULL,
+                 ram_total INTEGER NOT NULL,
+                 gpu_usage REAL NOT NULL,
+                 gpu_mem_used INTEGER NOT NULL,
+                 disk_used INTEGER NOT NULL,
+                 status TEXT NOT NULL,
+                 health_score INTEGER NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_node_stats_node_ts ON node_stats(node, ts);
+             CREATE TABLE IF NOT EXISTS service_changes (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 ts TEXT NOT NULL,
+                 node TEXT NOT NULL,
+                 service TEXT NOT NULL,
+                 old_status TEXT,
+                 new_status TEXT NOT NULL,
+                 health TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_service_changes_ts ON service_changes(ts);",
+        )?;
+
+        let (tx, rx) = mpsc::channel(STORAGE_QUEUE_LEN);
+        std::thread::Builder::new()
+            .name("storage-writer".into())
+            .spawn(move || Self::writer_loop(conn, rx))?;
+
+        info!(event="STORAGE_OPENED", path=%path, "🗄️ SQLite storage enabled.");
+        Ok(Self {
+            path: path.to_string(),
+            tx,
+        })
+    }
+
+    pub fn record_node(&self, stats: &NodeStats) {
+        self.enqueue(StorageCmd::Node {
+            ts: chrono::Utc::now().to_rfc3339(),
+            stats: Box::new(stats.clone()),
+        });
+    }
+
+    pub fn record_service_change(
+        &self,
+        node: &str,
+        service: &str,
+        old_status: Option<&str>,
+        new_status: &str,
+        health: &str,
+    ) {
+        self.enqueue(StorageCmd::ServiceChange {
+            ts: chrono::Utc::now().to_rfc3339(),
+            node: node.to_string(),
+            service: service.to_string(),
+            old_status: old_status.map(str::to_string),
+            new_status: new_status.to_string(),
+            health: health.to_string(),
+        });
+    }
+
+    pub async fn recent_nodes(
+        &self,
+        node: Option<String>,
+        limit: usize,
+    ) -> Result<Vec<NodeStatsRow>> {
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(path)?;
+            let mut stmt = conn.prepare(
+                "SELECT ts, node, cpu_usage, ram_used, ram_total, gpu_usage, gpu_mem_used, disk_used, status, health_score
+                 FROM node_stats WHERE (?1 IS NULL OR node = ?1) ORDER BY id DESC LIMIT ?2",
+            )?;
+            let rows = stmt
+                .query_map(params![node, limit as i64], |r| {
+                    Ok(NodeStatsRow {
+                        ts: r.get(0)?,
+                        node: r.get(1)?,
+                        cpu_usage: r.get(2)?,
+                        ram_used: r.get(3)?,
+                        ram_total: r.get(4)?,
+                        gpu_usage: r.get(5)?,
+                        gpu_mem_used: r.get(6)?,
+                        disk_used: r.get(7)?,
+                        status: r.get(8)?,
+                        health_score: r.get(9)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(rows)
+        })
+        .await?
+    }
+
+    fn enqueue(&self, cmd: StorageCmd) {
+        if self.tx.try_send(cmd).is_err() {
+            warn!(
+                event = "STORAGE_QUEUE_FULL",
+                "⚠️ Storage queue full, dropping record."
+            );
+        }
+    }
+
+    fn writer_loop(mut conn: Connection, mut rx: mpsc::Receiver<StorageCmd>) {
+        while let Some(first) = rx.blocking_recv() {
+            let mut batch = vec![first];
+            while batch.len() < STORAGE_BATCH_MAX {
+                match rx.try_recv() {
+                    Ok(cmd) => batch.push(cmd),
+                    Err(_) => break,
+                }
+            }
+            if let Err(e) = Self::write_batch(&mut conn, batch) {
+                error!(event="STORAGE_WRITE_FAIL", error=%e, "❌ Failed to write storage batch.");
+            }
+        }
+    }
+
+    fn write_batch(conn: &mut Connection, batch: Vec<StorageCmd>) -> rusqlite::Result<()> {
+        let txn = conn.transaction()?;
+        for cmd in batch {
+            match cmd {
+                StorageCmd::Node { ts, stats } => {
+                    txn.execute(
+                        "INSERT INTO node_stats (ts, node, cpu_usage, ram_used, ram_total, gpu_usage, gpu_mem_used, disk_used, status, health_score)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                        params![
+                            ts,
+                            stats.name,
+                            stats.cpu_usage as f64,
+                            stats.ram_used as i64,
+                            stats.ram_total as i64,
+                            stats.gpu_usage as f64,
+                            stats.gpu_mem_used as i64,
+                            stats.disk_used as i64,
+                            stats.status,
+                            stats.health_score as i64,
+                        ],
+                    )?;
+                }
+                StorageCmd::ServiceChange {
+                    ts,
+                    node,
+                    service,
+                    old_status,
+                    new_status,
+                    health,
+                } => {
+                    txn.execute(
+                        "INSERT INTO service_changes (ts, node, service, old_status, new_status, health)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                        params![ts, node, service, old_status, new_status, health],
+                    )?;
+                }
+            }
+        }
+        txn.commit()
+    }
+}
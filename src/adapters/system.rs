@@ -1,29 +1,67 @@
 // src/adapters/system.rs
-use crate::core::domain::NodeStats;
+use crate::core::domain::{DiskStat, NodeStats};
+use nvml_wrapper::enum_wrappers::device::TemperatureSensor;
+use nvml_wrapper::Nvml;
 use std::process::Command;
-use std::time::Instant;
-use sysinfo::{Disks, Networks, System};
+use std::time::{Duration, Instant};
+use sysinfo::{Components, Disks, Networks, System};
+use tracing::{debug, info};
+
+// Disk doluluğu yavaş değiştiği için CPU/RAM'den seyrek okunur.
+const DISK_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Açılışta bir kez tespit edilir; her turda iki aracı birden denememek için cache'lenir.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    None,
+}
 
 pub struct SystemMonitor {
+    gpu_vendor: GpuVendor,
+    // NVML yüklenebildiyse her turda nvidia-smi süreci başlatılmaz; yoksa shell-out'a düşülür.
+    nvml: Option<Nvml>,
     sys: System,
     networks: Networks,
     disks: Disks,
+    components: Components,
+    // Son disk okumasının sonucu: (mount bazında liste, toplam GB, kullanılan GB)
+    disk_cache: (Vec<DiskStat>, u64, u64),
+    last_disk_refresh: Option<Instant>,
     node_name: String,
     last_update: Instant,
-    last_net_rx: u64,
-    last_net_tx: u64,
+    // İlk okumada önceki değer yoktur; None iken hız 0 raporlanır (açılış sıçramasını önler).
+    last_net: Option<(u64, u64)>,
 }
 
 impl SystemMonitor {
     pub fn new(node_name: String) -> Self {
+        let nvml = match Nvml::init() {
+            Ok(nvml) => Some(nvml),
+            Err(e) => {
+                debug!(event="NVML_UNAVAILABLE", error=%e, "NVML not available, falling back to nvidia-smi.");
+                None
+            }
+        };
+        let gpu_vendor = if nvml.is_some() {
+            GpuVendor::Nvidia
+        } else {
+            Self::detect_gpu_vendor()
+        };
+        info!(event="GPU_VENDOR_DETECTED", gpu.vendor=?gpu_vendor, nvml=nvml.is_some(), "🎮 GPU vendor detected: {:?}", gpu_vendor);
         Self {
+            gpu_vendor,
+            nvml,
             sys: System::new_all(),
             networks: Networks::new_with_refreshed_list(),
             disks: Disks::new_with_refreshed_list(),
+            components: Components::new_with_refreshed_list(),
+            disk_cache: (Vec::new(), 0, 0),
+            last_disk_refresh: None,
             node_name,
             last_update: Instant::now(),
-            last_net_rx: 0,
-            last_net_tx: 0,
+            last_net: None,
         }
     }
 
@@ -31,7 +69,6 @@ impl SystemMonitor {
         self.sys.refresh_cpu_usage();
         self.sys.refresh_memory();
         self.networks.refresh_list();
-        self.disks.refresh_list();
 
         let elapsed = self.last_update.elapsed().as_secs_f64().max(0.1);
         self.last_update = Instant::now();
@@ -52,19 +89,80 @@ impl SystemMonitor {
             current_tx += data.total_transmitted();
         }
 
-        let rx_delta = current_rx.saturating_sub(self.last_net_rx);
-        let tx_delta = current_tx.saturating_sub(self.last_net_tx);
+        let (rx_delta, tx_delta) = match self.last_net {
+            Some((last_rx, last_tx)) => (
+                current_rx.saturating_sub(last_rx),
+                current_tx.saturating_sub(last_tx),
+            ),
+            None => (0, 0),
+        };
+        self.last_net = Some((current_rx, current_tx));
+
+        let net_rx_bps = (rx_delta as f64 / elapsed) as u64;
+        let net_tx_bps = (tx_delta as f64 / elapsed) as u64;
+        let net_rx_mbs = net_rx_bps as f64 / 1_048_576.0;
+        let net_tx_mbs = net_tx_bps as f64 / 1_048_576.0;
 
-        self.last_net_rx = current_rx;
-        self.last_net_tx = current_tx;
+        // 2. DİSK İSTATİSTİKLERİ
+        if self
+            .last_disk_refresh
+            .is_none_or(|t| t.elapsed() >= DISK_REFRESH_INTERVAL)
+        {
+            self.disks.refresh_list();
+            self.disk_cache = self.collect_disks();
+            self.last_disk_refresh = Some(Instant::now());
+        }
+        let (disks, disk_total_gb, disk_used_gb) = self.disk_cache.clone();
 
-        let net_rx_mbs = (rx_delta as f64 / elapsed) / 1_048_576.0;
-        let net_tx_mbs = (tx_delta as f64 / elapsed) / 1_048_576.0;
+        let (gpu_util, gpu_mem_used, gpu_mem_total, gpu_count, gpu_temp_c) = self.get_gpu_metrics();
+        let cpu_temp_c = self.cpu_temperature();
+        // Windows'ta load average kavramı yoktur; sysinfo'nun döndürdüğü değer yerine 0 raporlanır.
+        let load_avg = if cfg!(target_os = "windows") {
+            [0.0; 3]
+        } else {
+            let l = System::load_average();
+            [l.one, l.five, l.fifteen]
+        };
+
+        NodeStats {
+            name: self.node_name.clone(),
+            cpu_usage: self.sys.global_cpu_usage(),
+            per_core_usage: self.sys.cpus().iter().map(|c| c.cpu_usage()).collect(),
+            ram_used: self.sys.used_memory() / 1024 / 1024,
+            ram_total: self.sys.total_memory() / 1024 / 1024,
+            swap_used: self.sys.used_swap() / 1024 / 1024,
+            swap_total: self.sys.total_swap() / 1024 / 1024,
+            disk_used: disk_used_gb,
+            disk_total: disk_total_gb,
+            disks,
+            gpu_usage: gpu_util,
+            gpu_mem_used,
+            gpu_mem_total,
+            gpu_count,
+            load_avg,
+            uptime_secs: System::uptime(),
+            cpu_temp_c,
+            gpu_temp_c,
+            net_rx_mbs,
+            net_tx_mbs,
+            net_rx_bps,
+            net_tx_bps,
+            last_seen: chrono::Utc::now().to_rfc3339(),
+            status: "ONLINE".to_string(),
+            last_scan: None,
+            last_report: None,
+            health_score: 0,
+            tags: Vec::new(),
+        }
+    }
 
-        // 2. DİSK İSTATİSTİKLERİ [ARCH-COMPLIANCE FIX: Whitelist & Deduplication]
+    // [ARCH-COMPLIANCE FIX: Whitelist & Deduplication] Toplamlar fiziksel disk başına bir kez sayılır;
+    // mount listesi ise her mount noktasını ayrı raporlar.
+    fn collect_disks(&self) -> (Vec<DiskStat>, u64, u64) {
         let mut disk_total_bytes = 0;
         let mut disk_used_bytes = 0;
         let mut processed_devices = std::collections::HashSet::new();
+        let mut mounts = Vec::new();
 
         for disk in &self.disks {
             let fs_type = disk.file_system().to_string_lossy().to_lowercase();
@@ -78,6 +176,12 @@ impl SystemMonitor {
                 || fs_type == "zfs"
                 || fs_type == "vfat"
             {
+                mounts.push(DiskStat {
+                    mount_point: disk.mount_point().to_string_lossy().into_owned(),
+                    file_system: fs_type,
+                    total_bytes: disk.total_space(),
+                    available_bytes: disk.available_space(),
+                });
                 // Aynı fiziksel diski birden fazla mount noktasından okumamak için tekilleştir
                 if processed_devices.insert(device_name) {
                     disk_total_bytes += disk.total_space();
@@ -88,57 +192,257 @@ impl SystemMonitor {
 
         // Eğer Container izolasyonundan dolayı fiziksel disk bulunamazsa (Fallback Mechanism)
         if disk_total_bytes == 0 {
-            for disk in &self.disks {
-                if disk.mount_point().to_string_lossy() == "/" {
-                    disk_total_bytes = disk.total_space();
-                    disk_used_bytes = disk.total_space().saturating_sub(disk.available_space());
-                    break;
-                }
+            if let Some(disk) = self
+                .disks
+                .iter()
+                .find(|d| d.mount_point().to_string_lossy() == "/")
+            {
+                disk_total_bytes = disk.total_space();
+                disk_used_bytes = disk.total_space().saturating_sub(disk.available_space());
+                mounts.push(DiskStat {
+                    mount_point: "/".to_string(),
+                    file_system: disk.file_system().to_string_lossy().to_lowercase(),
+                    total_bytes: disk.total_space(),
+                    available_bytes: disk.available_space(),
+                });
             }
         }
 
-        let disk_total_gb = disk_total_bytes / 1_073_741_824;
-        let disk_used_gb = disk_used_bytes / 1_073_741_824;
+        (
+            mounts,
+            disk_total_bytes / 1_073_741_824,
+            disk_used_bytes / 1_073_741_824,
+        )
+    }
 
-        let (gpu_util, gpu_mem_used, gpu_mem_total) = self.get_gpu_metrics();
+    // En sıcak CPU sensörü. Sensör okunamayan platformlarda (VM, container, macOS) None döner.
+    fn cpu_temperature(&mut self) -> Option<f32> {
+        self.components.refresh();
+        self.components
+            .iter()
+            .filter(|c| {
+                let label = c.label().to_lowercase();
+                [
+                    "cpu", "core", "package", "tctl", "tdie", "k10temp", "coretemp",
+                ]
+                .iter()
+                .any(|k| label.contains(k))
+            })
+            .map(|c| c.temperature())
+            .filter(|t| t.is_finite() && *t > 0.0)
+            .reduce(f32::max)
+    }
 
-        NodeStats {
-            name: self.node_name.clone(),
-            cpu_usage: self.sys.global_cpu_usage(),
-            ram_used: self.sys.used_memory() / 1024 / 1024,
-            ram_total: self.sys.total_memory() / 1024 / 1024,
-            disk_used: disk_used_gb,
-            disk_total: disk_total_gb,
-            gpu_usage: gpu_util,
-            gpu_mem_used,
-            gpu_mem_total,
-            net_rx_mbs,
-            net_tx_mbs,
-            last_seen: chrono::Utc::now().to_rfc3339(),
-            status: "ONLINE".to_string(),
+    fn detect_gpu_vendor() -> GpuVendor {
+        let succeeds = |cmd: &str, args: &[&str]| {
+            Command::new(cmd)
+                .args(args)
+                .output()
+                .is_ok_and(|o| o.status.success())
+        };
+        if succeeds("nvidia-smi", &["-L"]) {
+            GpuVendor::Nvidia
+        } else if succeeds("rocm-smi", &["--showuse", "--json"]) {
+            GpuVendor::Amd
+        } else {
+            GpuVendor::None
+        }
+    }
+
+    fn get_gpu_metrics(&self) -> (f32, u64, u64, u32, Option<f32>) {
+        match self.gpu_vendor {
+            GpuVendor::Nvidia => self.get_nvidia_metrics(),
+            GpuVendor::Amd => self.get_amd_metrics(),
+            GpuVendor::None => (0.0, 0, 0, 0, None),
         }
     }
 
-    fn get_gpu_metrics(&self) -> (f32, u64, u64) {
+    // rocm-smi JSON çıktısı: {"card0": {"GPU use (%)": "12", "VRAM Total Memory (B)": "...", "VRAM Total Used Memory (B)": "..."}}
+    fn get_amd_metrics(&self) -> (f32, u64, u64, u32, Option<f32>) {
+        let output = Command::new("rocm-smi")
+            .args(["--showuse", "--showmeminfo", "vram", "--showtemp", "--json"])
+            .output();
+
+        let Ok(out) = output else {
+            return (0.0, 0, 0, 0, None);
+        };
+        if !out.status.success() {
+            return (0.0, 0, 0, 0, None);
+        }
+        Self::parse_rocm_smi(&String::from_utf8_lossy(&out.stdout))
+    }
+
+    // Sensörü olmayan kartlarda (ör. MI serisi "edge") değer "N/A" döner ve sıcaklığa katılmaz.
+    fn parse_rocm_smi(output: &str) -> (f32, u64, u64, u32, Option<f32>) {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(output) else {
+            return (0.0, 0, 0, 0, None);
+        };
+
+        let field = |card: &serde_json::Value, key: &str| -> f64 {
+            match card.get(key) {
+                Some(serde_json::Value::String(s)) => s.trim().parse().unwrap_or(0.0),
+                Some(v) => v.as_f64().unwrap_or(0.0),
+                None => 0.0,
+            }
+        };
+
+        let mut cards = 0u32;
+        let mut usage_sum = 0.0;
+        let mut mem_used = 0u64;
+        let mut mem_total = 0u64;
+        let mut temp: Option<f32> = None;
+        for (name, card) in json.as_object().into_iter().flatten() {
+            if !name.starts_with("card") {
+                continue;
+            }
+            cards += 1;
+            usage_sum += field(card, "GPU use (%)");
+            mem_used += field(card, "VRAM Total Used Memory (B)") as u64 / 1024 / 1024;
+            mem_total += field(card, "VRAM Total Memory (B)") as u64 / 1024 / 1024;
+            let t = field(card, "Temperature (Sensor edge) (C)") as f32;
+            if t > 0.0 {
+                temp = Some(temp.map_or(t, |m| m.max(t)));
+            }
+        }
+
+        if cards == 0 {
+            return (0.0, 0, 0, 0, None);
+        }
+        (
+            (usage_sum / cards as f64) as f32,
+            mem_used,
+            mem_total,
+            cards,
+            temp,
+        )
+    }
+
+    // NVML üzerinden doğrudan sorgu; nvidia-smi ile aynı toplama kuralları uygulanır.
+    fn get_nvml_metrics(
+        nvml: &Nvml,
+    ) -> Result<(f32, u64, u64, u32, Option<f32>), nvml_wrapper::error::NvmlError> {
+        let count = nvml.device_count()?;
+        if count == 0 {
+            return Ok((0.0, 0, 0, 0, None));
+        }
+        let mut usage_sum = 0.0f32;
+        let mut mem_used = 0u64;
+        let mut mem_total = 0u64;
+        let mut temp: Option<f32> = None;
+        for i in 0..count {
+            let device = nvml.device_by_index(i)?;
+            usage_sum += device.utilization_rates()?.gpu as f32;
+            let mem = device.memory_info()?;
+            mem_used += mem.used / 1024 / 1024;
+            mem_total += mem.total / 1024 / 1024;
+            if let Ok(t) = device.temperature(TemperatureSensor::Gpu) {
+                let t = t as f32;
+                temp = Some(temp.map_or(t, |m| m.max(t)));
+            }
+        }
+        Ok((usage_sum / count as f32, mem_used, mem_total, count, temp))
+    }
+
+    // Çoklu GPU: her satır bir kart; bellek toplanır, kullanım ortalaması alınır, sıcaklık en yükseğidir.
+    fn get_nvidia_metrics(&self) -> (f32, u64, u64, u32, Option<f32>) {
+        if let Some(nvml) = &self.nvml {
+            match Self::get_nvml_metrics(nvml) {
+                Ok(metrics) => return metrics,
+                Err(e) => {
+                    debug!(event="NVML_QUERY_FAIL", error=%e, "NVML query failed, using nvidia-smi.")
+                }
+            }
+        }
         let output = Command::new("nvidia-smi")
             .args([
-                "--query-gpu=utilization.gpu,memory.used,memory.total",
+                "--query-gpu=utilization.gpu,memory.used,memory.total,temperature.gpu",
                 "--format=csv,noheader,nounits",
             ])
             .output();
 
-        if let Ok(out) = output {
-            if out.status.success() {
-                let s = String::from_utf8_lossy(&out.stdout);
-                let parts: Vec<&str> = s.trim().split(',').collect();
-                if parts.len() >= 3 {
-                    let usage = parts[0].trim().parse::<f32>().unwrap_or(0.0);
-                    let mem = parts[1].trim().parse::<u64>().unwrap_or(0);
-                    let total = parts[2].trim().parse::<u64>().unwrap_or(0);
-                    return (usage, mem, total);
-                }
+        let Ok(out) = output else {
+            return (0.0, 0, 0, 0, None);
+        };
+        if !out.status.success() {
+            return (0.0, 0, 0, 0, None);
+        }
+
+        Self::parse_nvidia_smi(&String::from_utf8_lossy(&out.stdout))
+    }
+
+    // `--format=csv,noheader,nounits` çıktısı: her satır bir kart.
+    fn parse_nvidia_smi(output: &str) -> (f32, u64, u64, u32, Option<f32>) {
+        let mut count = 0u32;
+        let mut usage_sum = 0.0f32;
+        let mut mem_used = 0u64;
+        let mut mem_total = 0u64;
+        let mut temp: Option<f32> = None;
+        for line in output.lines() {
+            let parts: Vec<&str> = line.split(',').collect();
+            if parts.len() < 3 {
+                continue;
+            }
+            count += 1;
+            usage_sum += parts[0].trim().parse::<f32>().unwrap_or(0.0);
+            mem_used += parts[1].trim().parse::<u64>().unwrap_or(0);
+            mem_total += parts[2].trim().parse::<u64>().unwrap_or(0);
+            // Sensörü olmayan kartlarda "[N/A]" döner
+            if let Some(t) = parts.get(3).and_then(|p| p.trim().parse::<f32>().ok()) {
+                temp = Some(temp.map_or(t, |m| m.max(t)));
             }
         }
-        (0.0, 0, 0)
+
+        if count == 0 {
+            return (0.0, 0, 0, 0, None);
+        }
+        (usage_sum / count as f32, mem_used, mem_total, count, temp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `rocm-smi --showuse --showmeminfo vram --showtemp --json` (ROCm 6.x, MI210 + RX 7900 XTX)
+    const ROCM_SMI_TWO_CARDS: &str = r#"{"card0": {"Temperature (Sensor edge) (C)": "N/A", "Temperature (Sensor junction) (C)": "41.0", "Temperature (Sensor memory) (C)": "38.0", "GPU use (%)": "20", "VRAM Total Memory (B)": "68702699520", "VRAM Total Used Memory (B)": "10737418240"}, "card1": {"Temperature (Sensor edge) (C)": "52.0", "Temperature (Sensor junction) (C)": "60.0", "Temperature (Sensor memory) (C)": "56.0", "GPU use (%)": "40", "VRAM Total Memory (B)": "25753026560", "VRAM Total Used Memory (B)": "1073741824"}, "system": {"Driver version": "6.7.0"}}"#;
+
+    // `nvidia-smi --query-gpu=utilization.gpu,memory.used,memory.total,temperature.gpu --format=csv,noheader,nounits`
+    const NVIDIA_SMI_TWO_CARDS: &str = "35, 1024, 24576, 54\n5, 3, 16384, [N/A]\n";
+
+    #[test]
+    fn rocm_smi_aggregates_cards_and_skips_missing_sensors() {
+        let (usage, used, total, cards, temp) = SystemMonitor::parse_rocm_smi(ROCM_SMI_TWO_CARDS);
+        assert_eq!(cards, 2);
+        assert_eq!(usage, 30.0);
+        assert_eq!(used, 10240 + 1024);
+        assert_eq!(total, 65520 + 24560);
+        assert_eq!(temp, Some(52.0));
+    }
+
+    #[test]
+    fn rocm_smi_without_cards_or_valid_json_is_empty() {
+        assert_eq!(
+            SystemMonitor::parse_rocm_smi(r#"{"system": {"Driver version": "6.7.0"}}"#),
+            (0.0, 0, 0, 0, None)
+        );
+        assert_eq!(
+            SystemMonitor::parse_rocm_smi("WARNING: No AMD GPUs specified"),
+            (0.0, 0, 0, 0, None)
+        );
+    }
+
+    #[test]
+    fn nvidia_smi_aggregates_cards_and_skips_na_temperature() {
+        let (usage, used, total, cards, temp) =
+            SystemMonitor::parse_nvidia_smi(NVIDIA_SMI_TWO_CARDS);
+        assert_eq!(cards, 2);
+        assert_eq!(usage, 20.0);
+        assert_eq!(used, 1027);
+        assert_eq!(total, 40960);
+        assert_eq!(temp, Some(54.0));
+
+        let (_, _, _, cards, temp) = SystemMonitor::parse_nvidia_smi("0, 3, 16384, [N/A]\n");
+        assert_eq!((cards, temp), (1, None));
+        assert_eq!(SystemMonitor::parse_nvidia_smi(""), (0.0, 0, 0, 0, None));
     }
 }
@@ -0,0 +1,373 @@
+// src/api/agent.rs
+// [ARCH-COMPLIANCE]: Upstream -> edge komut kanalı. Edge node'lar upstream'e uzun ömürlü bir
+// WebSocket açar (`/ws/agent/:node`), komutları bu kanaldan alır ve her biri için Ack döner.
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, State,
+    },
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{header, HeaderValue};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::Connector;
+use tracing::{debug, info, warn};
+
+use crate::api::auth::{Caller, Scope, NODE_TOKEN_HEADER};
+use crate::api::routes::{audit, container_name};
+use crate::core::domain::{
+    CommandAck, LifecycleEvent, NodeCommand, NodeCommandParams, WsMessage as UiMessage,
+};
+use crate::AppState;
+
+// Güncelleme komutu pull + drain + sağlık doğrulaması içerdiği için uzun tutulur.
+const COMMAND_ACK_TIMEOUT: Duration = Duration::from_secs(300);
+const AGENT_QUEUE_LEN: usize = 32;
+const SUPPORTED_ACTIONS: [&str; 4] = ["start", "stop", "restart", "update"];
+
+#[derive(Default)]
+pub struct AgentRegistry {
+    links: Mutex<HashMap<String, mpsc::Sender<NodeCommand>>>,
+    pending: Mutex<HashMap<String, oneshot::Sender<CommandAck>>>,
+    next_id: AtomicU64,
+}
+
+impl AgentRegistry {
+    // Aynı node yeniden bağlanırsa eski kanal yenisiyle değiştirilir.
+    async fn register(
+        &self,
+        node: &str,
+    ) -> (mpsc::Sender<NodeCommand>, mpsc::Receiver<NodeCommand>) {
+        let (tx, rx) = mpsc::channel(AGENT_QUEUE_LEN);
+        self.links.lock().await.insert(node.to_string(), tx.clone());
+        (tx, rx)
+    }
+
+    // Sadece bu bağlantıya ait kanal silinir; yerine geçen yeni bağlantıya dokunulmaz.
+    async fn unregister(&self, node: &str, own: &mpsc::Sender<NodeCommand>) {
+        let mut links = self.links.lock().await;
+        if links.get(node).is_some_and(|tx| tx.same_channel(own)) {
+            links.remove(node);
+        }
+    }
+
+    pub async fn dispatch(
+        &self,
+        node: &str,
+        service: &str,
+        action: &str,
+    ) -> Result<CommandAck, String> {
+        let Some(link) = self.links.lock().await.get(node).cloned() else {
+            return Err(format!("Node '{}' has no active command link", node));
+        };
+        let id = format!("cmd-{}", self.next_id.fetch_add(1, Ordering::Relaxed));
+        let (ack_tx, ack_rx) = oneshot::channel();
+        self.pending.lock().await.insert(id.clone(), ack_tx);
+
+        let cmd = NodeCommand {
+            id: id.clone(),
+            service: service.to_string(),
+            action: action.to_string(),
+        };
+        if link.send(cmd).await.is_err() {
+            self.pending.lock().await.remove(&id);
+            return Err(format!("Command link to '{}' closed", node));
+        }
+
+        let result = tokio::time::timeout(COMMAND_ACK_TIMEOUT, ack_rx).await;
+        self.pending.lock().await.remove(&id);
+        match result {
+            Ok(Ok(ack)) => Ok(ack),
+            Ok(Err(_)) => Err(format!("Command link to '{}' dropped before ack", node)),
+            Err(_) => Err(format!("Timed out waiting for ack from '{}'", node)),
+        }
+    }
+
+    async fn complete(&self, ack: CommandAck) {
+        if let Some(tx) = self.pending.lock().await.remove(&ack.id) {
+            let _ = tx.send(ack);
+        }
+    }
+}
+
+// --- UPSTREAM TARAFI ---
+pub async fn ws_agent_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+    Path(node): Path<String>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_agent_socket(socket, state, node))
+}
+
+async fn handle_agent_socket(mut socket: WebSocket, state: Arc<AppState>, node: String) {
+    let (own, mut rx) = state.agents.register(&node).await;
+    info!(event="AGENT_LINK_UP", node=%node, "🔗 Edge command link established.");
+
+    loop {
+        tokio::select! {
+            cmd = rx.recv() => {
+                let Some(cmd) = cmd else { break };
+                let payload = serde_json::to_string(&cmd).unwrap_or_default();
+                if socket.send(Message::Text(payload)).await.is_err() {
+                    break;
+                }
+            }
+            msg = socket.recv() => {
+                match msg {
+                    // Kanal hem komut ack'lerini hem edge yaşam döngüsü olaylarını taşır.
+                    Some(Ok(Message::Text(text))) => {
+                        if let Ok(ack) = serde_json::from_str::<CommandAck>(&text) {
+                            state.agents.complete(ack).await;
+                        } else if let Ok(event) = serde_json::from_str::<LifecycleEvent>(&text) {
+                            debug!(event="AGENT_LIFECYCLE_EVENT", node=%node, service=%event.service, kind=%event.kind, "Edge lifecycle event received.");
+                            state.tx.send(UiMessage::Lifecycle { data: event }.encode());
+                        } else {
+                            debug!(event="AGENT_FRAME_INVALID", node=%node, "Ignoring malformed agent frame.");
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+
+    state.agents.unregister(&node, &own).await;
+    warn!(event="AGENT_LINK_DOWN", node=%node, "🔌 Edge command link closed.");
+}
+
+// Hedef node upstream'in kendisiyse komut yerelde çalıştırılır.
+pub async fn node_command_handler(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(node): Path<String>,
+    Json(p): Json<NodeCommandParams>,
+) -> Response {
+    if !SUPPORTED_ACTIONS.contains(&p.action.as_str()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("Unsupported action '{}'", p.action),
+        )
+            .into_response();
+    }
+
+    let result = if node == state.config.node_name {
+        let ack = execute_command(
+            &state,
+            NodeCommand {
+                id: "local".to_string(),
+                service: p.service.clone(),
+                action: p.action.clone(),
+            },
+        )
+        .await;
+        Ok(ack)
+    } else {
+        state.agents.dispatch(&node, &p.service, &p.action).await
+    };
+
+    audit(
+        &state,
+        &caller,
+        &format!("remote_{}", p.action),
+        Some(&format!("{}@{}", p.service, node)),
+        &result,
+    )
+    .await;
+    match result {
+        Ok(ack) => Json(ack).into_response(),
+        Err(e) => (StatusCode::BAD_GATEWAY, e).into_response(),
+    }
+}
+
+// --- EDGE TARAFI ---
+async fn execute_command(state: &AppState, cmd: NodeCommand) -> CommandAck {
+    let svc = cmd.service.as_str();
+    let result = match cmd.action.as_str() {
+        "start" => state
+            .docker
+            .start_service(svc)
+            .await
+            .map(|_| "Started".to_string()),
+        "stop" => state
+            .docker
+            .stop_service(svc)
+            .await
+            .map(|_| "Stopped".to_string()),
+        "restart" => state
+            .docker
+            .restart_service(svc)
+            .await
+            .map(|_| "Restarted".to_string()),
+        "update" => update_locked(state, svc).await,
+        other => Err(anyhow::anyhow!("Unsupported action '{}'", other)),
+    };
+
+    // Edge tarafında da denetim kaydı düşülür; kaynak upstream bağlantısıdır.
+    let upstream = Caller {
+        ip: None,
+        scope: Some(Scope::Admin),
+    };
+    audit(
+        state,
+        &upstream,
+        &format!("upstream_{}", cmd.action),
+        Some(svc),
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(message) => CommandAck {
+            id: cmd.id,
+            success: true,
+            message,
+        },
+        Err(e) => CommandAck {
+            id: cmd.id,
+            success: false,
+            message: e.to_string(),
+        },
+    }
+}
+
+// Scanner, auto-pilot ve UI güncellemeleriyle aynı kilit (container adıyla) alınır.
+async fn update_locked(state: &AppState, svc: &str) -> anyhow::Result<String> {
+    let name = container_name(state, svc).await?;
+    if !state.update_locks.lock().await.insert(name.clone()) {
+        return Err(anyhow::anyhow!("Update already in progress"));
+    }
+    let outcome = state.docker.force_update_service(svc, None).await;
+    state.update_locks.lock().await.remove(&name);
+    outcome
+}
+
+// UPSTREAM_ORCHESTRATOR_URL (ingest adresi) üzerinden `/ws/agent/<node>` adresi türetilir.
+pub fn agent_url(upstream_url: &str, node: &str) -> anyhow::Result<String> {
+    let mut url = reqwest::Url::parse(upstream_url)?;
+    let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+    url.set_scheme(scheme)
+        .map_err(|_| anyhow::anyhow!("Cannot derive WebSocket URL from {}", upstream_url))?;
+    url.set_path(&format!("/ws/agent/{}", node));
+    url.set_query(None);
+    Ok(url.to_string())
+}
+
+// Graceful shutdown'da çağrılan `DELETE /api/nodes/<node>` adresi, ingest adresinden türetilir.
+pub fn deregister_url(upstream_url: &str, node: &str) -> anyhow::Result<String> {
+    let mut url = reqwest::Url::parse(upstream_url)?;
+    url.set_path(&format!("/api/nodes/{}", node));
+    url.set_query(None);
+    Ok(url.to_string())
+}
+
+// Bağlantı koparsa üstel bekleme (1s -> 60s) ile yeniden bağlanılır.
+pub async fn run_edge_link(
+    state: Arc<AppState>,
+    url: String,
+    tls: Option<native_tls::TlsConnector>,
+    node_token: Option<String>,
+    upstream_token: Option<String>,
+) {
+    let mut backoff = Duration::from_secs(1);
+    loop {
+        let mut request = match url.as_str().into_client_request() {
+            Ok(r) => r,
+            Err(e) => {
+                warn!(event="UPSTREAM_COMMAND_LINK_DISABLED", error=%e, "⚠️ Invalid upstream command link URL.");
+                return;
+            }
+        };
+        if let Some(value) = node_token
+            .as_deref()
+            .and_then(|t| HeaderValue::from_str(t).ok())
+        {
+            request.headers_mut().insert(NODE_TOKEN_HEADER, value);
+        }
+        // Bearer token URL'de değil header'da taşınır (proxy/erişim loglarına düşmez).
+        if let Some(value) = upstream_token
+            .as_deref()
+            .and_then(|t| HeaderValue::from_str(&format!("Bearer {}", t)).ok())
+        {
+            request.headers_mut().insert(header::AUTHORIZATION, value);
+        }
+        let connector = tls.clone().map(Connector::NativeTls);
+        match tokio_tungstenite::connect_async_tls_with_config(request, None, false, connector)
+            .await
+        {
+            Ok((ws, _)) => {
+                info!(
+                    event = "UPSTREAM_COMMAND_LINK_UP",
+                    "🔗 Connected to upstream command link."
+                );
+                backoff = Duration::from_secs(1);
+                let (mut write, mut read) = ws.split();
+                let (ack_tx, mut ack_rx) = mpsc::unbounded_channel::<CommandAck>();
+                // Bağlantı yokken oluşan olaylar iletilmez; upstream bir sonraki raporla uzlaşır.
+                let mut lifecycle_rx = state.docker.subscribe_lifecycle();
+
+                loop {
+                    tokio::select! {
+                        msg = read.next() => {
+                            let text = match msg {
+                                Some(Ok(WsMessage::Text(text))) => text,
+                                Some(Ok(WsMessage::Close(_))) | Some(Err(_)) | None => break,
+                                Some(Ok(_)) => continue,
+                            };
+                            let Ok(cmd) = serde_json::from_str::<NodeCommand>(&text) else {
+                                debug!(event = "UPSTREAM_COMMAND_INVALID", "Ignoring malformed command.");
+                                continue;
+                            };
+                            info!(event="UPSTREAM_COMMAND_RECEIVED", service=%cmd.service, action=%cmd.action, "📥 Command received from upstream.");
+                            // Uzun süren güncellemeler diğer komutları bekletmesin diye her komut ayrı task'ta çalışır.
+                            let exec_state = state.clone();
+                            let ack_tx = ack_tx.clone();
+                            tokio::spawn(async move {
+                                let _ = ack_tx.send(execute_command(&exec_state, cmd).await);
+                            });
+                        }
+                        ack = ack_rx.recv() => {
+                            let Some(ack) = ack else { break };
+                            let payload = serde_json::to_string(&ack).unwrap_or_default();
+                            if write.send(WsMessage::Text(payload)).await.is_err() {
+                                break;
+                            }
+                        }
+                        event = lifecycle_rx.recv() => {
+                            let event = match event {
+                                Ok(event) => event,
+                                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                    debug!(event = "UPSTREAM_LIFECYCLE_LAGGED", skipped, "Lifecycle events dropped.");
+                                    continue;
+                                }
+                                Err(broadcast::error::RecvError::Closed) => break,
+                            };
+                            let payload = serde_json::to_string(&event).unwrap_or_default();
+                            if write.send(WsMessage::Text(payload)).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                }
+                warn!(
+                    event = "UPSTREAM_COMMAND_LINK_DOWN",
+                    "🔌 Upstream command link lost. Reconnecting..."
+                );
+            }
+            Err(e) => {
+                debug!(event="UPSTREAM_COMMAND_LINK_FAIL", error=%e, backoff_secs=backoff.as_secs(), "Upstream command link unavailable.");
+            }
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,307 @@
+// src/api/auth.rs
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts, Query, Request, State},
+    http::{header, request::Parts, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use tracing::{info_span, warn, Instrument};
+
+use crate::config::AppConfig;
+use crate::AppState;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Scope {
+    Viewer,
+    Admin,
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scope::Viewer => write!(f, "viewer"),
+            Scope::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+// [ARCH-COMPLIANCE]: Okuma uçları (GET, log soketleri) viewer, değiştiren uçlar admin ister.
+pub async fn require_viewer(
+    State(state): State<Arc<AppState>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    authorize(&state.config, Scope::Viewer, req, next).await
+}
+
+pub async fn require_admin(
+    State(state): State<Arc<AppState>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    authorize(&state.config, Scope::Admin, req, next).await
+}
+
+pub const NODE_TOKEN_HEADER: &str = "x-node-token";
+
+// Node raporlarına sahte veri enjekte edilmesini engeller. NODE_AUTH_TOKEN yoksa geriye dönük
+// uyumluluk için admin token'ı (tanımlıysa) istenir.
+pub async fn require_node_token(
+    State(state): State<Arc<AppState>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(expected) = state.config.node_auth_token.as_deref() else {
+        return authorize(&state.config, Scope::Admin, req, next).await;
+    };
+    let presented = req
+        .headers()
+        .get(NODE_TOKEN_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if !constant_time_eq(presented.as_bytes(), expected.as_bytes()) {
+        warn!(event="NODE_AUTH_REJECTED", path=%req.uri().path(), "🔒 Rejected node request without a valid x-node-token.");
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(
+                json!({ "error": "unauthenticated", "message": "Missing or invalid x-node-token" }),
+            ),
+        )
+            .into_response();
+    }
+    next.run(req).await
+}
+
+// Hiç token tanımlı değilse (yerel kurulum) kontrol atlanır.
+async fn authorize(cfg: &AppConfig, required: Scope, mut req: Request, next: Next) -> Response {
+    if cfg.api_token.is_none() && cfg.admin_token.is_none() && cfg.viewer_token.is_none() {
+        return next.run(req).await;
+    }
+
+    let path = req.uri().path().to_string();
+    let Some(scope) = presented_token(&req).and_then(|t| resolve_scope(cfg, &t)) else {
+        warn!(event="API_AUTH_REJECTED", path=%path, "🔒 Rejected request without a valid API token.");
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "unauthorized", "message": "Missing or invalid bearer token" })),
+        )
+            .into_response();
+    };
+
+    if scope < required {
+        warn!(event="API_AUTH_FORBIDDEN", path=%path, scope=%scope, required=%required, "⛔ Token scope is insufficient for this action.");
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({ "error": "forbidden", "message": format!("'{}' scope required", required) })),
+        )
+            .into_response();
+    }
+
+    req.extensions_mut().insert(scope);
+    next.run(req)
+        .instrument(info_span!("auth", scope = %scope))
+        .await
+}
+
+// Denetim kaydı için isteği yapanın IP'si ve (auth etkinse) çözümlenen yetki kapsamı.
+#[derive(Clone, Debug)]
+pub struct Caller {
+    pub ip: Option<String>,
+    pub scope: Option<Scope>,
+}
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for Caller {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|c| c.0.ip());
+        Ok(Self {
+            ip: client_ip(parts, peer, &state.config.trusted_proxies),
+            scope: parts.extensions.get::<Scope>().copied(),
+        })
+    }
+}
+
+// Reverse proxy arkasında gerçek istemci X-Forwarded-For'dadır; ancak başlık herkes tarafından
+// yazılabildiği için sadece TRUSTED_PROXIES'teki bir eşten geldiğinde okunur. Zincir sağdan
+// yürünür ve güvenilir olmayan ilk adres istemci sayılır. Eş adresi yoksa (unix socket) `unix` girdisi aranır.
+fn client_ip(parts: &Parts, peer: Option<IpAddr>, trusted: &[String]) -> Option<String> {
+    let is_trusted = |ip: IpAddr| trusted.iter().any(|t| ip_matches(t, ip));
+    let peer_trusted = match peer {
+        Some(ip) => is_trusted(ip),
+        None => trusted.iter().any(|t| t == "unix"),
+    };
+    if peer_trusted {
+        let forwarded: Vec<IpAddr> = parts
+            .headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|v| v.trim().parse().ok())
+            .collect();
+        if let Some(ip) = forwarded
+            .iter()
+            .rev()
+            .find(|ip| !is_trusted(**ip))
+            .or(forwarded.first())
+        {
+            return Some(ip.to_string());
+        }
+    }
+    peer.map(|ip| ip.to_string())
+}
+
+// `10.0.0.1` veya `10.0.0.0/8` biçimi; geçersiz girdi hiçbir adresle eşleşmez.
+fn ip_matches(entry: &str, ip: IpAddr) -> bool {
+    let (addr, prefix) = match entry.split_once('/') {
+        Some((a, p)) => (a, p.parse::<u32>().ok()),
+        None => (entry, None),
+    };
+    let Ok(net) = addr.parse::<IpAddr>() else {
+        return false;
+    };
+    match (net, ip) {
+        (IpAddr::V4(n), IpAddr::V4(i)) => {
+            let bits = prefix.unwrap_or(32).min(32);
+            let mask = u32::MAX.checked_shl(32 - bits).unwrap_or(0);
+            u32::from(n) & mask == u32::from(i) & mask
+        }
+        (IpAddr::V6(n), IpAddr::V6(i)) => {
+            let bits = prefix.unwrap_or(128).min(128);
+            let mask = u128::MAX.checked_shl(128 - bits).unwrap_or(0);
+            u128::from(n) & mask == u128::from(i) & mask
+        }
+        _ => false,
+    }
+}
+
+// API_TOKEN geriye dönük uyumluluk için admin yetkisi taşır.
+fn resolve_scope(cfg: &AppConfig, token: &str) -> Option<Scope> {
+    let matches = |expected: &Option<String>| {
+        expected
+            .as_deref()
+            .is_some_and(|e| constant_time_eq(token.as_bytes(), e.as_bytes()))
+    };
+    if matches(&cfg.admin_token) || matches(&cfg.api_token) {
+        Some(Scope::Admin)
+    } else if matches(&cfg.viewer_token) {
+        Some(Scope::Viewer)
+    } else {
+        None
+    }
+}
+
+// Tarayıcı WebSocket'i ve EventSource (SSE) header gönderemediği için `?token=` sadece bu isteklerde
+// kabul edilir; diğer uçlarda token URL'de (proxy/erişim loglarında) taşınmaz. Değer URL-decode edilir.
+fn presented_token(req: &Request) -> Option<String> {
+    if let Some(token) = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+    {
+        return Some(token.trim().to_string());
+    }
+    if !accepts_query_token(req) {
+        return None;
+    }
+    Query::<TokenQuery>::try_from_uri(req.uri()).ok()?.0.token
+}
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+fn accepts_query_token(req: &Request) -> bool {
+    let has = |name: header::HeaderName, value: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.to_ascii_lowercase().contains(value))
+    };
+    has(header::UPGRADE, "websocket") || has(header::ACCEPT, "text/event-stream")
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request as HttpRequest;
+
+    fn parts(xff: &str) -> Parts {
+        HttpRequest::builder()
+            .header("x-forwarded-for", xff)
+            .body(())
+            .unwrap()
+            .into_parts()
+            .0
+    }
+
+    #[test]
+    fn forwarded_for_ignored_from_untrusted_peer() {
+        let peer = Some("203.0.113.7".parse().unwrap());
+        assert_eq!(
+            client_ip(&parts("1.2.3.4"), peer, &[]),
+            Some("203.0.113.7".to_string())
+        );
+    }
+
+    #[test]
+    fn forwarded_for_walks_chain_from_trusted_proxy() {
+        let trusted = vec!["10.0.0.0/8".to_string()];
+        let peer = Some("10.1.2.3".parse().unwrap());
+        // İstemcinin sahte girdisi (en soldaki) değil, son güvenilmeyen atlama esas alınır.
+        assert_eq!(
+            client_ip(&parts("9.9.9.9, 198.51.100.4, 10.0.0.5"), peer, &trusted),
+            Some("198.51.100.4".to_string())
+        );
+    }
+
+    #[test]
+    fn cidr_matching() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+        assert!(ip_matches("192.168.0.0/16", ip("192.168.4.1")));
+        assert!(!ip_matches("192.168.0.0/16", ip("192.169.0.1")));
+        assert!(ip_matches("127.0.0.1", ip("127.0.0.1")));
+        assert!(ip_matches("::1", ip("::1")));
+        assert!(!ip_matches("not-an-ip", ip("127.0.0.1")));
+    }
+
+    #[test]
+    fn query_token_only_on_streaming_requests_and_decoded() {
+        let req = |upgrade: Option<&str>| {
+            let mut b = HttpRequest::builder().uri("/ws/logs/web?tail=10&token=a%2Bb%26c");
+            if let Some(u) = upgrade {
+                b = b.header(header::UPGRADE, u);
+            }
+            b.body(axum::body::Body::empty()).unwrap()
+        };
+        assert_eq!(presented_token(&req(None)), None);
+        assert_eq!(
+            presented_token(&req(Some("websocket"))),
+            Some("a+b&c".to_string())
+        );
+    }
+}
@@ -1 +1,287 @@
-// Planlı
+// src/api/grpc.rs
+// [ARCH-COMPLIANCE]: GRPC_PORT üzerinde standart gRPC sağlık protokolü (grpc.health.v1).
+// Durum `/readyz` ile aynı hazır olma mantığından türetilir; Docker erişilemezse NOT_SERVING döner.
+// GRPC_REFLECTION=true ise aynı portta grpc.reflection.v1alpha da sunulur (tonic-reflection).
+// TLS_CERT/TLS_KEY tanımlıysa port HTTP sunucusu gibi TLS ile açılır.
+use anyhow::Context;
+use futures_util::Stream;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::server::NamedService;
+use tonic::transport::{Certificate, Identity, ServerTlsConfig};
+use tonic::{Request, Response, Status};
+use tracing::{error, info};
+
+use crate::api::routes::probe;
+use crate::config::AppConfig;
+use crate::AppState;
+
+pub mod health {
+    tonic::include_proto!("grpc.health.v1");
+}
+
+// build.rs'in ürettiği, GRPC_PORT'ta sunulan servislerin descriptor seti. Reflection servisinin
+// kendi descriptor'larını tonic-reflection ekler.
+const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/grpc_descriptor.bin"));
+
+use health::health_check_response::ServingStatus;
+use health::health_server::{Health, HealthServer};
+use health::{HealthCheckRequest, HealthCheckResponse};
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+const REFLECTION_SERVICE: &str = "grpc.reflection.v1alpha.ServerReflection";
+
+pub struct HealthService {
+    state: Arc<AppState>,
+    // Reflection servisi gerçekten yüklendiyse true (GRPC_REFLECTION tek başına yetmez)
+    reflection: bool,
+}
+
+impl HealthService {
+    async fn status(&self) -> ServingStatus {
+        let (_, ready, _) = probe(&self.state).await;
+        if ready {
+            ServingStatus::Serving
+        } else {
+            ServingStatus::NotServing
+        }
+    }
+
+    fn known(&self, service: &str) -> bool {
+        is_served(service, self.reflection)
+    }
+}
+
+// Boş servis adı sunucunun genel durumunu sorar; bunun dışında sadece GRPC_PORT'ta gerçekten
+// sunulan servisler tanınır.
+fn is_served(service: &str, reflection: bool) -> bool {
+    service.is_empty()
+        || service == <HealthServer<HealthService> as NamedService>::NAME
+        || (reflection && service == REFLECTION_SERVICE)
+}
+
+#[tonic::async_trait]
+impl Health for HealthService {
+    async fn check(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        let service = request.into_inner().service;
+        if !self.known(&service) {
+            return Err(Status::not_found(format!("Unknown service '{}'", service)));
+        }
+        Ok(Response::new(HealthCheckResponse {
+            status: self.status().await as i32,
+        }))
+    }
+
+    type WatchStream = Pin<Box<dyn Stream<Item = Result<HealthCheckResponse, Status>> + Send>>;
+
+    // Protokol gereği ilk durum hemen, sonrakiler sadece değiştiğinde gönderilir.
+    async fn watch(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        let known = self.known(&request.into_inner().service);
+        let svc = HealthService {
+            state: self.state.clone(),
+            reflection: self.reflection,
+        };
+        let stream = futures_util::stream::unfold(
+            (svc, None::<ServingStatus>),
+            move |(svc, last)| async move {
+                loop {
+                    let status = if known {
+                        svc.status().await
+                    } else {
+                        ServingStatus::ServiceUnknown
+                    };
+                    if last != Some(status) {
+                        let item = Ok(HealthCheckResponse {
+                            status: status as i32,
+                        });
+                        return Some((item, (svc, Some(status))));
+                    }
+                    tokio::time::sleep(WATCH_INTERVAL).await;
+                }
+            },
+        );
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+fn reflection_service() -> Result<
+    tonic_reflection::pb::v1alpha::server_reflection_server::ServerReflectionServer<
+        impl tonic_reflection::server::v1alpha::ServerReflection,
+    >,
+    tonic_reflection::server::Error,
+> {
+    tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+        .build_v1alpha()
+}
+
+// TLS_CERT/TLS_KEY HTTP sunucusuyla paylaşılır; TLS_CLIENT_CA verilirse istemci sertifikası zorunludur.
+fn tls_config(cfg: &AppConfig) -> anyhow::Result<Option<ServerTlsConfig>> {
+    let (Some(cert), Some(key)) = (&cfg.tls_cert, &cfg.tls_key) else {
+        return Ok(None);
+    };
+    let identity = Identity::from_pem(
+        std::fs::read(cert).with_context(|| format!("TLS_CERT '{}'", cert))?,
+        std::fs::read(key).with_context(|| format!("TLS_KEY '{}'", key))?,
+    );
+    let mut tls = ServerTlsConfig::new().identity(identity);
+    if let Some(ca) = &cfg.tls_client_ca {
+        let ca = std::fs::read(ca).with_context(|| format!("TLS_CLIENT_CA '{}'", ca))?;
+        tls = tls.client_ca_root(Certificate::from_pem(ca));
+    }
+    Ok(Some(tls))
+}
+
+// HTTP sunucusundan bağımsız çalışır; port açılamazsa sadece loglanır, HTTP API etkilenmez.
+// TLS yapılandırması okunamazsa düz metne düşülmez, gRPC sunucusu hiç açılmaz.
+pub async fn serve(state: Arc<AppState>, host: String, port: u16) {
+    let addr = match format!("{}:{}", host, port).parse() {
+        Ok(addr) => addr,
+        Err(e) => {
+            error!(event="GRPC_HEALTH_DISABLED", error=%e, "❌ Invalid gRPC listen address.");
+            return;
+        }
+    };
+    let reflection = if state.config.grpc_reflection {
+        match reflection_service() {
+            Ok(svc) => Some(svc),
+            Err(e) => {
+                error!(event="GRPC_REFLECTION_DISABLED", error=%e, "❌ gRPC reflection descriptors could not be loaded.");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let mut builder = tonic::transport::Server::builder();
+    let tls = match tls_config(&state.config) {
+        Ok(tls) => tls,
+        Err(e) => {
+            error!(event="GRPC_HEALTH_DISABLED", error=%e, "❌ gRPC TLS configuration could not be loaded.");
+            return;
+        }
+    };
+    let secure = tls.is_some();
+    if let Some(tls) = tls {
+        builder = match builder.tls_config(tls) {
+            Ok(b) => b,
+            Err(e) => {
+                error!(event="GRPC_HEALTH_DISABLED", error=%e, "❌ gRPC TLS configuration rejected.");
+                return;
+            }
+        };
+    }
+    info!(event="GRPC_HEALTH_LISTENING", %addr, tls = secure, reflection = reflection.is_some(), "🩺 gRPC health service listening on {}", addr);
+    let health = HealthService {
+        state,
+        reflection: reflection.is_some(),
+    };
+    if let Err(e) = builder
+        .add_service(HealthServer::new(health))
+        .add_optional_service(reflection)
+        .serve(addr)
+        .await
+    {
+        error!(event="GRPC_HEALTH_FAIL", error=%e, "❌ gRPC health server stopped.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::transport::server::TcpIncoming;
+    use tonic_reflection::pb::v1alpha::server_reflection_client::ServerReflectionClient;
+    use tonic_reflection::pb::v1alpha::server_reflection_request::MessageRequest;
+    use tonic_reflection::pb::v1alpha::server_reflection_response::MessageResponse;
+    use tonic_reflection::pb::v1alpha::ServerReflectionRequest;
+
+    // Bilinmeyen sembolde tonic-reflection ErrorResponse yerine akışı NotFound ile sonlandırır.
+    async fn ask(requests: Vec<MessageRequest>) -> Vec<Result<MessageResponse, Status>> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(reflection_service().unwrap())
+                .serve_with_incoming(incoming),
+        );
+        let channel = tonic::transport::Endpoint::from_shared(format!("http://{}", addr))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        let mut client = ServerReflectionClient::new(channel);
+        // NotFound akışı kapattığı için her istek ayrı akışta sorulur.
+        let mut out = Vec::new();
+        for req in requests {
+            let outbound = futures_util::stream::iter([ServerReflectionRequest {
+                host: String::new(),
+                message_request: Some(req),
+            }]);
+            let mut inbound = client
+                .server_reflection_info(outbound)
+                .await
+                .unwrap()
+                .into_inner();
+            out.push(
+                inbound
+                    .message()
+                    .await
+                    .map(|resp| resp.and_then(|r| r.message_response).expect("message")),
+            );
+        }
+        out
+    }
+
+    #[test]
+    fn health_knows_only_served_services() {
+        assert!(is_served("", false));
+        assert!(is_served("grpc.health.v1.Health", false));
+        assert!(!is_served(REFLECTION_SERVICE, false));
+        assert!(is_served(REFLECTION_SERVICE, true));
+        assert!(!is_served(
+            "sentiric.orchestrator.v1.OrchestratorService",
+            true
+        ));
+    }
+
+    #[tokio::test]
+    async fn reflection_lists_and_resolves_served_services() {
+        let responses = ask(vec![
+            MessageRequest::ListServices(String::new()),
+            MessageRequest::FileContainingSymbol("grpc.health.v1.Health.Check".into()),
+            MessageRequest::FileContainingSymbol("no.Such".into()),
+            MessageRequest::FileContainingSymbol(
+                "sentiric.orchestrator.v1.OrchestratorService.ReportClusterState".into(),
+            ),
+        ])
+        .await;
+        let Ok(MessageResponse::ListServicesResponse(list)) = &responses[0] else {
+            panic!("expected service list");
+        };
+        let names: Vec<&str> = list.service.iter().map(|s| s.name.as_str()).collect();
+        assert!(names.contains(&"grpc.health.v1.Health"));
+        assert!(names.contains(&"grpc.reflection.v1alpha.ServerReflection"));
+        // Sözleşme sadece dokümantasyon; sunulmayan servis yansıtmada da görünmez.
+        assert!(!names.iter().any(|n| n.starts_with("sentiric.orchestrator")));
+        assert!(matches!(
+            responses[1],
+            Ok(MessageResponse::FileDescriptorResponse(_))
+        ));
+        assert_eq!(
+            responses[2].as_ref().unwrap_err().code(),
+            tonic::Code::NotFound
+        );
+        assert_eq!(
+            responses[3].as_ref().unwrap_err().code(),
+            tonic::Code::NotFound
+        );
+    }
+}
@@ -0,0 +1,187 @@
+// src/api/metrics.rs
+use std::fmt::Write;
+
+use crate::core::domain::{ClusterMap, ClusterReport, ServiceInstance};
+
+// [ARCH-COMPLIANCE]: Ağır bir framework yerine mevcut cache'ler üzerinden Prometheus text formatı üretilir.
+pub fn render(cluster: &ClusterMap) -> String {
+    let mut nodes: Vec<&ClusterReport> = cluster.values().map(|r| &**r).collect();
+    nodes.sort_by(|a, b| a.node.cmp(&b.node));
+
+    let mut out = String::new();
+
+    gauge(
+        &mut out,
+        "sentiric_node_cpu_usage",
+        "Node CPU usage percent",
+        &nodes,
+        |r| r.stats.cpu_usage as f64,
+    );
+    gauge(
+        &mut out,
+        "sentiric_node_ram_used_mb",
+        "Node RAM used in MB",
+        &nodes,
+        |r| r.stats.ram_used as f64,
+    );
+    gauge(
+        &mut out,
+        "sentiric_node_ram_total_mb",
+        "Node RAM total in MB",
+        &nodes,
+        |r| r.stats.ram_total as f64,
+    );
+    gauge(
+        &mut out,
+        "sentiric_node_disk_used_gb",
+        "Node disk used in GB",
+        &nodes,
+        |r| r.stats.disk_used as f64,
+    );
+    gauge(
+        &mut out,
+        "sentiric_gpu_usage",
+        "Node GPU usage percent",
+        &nodes,
+        |r| r.stats.gpu_usage as f64,
+    );
+    gauge(
+        &mut out,
+        "sentiric_gpu_mem_used_mb",
+        "Node GPU memory used in MB",
+        &nodes,
+        |r| r.stats.gpu_mem_used as f64,
+    );
+    gauge(
+        &mut out,
+        "sentiric_node_health_score",
+        "Node health score (0-100)",
+        &nodes,
+        |r| r.stats.health_score as f64,
+    );
+    gauge(
+        &mut out,
+        "sentiric_node_online",
+        "1 if node status is ONLINE",
+        &nodes,
+        |r| (r.stats.status == "ONLINE") as u8 as f64,
+    );
+
+    service_gauge(
+        &mut out,
+        "sentiric_service_up",
+        "1 if the container is running",
+        &nodes,
+        |svc| svc.status.to_lowercase().contains("up") as u8 as f64,
+    );
+    service_gauge(
+        &mut out,
+        "sentiric_service_cpu_usage",
+        "Container CPU usage percent",
+        &nodes,
+        |svc| svc.cpu_usage,
+    );
+    service_gauge(
+        &mut out,
+        "sentiric_service_mem_usage_mb",
+        "Container memory usage in MB",
+        &nodes,
+        |svc| svc.mem_usage as f64,
+    );
+
+    out
+}
+
+pub struct BroadcastMetrics {
+    pub capacity: usize,
+    pub queued: usize,
+    pub receivers: usize,
+    pub lagged_messages: u64,
+    pub lag_events: u64,
+}
+
+// UI yayın kanalının doluluğu ve yavaş istemcilerin kaçırdığı mesajlar (BROADCAST_CAPACITY ayarı için)
+pub fn render_broadcast(out: &mut String, m: &BroadcastMetrics) {
+    for (name, help, kind, value) in [
+        (
+            "sentiric_broadcast_capacity",
+            "Broadcast channel capacity in messages",
+            "gauge",
+            m.capacity as u64,
+        ),
+        (
+            "sentiric_broadcast_queued",
+            "Messages retained for the slowest subscriber",
+            "gauge",
+            m.queued as u64,
+        ),
+        (
+            "sentiric_broadcast_receivers",
+            "Active WebSocket/SSE subscribers",
+            "gauge",
+            m.receivers as u64,
+        ),
+        (
+            "sentiric_broadcast_lagged_messages_total",
+            "Messages skipped by lagging subscribers",
+            "counter",
+            m.lagged_messages,
+        ),
+        (
+            "sentiric_broadcast_lag_events_total",
+            "Times a subscriber fell behind and was resynced",
+            "counter",
+            m.lag_events,
+        ),
+    ] {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        let _ = writeln!(out, "{} {}", name, value);
+    }
+}
+
+fn header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+}
+
+fn gauge(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    nodes: &[&ClusterReport],
+    value: impl Fn(&ClusterReport) -> f64,
+) {
+    header(out, name, help);
+    for r in nodes {
+        let _ = writeln!(out, "{}{{node=\"{}\"}} {}", name, escape(&r.node), value(r));
+    }
+}
+
+fn service_gauge(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    nodes: &[&ClusterReport],
+    value: impl Fn(&ServiceInstance) -> f64,
+) {
+    header(out, name, help);
+    for r in nodes {
+        for svc in &r.services {
+            let _ = writeln!(
+                out,
+                "{}{{name=\"{}\",node=\"{}\"}} {}",
+                name,
+                escape(&svc.name),
+                escape(&r.node),
+                value(svc)
+            );
+        }
+    }
+}
+
+fn escape(v: &str) -> String {
+    v.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
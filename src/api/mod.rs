@@ -1,2 +1,9 @@
+pub mod agent;
+pub mod auth;
 pub mod grpc;
-pub mod routes; // Mevcut gRPC kodlarını buraya taşıyabiliriz (basitlik için şimdilik boş bırakıyorum)
+pub mod metrics;
+pub mod ratelimit;
+pub mod routes;
+pub mod tls;
+#[cfg(unix)]
+pub mod uds;
@@ -0,0 +1,95 @@
+// src/api/ratelimit.rs
+use axum::{
+    extract::{Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::warn;
+
+use crate::api::auth::Caller;
+use crate::AppState;
+
+// Bu sayının üstünde dolu (boşta) kovalar atılır; bellek istemci sayısıyla sınırsız büyümez.
+const MAX_TRACKED_CLIENTS: usize = 1024;
+
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+/// İstemci IP'si başına token bucket: dakikada `per_min` istek, aynı miktarda anlık patlama.
+pub struct RateLimiter {
+    per_min: u32,
+    buckets: std::sync::Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(per_min: u32) -> Self {
+        Self {
+            per_min,
+            buckets: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    // İzin yoksa bir sonraki token için beklenecek süre (saniye) döner.
+    fn check(&self, client: &str) -> Result<(), u64> {
+        if self.per_min == 0 {
+            return Ok(());
+        }
+        let capacity = self.per_min as f64;
+        let refill_per_sec = capacity / 60.0;
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(client) {
+            buckets.retain(|_, b| {
+                b.tokens + now.duration_since(b.last).as_secs_f64() * refill_per_sec < capacity
+            });
+        }
+
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            last: now,
+        });
+        let elapsed = now.duration_since(bucket.last).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(capacity);
+        bucket.last = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - bucket.tokens) / refill_per_sec).ceil() as u64)
+        }
+    }
+}
+
+// [ARCH-COMPLIANCE]: Sadece değiştiren (POST/PATCH/DELETE) uçlar sınırlanır; okuma ve WebSocket
+// upgrade'leri (GET) muaftır. RATE_LIMIT_PER_MIN=0 ise kapalıdır.
+pub async fn limit_mutations(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    req: Request,
+    next: Next,
+) -> Response {
+    if matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(req).await;
+    }
+    let client = caller.ip.unwrap_or_else(|| "unknown".to_string());
+    if let Err(retry_after) = state.rate_limiter.check(&client) {
+        warn!(event="API_RATE_LIMITED", client=%client, path=%req.uri().path(), retry_after_secs=retry_after, "🚦 Rate limit exceeded.");
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after.to_string())],
+            Json(json!({ "error": "rate_limited", "message": format!("Too many requests, retry in {}s", retry_after) })),
+        )
+            .into_response();
+    }
+    next.run(req).await
+}
@@ -1,64 +1,295 @@
 // src/api/routes.rs
 use axum::{
+    body::Body,
     extract::{
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
         Path, Query, State,
     },
-    http::StatusCode,
-    response::{Html, IntoResponse, Response},
-    routing::{get, post},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse, Response,
+    },
+    routing::{delete, get, patch, post},
     Json, Router,
 };
 use futures_util::StreamExt;
+use std::convert::Infallible;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::services::ServeDir;
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{debug, info, warn};
 
+use crate::adapters::audit::AuditEntry;
+use crate::adapters::autopilot_store::AutoPilotStore;
+use crate::adapters::docker::{DockerAdapter, PruneBusy, PullError, PullErrorKind};
+use crate::api::auth::Caller;
+use crate::api::{agent, auth, metrics, ratelimit};
 use crate::core::domain::{
-    ActionParams, ClusterReport, ServiceInstance, ToggleParams, TopologyEdge, TopologyMap,
-    TopologyNode,
+    ActionParams, BatchUpdateResult, BulkActionParams, ClusterMap, ClusterReport, ClusterSummary,
+    CreateServiceResult, DiagnosticReport, DiagnosticService, EnvPatchParams, ExecParams,
+    ExportParams, HealthStatus, InspectParams, LogDownloadParams, LogStreamParams,
+    NodeHistoryParams, NodeListParams, NodeStats, NodeStatusChange, Page, PendingUpdates,
+    PruneParams, ServiceGroup, ServiceInstance, ServicePlacement, ServiceSpec, ServicesSnapshot,
+    StatusFilterParams, StoredHistoryParams, ToggleParams, TopologyEdge, TopologyMap, TopologyNode,
+    WsMessage,
 };
+use crate::core::governor::{DependencyCycle, Governor};
+use crate::core::node_history::NodeHistory;
 use crate::AppState;
+use regex::Regex;
 use serde_json::json;
 
 const UI_ASSETS_PATH: &str = "src/ui";
+// Toplu güncellemede Docker daemon ve ağı boğmamak için eşzamanlılık sınırı
+const BATCH_UPDATE_CONCURRENCY: usize = 3;
+// Toplu start/stop/restart hafif olduğu için daha geniş paralellikle yürütülür.
+const BULK_ACTION_CONCURRENCY: usize = 8;
+// `/api/status` sayfalaması: sayfalama bilmeyen istemciler için varsayılan cömert tutulur.
+const STATUS_PAGE_DEFAULT_LIMIT: usize = 100;
+const STATUS_PAGE_MAX_LIMIT: usize = 1000;
 
 pub fn create_router(state: Arc<AppState>) -> Router {
-    Router::new()
-        .route("/", get(index_handler))
-        .nest_service("/ui", ServeDir::new(UI_ASSETS_PATH))
+    // [ARCH-COMPLIANCE]: Her rota gerektirdiği yetki kapsamıyla etiketlenir.
+    let viewer_routes = Router::new()
         .route("/ws", get(ws_handler))
+        .route("/sse", get(sse_handler))
         .route("/ws/logs/:id", get(ws_logs_handler))
+        .route("/ws/stats/:id", get(ws_stats_handler))
         .route("/api/config", get(get_system_config))
+        .route("/api/version", get(version_handler))
         .route("/api/status", get(status_handler))
+        .route("/api/nodes", get(nodes_handler))
+        .route("/api/cluster/summary", get(cluster_summary_handler))
+        .route("/api/nodes/:name/history", get(node_history_handler))
+        .route("/api/history/nodes", get(stored_node_history_handler))
+        .route("/api/services/grouped", get(grouped_services_handler))
         .route("/api/topology", get(topology_handler))
+        .route("/api/images", get(images_handler))
+        .route("/api/autopilot/pending", get(pending_updates_handler))
+        .route("/api/service/:id/inspect", get(inspect_handler))
+        .route("/api/service/:id/env", get(env_handler))
+        .route("/api/service/:id/history", get(history_handler))
+        .route("/api/service/:id/logs/download", get(logs_download_handler))
+        .route("/api/export/llm", get(export_llm_handler))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::require_viewer,
+        ));
+
+    // Edge node'ların kullandığı uçlar ayrıca NODE_AUTH_TOKEN (x-node-token) ister.
+    let node_routes = Router::new()
+        .route("/api/ingest/report", post(ingest_report_handler))
+        .route("/ws/agent/:node", get(agent::ws_agent_handler))
+        .route("/api/nodes/:name", delete(deregister_node_handler))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::require_node_token,
+        ));
+
+    let admin_routes = Router::new()
+        .route("/ws/update/:id", get(ws_update_handler))
         .route("/api/update", post(update_handler))
+        .route("/api/update-all", post(update_all_handler))
+        .route("/api/services/action", post(bulk_action_handler))
         .route("/api/toggle-autopilot", post(toggle_handler))
+        .route("/api/service/create", post(create_service_handler))
         .route("/api/service/:id/start", post(start_handler))
         .route("/api/service/:id/stop", post(stop_handler))
         .route("/api/service/:id/restart", post(restart_handler))
-        .route("/api/service/:id/inspect", get(inspect_handler))
+        .route("/api/service/:id/pause", post(pause_handler))
+        .route("/api/service/:id/unpause", post(unpause_handler))
+        .route("/api/service/:id/env", patch(env_patch_handler))
+        .route("/api/service/:id/exec", post(exec_handler))
         .route("/api/system/prune", post(prune_handler))
-        .route("/api/system/self-update", post(self_update_handler)) // <--- BURA EKLENECEK
-        .route("/api/export/llm", get(export_llm_handler))
-        .route("/api/ingest/report", post(ingest_report_handler))
+        .route("/api/system/self-update", post(self_update_handler))
+        .route(
+            "/api/nodes/:name/command",
+            post(agent::node_command_handler),
+        )
+        // Edge raporları (node_routes) sınır dışıdır; aksi halde büyük filolarda ingest düşer.
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            ratelimit::limit_mutations,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::require_admin,
+        ));
+
+    let cors = cors_layer(&state.config.cors_allowed_origins);
+    let router = Router::new()
+        .route("/", get(index_handler))
+        .nest_service("/ui", ServeDir::new(UI_ASSETS_PATH))
+        .route("/metrics", get(metrics_handler))
+        .route("/healthz", get(healthz_handler))
+        .route("/readyz", get(readyz_handler))
+        .merge(viewer_routes)
+        .merge(admin_routes)
+        // Edge uçları admin katmanının dışındadır: edge'ler admin token'ı değil x-node-token taşır.
+        .merge(node_routes)
         .with_state(state)
-        .layer(TraceLayer::new_for_http())
+        // /api/status, export/llm, log indirme ve statik UI gzip/br ile sıkıştırılır. Varsayılan
+        // predicate SSE'yi (text/event-stream) ve küçük yanıtları dışarıda bırakır; WS etkilenmez.
+        .layer(CompressionLayer::new())
+        .layer(TraceLayer::new_for_http());
+    // Varsayılan: CORS başlığı yok (sadece aynı origin). Preflight OPTIONS'ı katman yanıtlar.
+    match cors {
+        Some(cors) => router.layer(cors),
+        None => router,
+    }
 }
 
-async fn get_system_config(State(_state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+fn cors_layer(origins: &[String]) -> Option<CorsLayer> {
+    if origins.is_empty() {
+        return None;
+    }
+    let allow_origin = if origins.iter().any(|o| o == "*") {
+        AllowOrigin::any()
+    } else {
+        let parsed: Vec<HeaderValue> = origins
+            .iter()
+            .filter_map(|o| match HeaderValue::from_str(o) {
+                Ok(v) => Some(v),
+                Err(_) => {
+                    warn!(event="CORS_ORIGIN_INVALID", origin=%o, "⚠️ Ignoring invalid CORS origin.");
+                    None
+                }
+            })
+            .collect();
+        AllowOrigin::list(parsed)
+    };
+    info!(event="CORS_ENABLED", origins=%origins.join(","), "🌐 CORS enabled.");
+    Some(
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods([
+                Method::GET,
+                Method::POST,
+                Method::PATCH,
+                Method::DELETE,
+                Method::OPTIONS,
+            ])
+            .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
+            .max_age(Duration::from_secs(600)),
+    )
+}
+
+async fn get_system_config(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
     let version = env!("CARGO_PKG_VERSION");
     let node_name = hostname::get()
         .map(|h| h.to_string_lossy().into_owned())
         .unwrap_or("unknown".into());
     Json(json!({
         "version": version,
+        "cluster_name": state.config.cluster_name,
         "node_name": node_name,
-        "is_upstream_enabled": !std::env::var("UPSTREAM_ORCHESTRATOR_URL").unwrap_or_default().is_empty(),
+        "is_upstream_enabled": state.config.upstream_url.is_some(),
+    }))
+}
+
+async fn version_handler(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    Json(json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "cluster_name": state.config.cluster_name,
+        "node_name": state.config.node_name,
+        "docker": state.docker.daemon_info(),
     }))
 }
 
+async fn metrics_handler(State(state): State<Arc<AppState>>) -> Response {
+    let mut body = metrics::render(&*state.cluster_cache.lock().await);
+    metrics::render_broadcast(
+        &mut body,
+        &metrics::BroadcastMetrics {
+            capacity: state.config.broadcast_capacity,
+            queued: state.tx.queued(),
+            receivers: state.tx.receiver_count(),
+            lagged_messages: state.broadcast_lagged.load(Ordering::Relaxed),
+            lag_events: state.broadcast_lag_events.load(Ordering::Relaxed),
+        },
+    );
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        body,
+    )
+        .into_response()
+}
+
+// Probe'lar auth istemez. Arka plan döngüleri son çalışma zamanlarından izlenir:
+// monitor her turda `last_seen`, tarayıcı her başarılı taramada `last_scan` yazar.
+const PROBE_DOCKER_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub(crate) async fn probe(state: &AppState) -> (bool, bool, serde_json::Value) {
+    let docker_up = tokio::time::timeout(PROBE_DOCKER_TIMEOUT, state.docker.ping())
+        .await
+        .is_ok_and(|r| r.is_ok());
+    // Tek bir yavaş tur alarm üretmesin diye birkaç tur tolerans tanınır.
+    let stall_secs = (3 * state.config.poll_interval as i64).max(30);
+    let task_status = |ts: Option<&str>| -> &'static str {
+        match ts.and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok()) {
+            None => "starting",
+            Some(t)
+                if (chrono::Utc::now() - t.with_timezone(&chrono::Utc)).num_seconds()
+                    > stall_secs =>
+            {
+                "stalled"
+            }
+            Some(_) => "running",
+        }
+    };
+    let last_seen = state.node_stats_cache.lock().await.last_seen.clone();
+    let monitor = task_status(
+        Some(&last_seen)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.as_str()),
+    );
+    let scanner = task_status(state.last_scan.lock().await.as_deref());
+
+    let live = docker_up && monitor == "running" && scanner != "stalled";
+    let ready = live && scanner == "running";
+    (
+        live,
+        ready,
+        json!({
+            "docker": if docker_up { "up" } else { "down" },
+            "monitor": monitor,
+            "scanner": scanner,
+        }),
+    )
+}
+
+fn probe_response(ok: bool, components: serde_json::Value) -> Response {
+    let code = if ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        code,
+        Json(json!({ "status": if ok { "ok" } else { "fail" }, "components": components })),
+    )
+        .into_response()
+}
+
+async fn healthz_handler(State(state): State<Arc<AppState>>) -> Response {
+    let (live, _, components) = probe(&state).await;
+    probe_response(live, components)
+}
+
+// Hazır olmak için en az bir container taramasının tamamlanmış olması gerekir.
+async fn readyz_handler(State(state): State<Arc<AppState>>) -> Response {
+    let (_, ready, components) = probe(&state).await;
+    probe_response(ready, components)
+}
+
 async fn index_handler() -> impl IntoResponse {
     match std::fs::read_to_string(format!("{}/index.html", UI_ASSETS_PATH)) {
         Ok(html) => Html(html),
@@ -316,19 +547,219 @@ async fn ingest_report_handler(
     Json(report): Json<ClusterReport>,
 ) -> StatusCode {
     let node_name = report.node.clone();
-    state.cluster_cache.lock().await.insert(node_name, report);
-    let cluster_map = state.cluster_cache.lock().await.clone();
-    let _ = state
-        .tx
-        .send(serde_json::json!({ "type": "cluster_update", "data": cluster_map }).to_string());
+    state.node_history.lock().await.record(
+        &node_name,
+        &report.stats,
+        chrono::Utc::now().timestamp(),
+    );
+    let (new_status, last_seen) = (report.stats.status.clone(), report.stats.last_seen.clone());
+    let previous = state
+        .cluster_cache
+        .lock()
+        .await
+        .insert(node_name.clone(), Arc::new(report));
+    // Watchdog'un OFFLINE işaretlediği node tekrar rapor verince geri dönüş bildirilir.
+    if let Some(prev) = previous.filter(|p| p.stats.status != new_status) {
+        info!(event="NODE_STATUS_CHANGED", node=%node_name, old=%prev.stats.status, new=%new_status, "🔁 Node status changed.");
+        node_status_changed(
+            &state,
+            &node_name,
+            &prev.stats.status,
+            &new_status,
+            &last_seen,
+        );
+    }
+    publish_cluster(&state).await;
     StatusCode::OK
 }
 
-async fn export_llm_handler(State(state): State<Arc<AppState>>) -> String {
-    let cluster = state.cluster_cache.lock().await;
-    let mut report = String::from("# 🤖 SENTIRIC CLUSTER DIAGNOSTIC REPORT\n\n");
+// Kapatılan edge node'un kendini cluster listesinden düşürmesi (graceful shutdown'da çağrılır).
+async fn deregister_node_handler(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(name): Path<String>,
+) -> Response {
+    if name == state.config.node_name {
+        return (
+            StatusCode::BAD_REQUEST,
+            "Cannot deregister the local node".to_string(),
+        )
+            .into_response();
+    }
+    let removed = remove_node(&state, &name).await;
+    let result = if removed {
+        Ok(())
+    } else {
+        Err(format!("Node '{}' not found", name))
+    };
+    audit(&state, &caller, "deregister_node", Some(&name), &result).await;
+    match result {
+        Ok(()) => {
+            info!(event="NODE_DEREGISTERED", node=%name, "👋 Node deregistered.");
+            StatusCode::OK.into_response()
+        }
+        Err(e) => (StatusCode::NOT_FOUND, e).into_response(),
+    }
+}
+
+/// `update_locks` tarayıcı ve auto-pilot gibi container adıyla tutulur; ID veya kısa ID ile gelen
+/// istekler kilitlenmeden önce bu ada çözülür.
+pub(crate) async fn container_name(state: &AppState, id: &str) -> anyhow::Result<String> {
+    let info = state.docker.inspect_service(id).await?;
+    Ok(info
+        .name
+        .map(|n| n.trim_start_matches('/').to_string())
+        .unwrap_or_else(|| id.to_string()))
+}
+
+/// Node'u cluster cache'inden ve metrik geçmişinden siler; UI'ın kartı kaldırması için
+/// `nodes_list_update` yayınlanır. Deregister ve watchdog eviction aynı yolu kullanır.
+pub(crate) async fn remove_node(state: &AppState, name: &str) -> bool {
+    let snapshot = {
+        let mut cluster = state.cluster_cache.lock().await;
+        if cluster.remove(name).is_none() {
+            return false;
+        }
+        cluster.clone()
+    };
+    state.node_history.lock().await.remove(name);
+    state
+        .tx
+        .send(WsMessage::NodesListUpdate { data: &snapshot }.encode());
+    // Yeniden senkronizasyonda silinen node geri gelmesin diye önbellekteki mesaj da yenilenir.
+    publish_cluster(state).await;
+    true
+}
+
+/// Cluster değiştikten sonra `cluster_update` bir kez serileştirilip yayınlanır ve `cluster_json`'a
+/// yazılır. `cluster_cache` kilidi sadece Arc snapshot'ı alınırken tutulur; `cluster_json` kilidi
+/// ise eşzamanlı iki yayının eski snapshot'ı sona bırakmasını engeller.
+pub(crate) async fn publish_cluster(state: &AppState) {
+    let mut cached = state.cluster_json.lock().await;
+    let snapshot = state.cluster_cache.lock().await.clone();
+    *cached = WsMessage::ClusterUpdate { data: &snapshot }.encode();
+    state.tx.send(cached.clone());
+}
+
+/// Node durum geçişini (ONLINE ⇄ OFFLINE) alarm webhook'una ve UI'a bildirir.
+/// Ingest (geri dönüş) ve liveness watchdog (OFFLINE) aynı yolu kullanır.
+pub(crate) fn node_status_changed(
+    state: &AppState,
+    node: &str,
+    old_status: &str,
+    new_status: &str,
+    last_seen: &str,
+) {
+    state
+        .notifier
+        .node_status_changed(node, old_status, new_status, last_seen);
+    state.tx.send(
+        WsMessage::NodeStatusChanged {
+            data: NodeStatusChange {
+                node: node.to_string(),
+                old_status: old_status.to_string(),
+                new_status: new_status.to_string(),
+                last_seen: last_seen.to_string(),
+            },
+        }
+        .encode(),
+    );
+}
+
+const EXPORT_MAX_LOG_LINES: usize = 1000;
+
+async fn export_llm_handler(
+    State(state): State<Arc<AppState>>,
+    Query(p): Query<ExportParams>,
+) -> Response {
+    let log_lines = p.log_lines.unwrap_or(50).min(EXPORT_MAX_LOG_LINES);
+    let diag = gather_diagnostics(&state, log_lines, p.timestamps).await;
+    match p.format.as_deref() {
+        Some("json") => Json(diag).into_response(),
+        None | Some("markdown") | Some("md") => render_llm_markdown(&diag).into_response(),
+        Some(other) => (
+            StatusCode::BAD_REQUEST,
+            format!("Unknown format '{}', expected markdown or json", other),
+        )
+            .into_response(),
+    }
+}
+
+// Cluster görüntüsü ve yerel servislerin son logları tek turda toplanır; log çekimleri paralel yapılır.
+async fn gather_diagnostics(
+    state: &AppState,
+    log_lines: usize,
+    timestamps: bool,
+) -> DiagnosticReport {
+    let nodes = state.cluster_cache.lock().await.clone();
+    let system = state.node_stats_cache.lock().await.clone();
+    let mut local: Vec<ServiceInstance> = state
+        .services_cache
+        .read()
+        .await
+        .values()
+        .cloned()
+        .collect();
+    local.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let logs = if log_lines > 0 {
+        futures_util::future::join_all(local.iter().map(|svc| {
+            state
+                .docker
+                .get_logs_snapshot(&svc.name, log_lines, timestamps)
+        }))
+        .await
+    } else {
+        vec![String::new(); local.len()]
+    };
 
-    report.push_str("## 1. INFRASTRUCTURE HEALTH\n");
+    DiagnosticReport {
+        cluster: state.config.cluster_name.clone(),
+        node: state.config.node_name.clone(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        log_lines,
+        log_timestamps: timestamps,
+        system,
+        nodes,
+        services: local
+            .into_iter()
+            .zip(logs)
+            .map(|(svc, recent_logs)| DiagnosticService {
+                name: svc.name,
+                status: svc.status,
+                image: svc.image,
+                cpu: svc.cpu_usage,
+                mem: svc.mem_usage,
+                recent_logs,
+            })
+            .collect(),
+    }
+}
+
+fn render_llm_markdown(diag: &DiagnosticReport) -> String {
+    let cluster = &diag.nodes;
+    let mut report = format!(
+        "# 🤖 SENTIRIC CLUSTER DIAGNOSTIC REPORT ({})\n_Generated by {} at {}_\n\n",
+        diag.cluster, diag.node, diag.generated_at
+    );
+
+    let sys = &diag.system;
+    report.push_str(&format!("## 1. SYSTEM RESOURCES ({})\n", diag.node));
+    report.push_str(&format!(
+        "- **CPU:** {:.1}%\n- **RAM:** {}/{} MB\n- **Disk:** {}/{} GB\n",
+        sys.cpu_usage, sys.ram_used, sys.ram_total, sys.disk_used, sys.disk_total
+    ));
+    if sys.gpu_count > 0 || sys.gpu_mem_total > 0 {
+        report.push_str(&format!(
+            "- **GPU:** {:.1}% util | VRAM: {}/{} MB ({} device)\n",
+            sys.gpu_usage, sys.gpu_mem_used, sys.gpu_mem_total, sys.gpu_count
+        ));
+    } else {
+        report.push_str("- **GPU:** none detected\n");
+    }
+    report.push('\n');
+
+    report.push_str("## 2. INFRASTRUCTURE HEALTH\n");
     for (node, data) in cluster.iter() {
         report.push_str(&format!(
             "- **{}** | CPU: {:.1}% | RAM: {}/{} MB | Status: {}\n",
@@ -340,7 +771,26 @@ async fn export_llm_handler(State(state): State<Arc<AppState>>) -> String {
         ));
     }
 
-    report.push_str("\n## 2. CONFIG DRIFT DETECTION\n");
+    let unhealthy: Vec<String> = cluster
+        .iter()
+        .flat_map(|(node, data)| {
+            data.services
+                .iter()
+                .filter(|s| s.healthcheck.as_deref() == Some("unhealthy"))
+                .map(move |s| format!("{}@{}", s.name, node))
+        })
+        .collect();
+    report.push_str(&format!(
+        "- **Unhealthy Workloads:** {}{}\n",
+        unhealthy.len(),
+        if unhealthy.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", unhealthy.join(", "))
+        }
+    ));
+
+    report.push_str("\n## 3. CONFIG DRIFT DETECTION\n");
     let mut service_versions: std::collections::HashMap<String, Vec<(String, String)>> =
         std::collections::HashMap::new();
 
@@ -378,7 +828,7 @@ async fn export_llm_handler(State(state): State<Arc<AppState>>) -> String {
         report.push_str("✅ No configuration drift detected. Cluster is synchronized.\n");
     }
 
-    report.push_str("\n## 3. SERVICE DETAILS\n");
+    report.push_str("\n## 4. SERVICE DETAILS\n");
     for (node, data) in cluster.iter() {
         report.push_str(&format!("### {}\n", node));
         for svc in &data.services {
@@ -388,49 +838,499 @@ async fn export_llm_handler(State(state): State<Arc<AppState>>) -> String {
                 "🔴"
             };
             report.push_str(&format!(
-                "- {} **{}** | CPU: {:.1}% | RAM: {}MB | AP: {}\n",
-                status_icon, svc.name, svc.cpu_usage, svc.mem_usage, svc.auto_pilot
+                "- {} **{}** | CPU: {:.1}% | RAM: {}MB | AP: {} | Health: {}\n",
+                status_icon,
+                svc.name,
+                svc.cpu_usage,
+                svc.mem_usage,
+                svc.auto_pilot,
+                svc.healthcheck.as_deref().unwrap_or("none")
             ));
         }
         report.push('\n');
     }
+
+    report.push_str(&format!(
+        "## 5. RECENT LOGS ({}, last {} lines{})\n",
+        diag.node,
+        diag.log_lines,
+        if diag.log_timestamps {
+            ", timestamped"
+        } else {
+            ""
+        }
+    ));
+    for svc in diag.services.iter().filter(|s| !s.recent_logs.is_empty()) {
+        report.push_str(&format!(
+            "### {} ({})\n```\n{}\n```\n",
+            svc.name,
+            svc.status,
+            svc.recent_logs.trim_end()
+        ));
+    }
     report
 }
 
-async fn inspect_handler(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> Response {
+async fn inspect_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(p): Query<InspectParams>,
+) -> Response {
+    if id.is_empty() || id == "null" {
+        return (StatusCode::BAD_REQUEST, "Invalid ID").into_response();
+    }
+    let mut inspect = match state.docker.inspect_service(&id).await {
+        Ok(d) => d,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+    // Viewer rolüne açık: `?fields=Config.Env` dahil her yolda env değerleri ENV_REDACT_PATTERNS ile gizlenir.
+    if let Some(env) = inspect.config.as_mut().and_then(|c| c.env.as_mut()) {
+        *env = Governor::redact_env_entries(env, &state.config.env_redact_patterns);
+    }
+    let fields: Vec<&str> = p
+        .fields
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .collect();
+    if fields.is_empty() {
+        return Json(inspect).into_response();
+    }
+    match serde_json::to_value(&inspect) {
+        Ok(full) => Json(Governor::project_fields(&full, &fields)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+// Viewer rolüne açık olduğu için hassas değerler ENV_REDACT_PATTERNS ile gizlenir.
+async fn env_handler(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> Response {
     if id.is_empty() || id == "null" {
         return (StatusCode::BAD_REQUEST, "Invalid ID").into_response();
     }
     match state.docker.inspect_service(&id).await {
-        Ok(d) => Json(d).into_response(),
+        Ok(inspect) => {
+            let env = inspect.config.and_then(|c| c.env).unwrap_or_default();
+            Json(Governor::redact_env(
+                &env,
+                &state.config.env_redact_patterns,
+            ))
+            .into_response()
+        }
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
 
-async fn prune_handler(State(state): State<Arc<AppState>>) -> Response {
-    match state.docker.prune_system().await {
-        Ok(m) => (StatusCode::OK, m).into_response(),
+async fn images_handler(State(state): State<Arc<AppState>>) -> Response {
+    match state.docker.list_images().await {
+        Ok(images) => Json(images).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+// Bakım penceresi dışında bulunup sırada bekleyen auto-pilot güncellemeleri
+async fn pending_updates_handler(State(state): State<Arc<AppState>>) -> Json<PendingUpdates> {
+    let window = state.config.autopilot_window;
+    Json(PendingUpdates {
+        window: window.map(|w| w.to_string()),
+        in_window: window.is_none_or(|w| w.contains(chrono::Utc::now())),
+        services: state.pending_updates.lock().await.iter().cloned().collect(),
+    })
+}
+
+// Büyük loglar bellekte toplanmadan doğrudan yanıt gövdesine akıtılır.
+async fn logs_download_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(p): Query<LogDownloadParams>,
+) -> Response {
+    if id.is_empty() || id == "null" {
+        return (StatusCode::BAD_REQUEST, "Invalid ID").into_response();
+    }
+    let tail = p.tail.unwrap_or_else(|| "all".to_string());
+    if tail != "all" && tail.parse::<u64>().is_err() {
+        return (
+            StatusCode::BAD_REQUEST,
+            "tail must be a line count or 'all'",
+        )
+            .into_response();
+    }
+    // Akış başladıktan sonra hata durum koduna yansıtılamayacağı için container önceden doğrulanır.
+    let name = match state.docker.inspect_service(&id).await {
+        Ok(info) => info
+            .name
+            .map(|n| n.trim_start_matches('/').to_string())
+            .unwrap_or(id.clone()),
+        Err(e) => return (StatusCode::NOT_FOUND, e.to_string()).into_response(),
+    };
+
+    let filename = format!(
+        "{}-{}.log",
+        name,
+        chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+    );
+    let stream = state.docker.get_logs_tail(&id, &tail, p.timestamps);
+    (
+        [
+            (
+                header::CONTENT_TYPE,
+                "text/plain; charset=utf-8".to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response()
+}
+
+async fn history_handler(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> Response {
+    if id.is_empty() || id == "null" {
+        return (StatusCode::BAD_REQUEST, "Invalid ID").into_response();
+    }
+    // UI servis adını gönderir; kısa ID ile gelen istekler de isme çözülür.
+    let name = {
+        let cache = state.services_cache.read().await;
+        cache
+            .values()
+            .find(|s| s.name == id || s.short_id == id)
+            .map(|s| s.name.clone())
+            .unwrap_or(id)
+    };
+    match state.service_history.lock().await.get(&name) {
+        Some(buf) => Json(buf.iter().cloned().collect::<Vec<_>>()).into_response(),
+        None => (StatusCode::NOT_FOUND, "No history for service").into_response(),
+    }
+}
+
+async fn env_patch_handler(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(id): Path<String>,
+    Json(p): Json<EnvPatchParams>,
+) -> Response {
+    if id.is_empty() || id == "null" {
+        return (StatusCode::BAD_REQUEST, "Invalid ID").into_response();
+    }
+    if p.set.is_empty() && p.unset.is_empty() {
+        return (StatusCode::BAD_REQUEST, "Empty env patch").into_response();
+    }
+    for key in p.set.keys().chain(p.unset.iter()) {
+        if let Err(e) = Governor::validate_env_key(key) {
+            return (StatusCode::BAD_REQUEST, e).into_response();
+        }
+        if p.set.contains_key(key) && p.unset.contains(key) {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!("Key '{}' cannot be both set and unset", key),
+            )
+                .into_response();
+        }
+    }
+
+    // Auto-pilot güncellemesi ile çakışmaması için aynı kilit (container adıyla) kullanılır.
+    let name = match container_name(&state, &id).await {
+        Ok(name) => name,
+        Err(e) => return (StatusCode::NOT_FOUND, e.to_string()).into_response(),
+    };
+    if !state.update_locks.lock().await.insert(name.clone()) {
+        return (StatusCode::CONFLICT, "Update already in progress").into_response();
+    }
+    let result = state
+        .docker
+        .update_service_env(&id, &p.set, &p.unset, &state.config.env_redact_patterns)
+        .await;
+    state.update_locks.lock().await.remove(&name);
+    audit(&state, &caller, "env_patch", Some(&id), &result).await;
+
+    match result {
+        Ok(r) => Json(r).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+async fn exec_handler(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(id): Path<String>,
+    Json(p): Json<ExecParams>,
+) -> Response {
+    if !state.config.enable_exec {
+        return (
+            StatusCode::FORBIDDEN,
+            "Exec is disabled (ENABLE_EXEC=false)",
+        )
+            .into_response();
+    }
+    if id.is_empty() || id == "null" {
+        return (StatusCode::BAD_REQUEST, "Invalid ID").into_response();
+    }
+    if p.cmd.is_empty() {
+        return (StatusCode::BAD_REQUEST, "Empty command").into_response();
+    }
+    let result = state.docker.exec_command(&id, p.cmd).await;
+    audit(&state, &caller, "exec", Some(&id), &result).await;
+    match result {
+        Ok(out) => Json(out).into_response(),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
 
-async fn self_update_handler(State(state): State<Arc<AppState>>) -> Response {
-    match state.docker.pull_nexus_image().await {
+// Dry-run hiçbir şey silmediği için denetim kaydı ve alarm üretmez. Başka bir prune sürüyorsa
+// 409 döner; reddedilen istek denetime yazılır ama alarm üretmez.
+async fn prune_handler(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Query(p): Query<PruneParams>,
+) -> Response {
+    let result = state.docker.prune_with(&p).await;
+    let busy = result.as_ref().is_err_and(|e| e.is::<PruneBusy>());
+    if !p.dry_run {
+        let summary = result
+            .as_ref()
+            .map(|r| r.summary.clone())
+            .map_err(|e| anyhow::anyhow!("{}", e));
+        // Volume silme geri alınamaz; denetim kaydında ayrı aksiyon olarak görünür.
+        let action = if p.volumes { "prune_volumes" } else { "prune" };
+        audit(&state, &caller, action, None, &summary).await;
+        if !busy {
+            state
+                .notifier
+                .prune_result(&state.config.node_name, "manual", &summary);
+        }
+    }
+    match result {
+        Ok(report) => Json(report).into_response(),
+        Err(e) if busy => (StatusCode::CONFLICT, e.to_string()).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+async fn self_update_handler(State(state): State<Arc<AppState>>, caller: Caller) -> Response {
+    let result = state.docker.pull_nexus_image().await;
+    audit(&state, &caller, "self_update", None, &result).await;
+    match result {
         Ok(msg) => (StatusCode::OK, msg).into_response(),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
 
+fn record_lag(state: &AppState, skipped: u64) {
+    state.broadcast_lagged.fetch_add(skipped, Ordering::Relaxed);
+    state.broadcast_lag_events.fetch_add(1, Ordering::Relaxed);
+}
+
 async fn ws_handler(ws: WebSocketUpgrade, State(state): State<Arc<AppState>>) -> impl IntoResponse {
     ws.on_upgrade(|socket| handle_socket(socket, state))
 }
 
+// Takılı kalmış bir sekme (TCP tamponu dolu) yayın döngüsünü sonsuza dek bekletemez.
+const WS_SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Boşta kalan bağlantıyı kesen proxy'lere karşı periyodik Ping gönderilir. İstemcinin Ping'lerine
+// Pong'u axum (tungstenite) bir sonraki okumada otomatik yazar; bunun için soket sürekli okunur.
+// İki aralık boyunca istemciden hiçbir çerçeve (Pong dahil) gelmezse bağlantı ölü sayılır.
+struct Keepalive {
+    period: Option<Duration>,
+    ticker: tokio::time::Interval,
+    last_seen: tokio::time::Instant,
+}
+
+impl Keepalive {
+    fn new(state: &AppState) -> Self {
+        let secs = state.config.ws_ping_interval_secs;
+        let period = Duration::from_secs(secs.max(1));
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        Self {
+            period: (secs > 0).then_some(period),
+            ticker,
+            last_seen: tokio::time::Instant::now(),
+        }
+    }
+
+    fn enabled(&self) -> bool {
+        self.period.is_some()
+    }
+
+    async fn tick(&mut self) {
+        self.ticker.tick().await;
+    }
+
+    // Ping gönderir; istemci uzun süredir sessizse `false` döner ve soket kapatılmalıdır.
+    async fn ping(&self, socket: &mut WebSocket) -> bool {
+        if let Some(period) = self.period {
+            if self.last_seen.elapsed() > period * 2 {
+                debug!(
+                    event = "WS_CLIENT_TIMEOUT",
+                    "WebSocket client stopped answering pings."
+                );
+                return false;
+            }
+        }
+        matches!(
+            tokio::time::timeout(WS_SEND_TIMEOUT, socket.send(Message::Ping(Vec::new()))).await,
+            Ok(Ok(()))
+        )
+    }
+
+    // İstemciden gelen çerçeveyi işler; bağlantı kapandıysa `false` döner.
+    fn inbound(&mut self, frame: Option<Result<Message, axum::Error>>) -> bool {
+        match frame {
+            Some(Ok(Message::Close(_))) | Some(Err(_)) | None => false,
+            Some(Ok(_)) => {
+                self.last_seen = tokio::time::Instant::now();
+                true
+            }
+        }
+    }
+}
+
+// Yayın kanalı sınırlı olduğundan yavaş istemci mesaj kaçırır (Lagged); bu durumda istemciye
+// `lagged` bildirimi gönderilip güncel cluster durumu yeniden basılır.
 async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
-    let mut rx = state.tx.subscribe();
-    while let Ok(msg) = rx.recv().await {
+    // Abonelik snapshot'tan önce açılır ki arada yayınlanan güncellemeler kaybolmasın. Replay aynı
+    // anda alınır: abonelikten sonra yayınlanan olay sadece canlı akışta gelir.
+    let (mut rx, replay) = state.tx.subscribe_with_replay();
+    // Yeni bağlanan istemci bir sonraki tarama/raporu beklemeden mevcut durumu görür.
+    for msg in initial_snapshot_messages(&state).await {
         if socket.send(Message::Text(msg)).await.is_err() {
-            break;
+            return;
+        }
+    }
+    // Bağlanmadan önce olan geçici olaylar (güncelleme sonucu, node OFFLINE...) geçmiş olarak gösterilir.
+    for msg in replay {
+        if socket.send(Message::Text(msg)).await.is_err() {
+            return;
+        }
+    }
+    let mut keepalive = Keepalive::new(&state);
+    loop {
+        let batch = tokio::select! {
+            res = rx.recv() => match res {
+                Ok(msg) => vec![msg],
+                Err(RecvError::Lagged(skipped)) => {
+                    record_lag(&state, skipped);
+                    warn!(
+                        event = "WS_CLIENT_LAGGED",
+                        skipped, "🐢 WebSocket client lagging, resyncing."
+                    );
+                    vec![
+                        WsMessage::Lagged { skipped }.encode(),
+                        cluster_snapshot_message(&state).await,
+                    ]
+                }
+                Err(RecvError::Closed) => break,
+            },
+            frame = socket.recv() => {
+                if !keepalive.inbound(frame) {
+                    break;
+                }
+                continue;
+            }
+            _ = keepalive.tick(), if keepalive.enabled() => {
+                if !keepalive.ping(&mut socket).await {
+                    break;
+                }
+                continue;
+            }
+        };
+        for msg in batch {
+            match tokio::time::timeout(WS_SEND_TIMEOUT, socket.send(Message::Text(msg))).await {
+                Ok(Ok(())) => {}
+                Ok(Err(_)) => return,
+                Err(_) => {
+                    warn!(
+                        event = "WS_CLIENT_STUCK",
+                        "🐢 WebSocket client not reading, dropping."
+                    );
+                    return;
+                }
+            }
+        }
+    }
+}
+
+async fn initial_snapshot_messages(state: &AppState) -> Vec<String> {
+    let cluster = state.cluster_cache.lock().await.clone();
+    let nodes = WsMessage::NodesListUpdate { data: &cluster }.encode();
+    let services: Vec<ServiceInstance> = state
+        .services_cache
+        .read()
+        .await
+        .values()
+        .cloned()
+        .collect();
+    vec![
+        nodes,
+        WsMessage::ServicesUpdate {
+            data: ServicesSnapshot {
+                node: state.config.node_name.clone(),
+                services,
+            },
+        }
+        .encode(),
+    ]
+}
+
+// Yeniden senkronizasyon için son yayınlanan cluster durumu; tekrar serileştirilmez.
+// Henüz yayın yapılmadıysa (ilk taramadan önce) boş cluster gönderilir.
+async fn cluster_snapshot_message(state: &AppState) -> String {
+    let cached = state.cluster_json.lock().await.clone();
+    if cached.is_empty() {
+        return WsMessage::ClusterUpdate {
+            data: &ClusterMap::new(),
         }
+        .encode();
+    }
+    cached
+}
+
+// WebSocket upgrade'ini bozan proxy'ler için aynı yayın kanalının SSE karşılığı. JSON'daki `type`
+// alanı SSE event adı olur, böylece istemci `addEventListener(type)` ile süzebilir.
+async fn sse_handler(
+    State(state): State<Arc<AppState>>,
+) -> Sse<impl futures_util::Stream<Item = Result<Event, Infallible>>> {
+    let (rx, replay) = state.tx.subscribe_with_replay();
+    let replay: Vec<Result<Event, Infallible>> =
+        replay.into_iter().map(|msg| Ok(sse_event(msg))).collect();
+    let stream = futures_util::stream::unfold((rx, state), |(mut rx, state)| async move {
+        let events = match rx.recv().await {
+            Ok(msg) => vec![sse_event(msg)],
+            // WS ile aynı: `lagged` bildirimi ve kaçırılan mesajların yerine güncel cluster durumu.
+            Err(RecvError::Lagged(skipped)) => {
+                record_lag(&state, skipped);
+                debug!(
+                    event = "SSE_CLIENT_LAGGED",
+                    skipped, "SSE client lagging, resyncing."
+                );
+                vec![
+                    sse_event(WsMessage::Lagged { skipped }.encode()),
+                    sse_event(cluster_snapshot_message(&state).await),
+                ]
+            }
+            Err(RecvError::Closed) => return None,
+        };
+        Some((
+            futures_util::stream::iter(events.into_iter().map(Ok)),
+            (rx, state),
+        ))
+    })
+    .flatten();
+    Sse::new(futures_util::stream::iter(replay).chain(stream)).keep_alive(KeepAlive::default())
+}
+
+fn sse_event(msg: String) -> Event {
+    let name = serde_json::from_str::<serde_json::Value>(&msg)
+        .ok()
+        .and_then(|v| v.get("type").and_then(|t| t.as_str()).map(str::to_string))
+        .filter(|t| !t.is_empty() && !t.contains(['\n', '\r']));
+    match name {
+        Some(name) => Event::default().event(name).data(msg),
+        None => Event::default().data(msg),
     }
 }
 
@@ -438,102 +1338,888 @@ async fn ws_logs_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
+    Query(params): Query<LogStreamParams>,
 ) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_log_socket(socket, state, id))
+    ws.on_upgrade(move |socket| handle_log_socket(socket, state, id, params))
 }
 
-async fn handle_log_socket(mut socket: WebSocket, state: Arc<AppState>, id: String) {
+// Filtre parametreleri hatalıysa tek bir hata mesajı gönderilip soket kapatılır.
+fn parse_log_filter(params: &LogStreamParams) -> Result<(Option<Regex>, Option<i64>), String> {
+    let grep = params
+        .grep
+        .as_deref()
+        .filter(|g| !g.is_empty())
+        .map(Regex::new)
+        .transpose()
+        .map_err(|e| format!("Invalid grep regex: {}", e))?;
+    let since = params
+        .since
+        .as_deref()
+        .filter(|s| !s.is_empty())
+        .map(chrono::DateTime::parse_from_rfc3339)
+        .transpose()
+        .map_err(|e| format!("Invalid since timestamp (expected RFC3339): {}", e))?
+        .map(|t| t.timestamp());
+    Ok((grep, since))
+}
+
+async fn handle_log_socket(
+    mut socket: WebSocket,
+    state: Arc<AppState>,
+    id: String,
+    params: LogStreamParams,
+) {
     if id.is_empty() || id == "null" {
         return;
     }
-    let mut log_stream = state.docker.get_log_stream(&id);
-    while let Some(res) = log_stream.next().await {
-        if let Ok(out) = res {
-            let b: Vec<u8> = match out {
-                bollard::container::LogOutput::StdOut { message } => message.into(),
-                bollard::container::LogOutput::StdErr { message } => message.into(),
-                _ => vec![],
-            };
-            if socket
-                .send(Message::Text(String::from_utf8_lossy(&b).to_string()))
-                .await
-                .is_err()
-            {
+    let (grep, since) = match parse_log_filter(&params) {
+        Ok(filter) => filter,
+        Err(msg) => {
+            let _ = socket
+                .send(Message::Text(json!({ "error": msg }).to_string()))
+                .await;
+            let _ = socket
+                .send(Message::Close(Some(CloseFrame {
+                    code: close_code::POLICY,
+                    reason: "Invalid log filter".into(),
+                })))
+                .await;
+            return;
+        }
+    };
+    let mut log_stream = state.docker.get_log_stream(&id, since);
+
+    // Unutulan tarayıcı sekmelerinin Docker log bağlantısını sonsuza dek tutmasını engeller.
+    let max_secs = state.config.log_stream_max_secs;
+    let deadline = tokio::time::sleep(if max_secs > 0 {
+        Duration::from_secs(max_secs)
+    } else {
+        Duration::MAX
+    });
+    tokio::pin!(deadline);
+    let mut keepalive = Keepalive::new(&state);
+
+    loop {
+        tokio::select! {
+            res = log_stream.next() => {
+                let Some(res) = res else { break };
+                if let Ok(out) = res {
+                    let b: Vec<u8> = match out {
+                        bollard::container::LogOutput::StdOut { message } => message.into(),
+                        bollard::container::LogOutput::StdErr { message } => message.into(),
+                        _ => vec![],
+                    };
+                    let mut text = String::from_utf8_lossy(&b).to_string();
+                    if let Some(re) = &grep {
+                        text = text
+                            .lines()
+                            .filter(|l| re.is_match(l))
+                            .map(|l| format!("{}\n", l))
+                            .collect();
+                        if text.is_empty() {
+                            continue;
+                        }
+                    }
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            frame = socket.recv() => {
+                if !keepalive.inbound(frame) {
+                    break;
+                }
+            }
+            _ = keepalive.tick(), if keepalive.enabled() => {
+                if !keepalive.ping(&mut socket).await {
+                    break;
+                }
+            }
+            _ = &mut deadline, if max_secs > 0 => {
+                debug!(event="LOG_STREAM_EXPIRED", container.id=%id, max_secs, "Log stream duration limit reached, closing socket.");
+                let _ = socket
+                    .send(Message::Close(Some(CloseFrame {
+                        code: close_code::NORMAL,
+                        reason: "Log stream duration limit reached. Please reconnect.".into(),
+                    })))
+                    .await;
                 break;
             }
         }
     }
 }
 
-async fn status_handler(State(state): State<Arc<AppState>>) -> Json<Vec<ServiceInstance>> {
-    let s = state.services_cache.lock().await;
-    Json(s.values().cloned().collect())
+async fn ws_stats_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_stats_socket(socket, state, id))
+}
+
+async fn handle_stats_socket(mut socket: WebSocket, state: Arc<AppState>, id: String) {
+    if id.is_empty() || id == "null" {
+        return;
+    }
+    // İstemci ayrıldığında döngüden çıkılır ve stream drop edilerek Docker bağlantısı kapanır.
+    let stats_stream = state.docker.get_stats_stream(&id);
+    tokio::pin!(stats_stream);
+
+    loop {
+        tokio::select! {
+            res = stats_stream.next() => {
+                let Some(Ok(stats)) = res else { break };
+                let (net_rx, net_tx) = stats
+                    .networks
+                    .as_ref()
+                    .map(|n| {
+                        n.values()
+                            .fold((0, 0), |(rx, tx), s| (rx + s.rx_bytes, tx + s.tx_bytes))
+                    })
+                    .unwrap_or((0, 0));
+                let payload = json!({
+                    "cpu_percent": DockerAdapter::cpu_percent(&stats),
+                    "mem_used": stats.memory_stats.usage.unwrap_or(0),
+                    "mem_limit": stats.memory_stats.limit.unwrap_or(0),
+                    "net_rx": net_rx,
+                    "net_tx": net_tx,
+                });
+                if socket.send(Message::Text(payload.to_string())).await.is_err() {
+                    break;
+                }
+            }
+            msg = socket.recv() => {
+                match msg {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => {
+                        debug!(event="STATS_SOCKET_CLOSED", container.id=%id, "Stats socket closed by client.");
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+async fn ws_update_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_update_socket(socket, state, caller, id))
+}
+
+async fn handle_update_socket(
+    mut socket: WebSocket,
+    state: Arc<AppState>,
+    caller: Caller,
+    id: String,
+) {
+    if id.is_empty() || id == "null" {
+        return;
+    }
+    let name = match container_name(&state, &id).await {
+        Ok(name) => name,
+        Err(e) => {
+            let _ = socket
+                .send(Message::Text(
+                    json!({ "type": "result", "ok": false, "error": e.to_string() }).to_string(),
+                ))
+                .await;
+            return;
+        }
+    };
+    if !state.update_locks.lock().await.insert(name.clone()) {
+        let _ = socket
+            .send(Message::Text(
+                json!({ "type": "result", "ok": false, "error": "Update already in progress" })
+                    .to_string(),
+            ))
+            .await;
+        return;
+    }
+
+    let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+
+    // Güncelleme ayrı task'ta koşar; soket kapansa bile yarıda kesilmez.
+    let task_state = state.clone();
+    let svc = id.clone();
+    tokio::spawn(async move {
+        let outcome = task_state
+            .docker
+            .force_update_service(&svc, Some(&progress_tx))
+            .await;
+        task_state.update_locks.lock().await.remove(&name);
+        audit(&task_state, &caller, "update", Some(&svc), &outcome).await;
+        let _ = progress_tx.send(match outcome {
+            Ok(m) => json!({ "type": "result", "ok": true, "message": m }),
+            Err(e) => json!({ "type": "result", "ok": false, "error": e.to_string() }),
+        });
+    });
+
+    while let Some(event) = progress_rx.recv().await {
+        if socket.send(Message::Text(event.to_string())).await.is_err() {
+            debug!(event="UPDATE_SOCKET_DETACHED", service=%id, "Update socket closed, update continues in background.");
+            break;
+        }
+    }
+}
+
+// Filtre yoksa yerel servisler döner; `node` başka bir node'u gösteriyorsa onun son raporu kullanılır.
+// Sonuç isme göre sıralanır. Mevcut istemciler düz dizi beklediği için `Page` zarfı sadece
+// `limit` veya `offset` verildiğinde döner; eşleşme yoksa dizi/`items` boştur.
+async fn status_handler(
+    State(state): State<Arc<AppState>>,
+    Query(p): Query<StatusFilterParams>,
+) -> Response {
+    let paged = p.limit.is_some() || p.offset.is_some();
+    let offset = p.offset.unwrap_or(0);
+    let limit = if paged {
+        p.limit
+            .unwrap_or(STATUS_PAGE_DEFAULT_LIMIT)
+            .min(STATUS_PAGE_MAX_LIMIT)
+    } else {
+        usize::MAX
+    };
+    // Filtre ve sıralama referanslar üzerinde yapılır; sadece döndürülen sayfa kopyalanır.
+    let page = |all: &mut dyn Iterator<Item = &ServiceInstance>| {
+        let services = Governor::filter_services(all, p.name.as_deref(), p.status.as_deref());
+        let total = services.len();
+        let items: Vec<ServiceInstance> = services
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect();
+        (items, total)
+    };
+
+    let (items, total) = match p.node.as_deref() {
+        Some(node) if node != state.config.node_name => state
+            .cluster_cache
+            .lock()
+            .await
+            .get(node)
+            .map(|r| page(&mut r.services.iter()))
+            .unwrap_or_default(),
+        _ => page(&mut state.services_cache.read().await.values()),
+    };
+    if !paged {
+        return Json(items).into_response();
+    }
+    Json(Page {
+        items,
+        total,
+        offset,
+        limit,
+    })
+    .into_response()
+}
+
+// `?tag=media` sadece o etiketi taşıyan node'ları döndürür (büyük/küçük harf duyarsız).
+async fn nodes_handler(
+    State(state): State<Arc<AppState>>,
+    Query(p): Query<NodeListParams>,
+) -> Json<Vec<NodeStats>> {
+    let tag = p.tag.filter(|t| !t.trim().is_empty());
+    let cluster = state.cluster_cache.lock().await;
+    let mut nodes: Vec<NodeStats> = cluster
+        .values()
+        .filter(|r| {
+            tag.as_deref().is_none_or(|t| {
+                r.stats
+                    .tags
+                    .iter()
+                    .any(|x| x.eq_ignore_ascii_case(t.trim()))
+            })
+        })
+        .map(|r| r.stats.clone())
+        .collect();
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    Json(nodes)
+}
+
+async fn node_history_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Query(p): Query<NodeHistoryParams>,
+) -> Response {
+    let metric = p.metric.unwrap_or_else(|| "cpu".into());
+    if !NodeHistory::METRICS.contains(&metric.as_str()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Unknown metric '{}'. Expected one of: {}",
+                metric,
+                NodeHistory::METRICS.join(", ")
+            ),
+        )
+            .into_response();
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let parse_ts = |v: &Option<String>, default: i64| -> Result<i64, String> {
+        match v.as_deref() {
+            None | Some("") => Ok(default),
+            Some(raw) => raw
+                .parse::<i64>()
+                .or_else(|_| chrono::DateTime::parse_from_rfc3339(raw).map(|d| d.timestamp()))
+                .map_err(|_| format!("Invalid timestamp '{}'", raw)),
+        }
+    };
+    let (from, to) = match (parse_ts(&p.from, now - 3600), parse_ts(&p.to, now)) {
+        (Ok(f), Ok(t)) => (f, t),
+        (Err(e), _) | (_, Err(e)) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+
+    let history = state.node_history.lock().await;
+    match history.query(&name, &metric, from, to, p.points.unwrap_or(500)) {
+        Some(points) => Json(points).into_response(),
+        None => (StatusCode::NOT_FOUND, "Unknown node").into_response(),
+    }
+}
+
+// DATABASE_PATH tanımlı değilse kalıcı geçmiş yoktur.
+async fn stored_node_history_handler(
+    State(state): State<Arc<AppState>>,
+    Query(p): Query<StoredHistoryParams>,
+) -> Response {
+    let Some(storage) = &state.storage else {
+        return (
+            StatusCode::NOT_FOUND,
+            "Persistent storage is disabled (DATABASE_PATH not set)",
+        )
+            .into_response();
+    };
+    match storage
+        .recent_nodes(p.node, p.limit.unwrap_or(100).min(5000))
+        .await
+    {
+        Ok(rows) => Json(rows).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+// Yerel node'un container'ları taze `services_cache`'ten, diğerleri son rapordan sayılır.
+// Bekleyen güncellemeler sadece bu node'un auto-pilot kuyruğunu yansıtır.
+async fn cluster_summary_handler(State(state): State<Arc<AppState>>) -> Json<ClusterSummary> {
+    let mut summary = ClusterSummary {
+        cluster_name: state.config.cluster_name.clone(),
+        ..Default::default()
+    };
+    let (mut cpu_weighted, mut cores) = (0.0, 0.0);
+    let (mut gpu_weighted, mut gpus) = (0.0, 0.0);
+    let mut count = |services: &mut dyn Iterator<Item = &ServiceInstance>| {
+        for svc in services {
+            summary.containers += 1;
+            *summary
+                .containers_by_status
+                .entry(Governor::container_state(&svc.status))
+                .or_default() += 1;
+        }
+    };
+
+    let cluster = state.cluster_cache.lock().await;
+    count(&mut state.services_cache.read().await.values());
+    for (node, report) in cluster.iter() {
+        if *node != state.config.node_name {
+            count(&mut report.services.iter());
+        }
+    }
+    for report in cluster.values() {
+        let s = &report.stats;
+        if s.status != "ONLINE" {
+            summary.nodes_offline += 1;
+            continue;
+        }
+        summary.nodes_online += 1;
+        let weight = s.per_core_usage.len().max(1) as f64;
+        cpu_weighted += s.cpu_usage as f64 * weight;
+        cores += weight;
+        summary.ram_used += s.ram_used;
+        summary.ram_total += s.ram_total;
+        if s.gpu_mem_total > 0 {
+            let weight = s.gpu_count.max(1) as f64;
+            gpu_weighted += s.gpu_usage as f64 * weight;
+            gpus += weight;
+        }
+    }
+    drop(cluster);
+
+    summary.cpu_usage = if cores > 0.0 {
+        cpu_weighted / cores
+    } else {
+        0.0
+    };
+    summary.gpu_usage = if gpus > 0.0 { gpu_weighted / gpus } else { 0.0 };
+    summary.ram_usage = if summary.ram_total > 0 {
+        summary.ram_used as f64 / summary.ram_total as f64 * 100.0
+    } else {
+        0.0
+    };
+    summary.pending_updates = state.pending_updates.lock().await.len();
+    Json(summary)
+}
+
+async fn grouped_services_handler(State(state): State<Arc<AppState>>) -> Json<Vec<ServiceGroup>> {
+    let cluster = state.cluster_cache.lock().await;
+    let mut groups: std::collections::BTreeMap<String, Vec<ServicePlacement>> =
+        std::collections::BTreeMap::new();
+
+    for (node, data) in cluster.iter() {
+        for svc in &data.services {
+            groups
+                .entry(svc.name.clone())
+                .or_default()
+                .push(ServicePlacement {
+                    node: node.clone(),
+                    short_id: svc.short_id.clone(),
+                    status: svc.status.clone(),
+                    health: svc.health.clone(),
+                    cpu_usage: svc.cpu_usage,
+                    mem_usage: svc.mem_usage,
+                });
+        }
+    }
+
+    let result = groups
+        .into_iter()
+        .map(|(name, mut placements)| {
+            placements.sort_by(|a, b| a.node.cmp(&b.node));
+            let unhealthy_count = placements
+                .iter()
+                .filter(|p| p.health != HealthStatus::Online)
+                .count();
+            ServiceGroup {
+                name,
+                node_count: placements.len(),
+                unhealthy_count,
+                placements,
+            }
+        })
+        .collect();
+    Json(result)
 }
 
 async fn update_handler(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Query(p): Query<ActionParams>,
 ) -> Response {
     info!(event="MANUAL_UPDATE_TRIGGERED", service=%p.service, "API Update Request");
-    match state.docker.force_update_service(&p.service).await {
+    let result = state.docker.force_update_service(&p.service, None).await;
+    audit(&state, &caller, "update", Some(&p.service), &result).await;
+    match result {
         Ok(m) => (StatusCode::OK, m).into_response(),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
 
+async fn update_all_handler(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+) -> Json<Vec<BatchUpdateResult>> {
+    let mut services: Vec<String> = state
+        .auto_pilot_config
+        .lock()
+        .await
+        .iter()
+        .filter(|(_, enabled)| **enabled)
+        .map(|(name, _)| name.clone())
+        .collect();
+    services.sort();
+
+    info!(
+        event = "BATCH_UPDATE_TRIGGERED",
+        count = services.len(),
+        "⚡ Batch update requested for all auto-pilot services"
+    );
+
+    // Self-update koruması check_and_update_service içinde uygulanır.
+    let results = futures_util::stream::iter(services)
+        .map(|svc| {
+            let state = state.clone();
+            let caller = caller.clone();
+            async move {
+                if !state.update_locks.lock().await.insert(svc.clone()) {
+                    return BatchUpdateResult {
+                        service: svc,
+                        result: None,
+                        error: Some("Update already in progress".into()),
+                    };
+                }
+                let outcome = state.docker.force_update_service(&svc, None).await;
+                state.update_locks.lock().await.remove(&svc);
+                audit(&state, &caller, "update_all", Some(&svc), &outcome).await;
+
+                match outcome {
+                    Ok(m) => BatchUpdateResult {
+                        service: svc,
+                        result: Some(m),
+                        error: None,
+                    },
+                    Err(e) => BatchUpdateResult {
+                        service: svc,
+                        result: None,
+                        error: Some(e.to_string()),
+                    },
+                }
+            }
+        })
+        .buffer_unordered(BATCH_UPDATE_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+
+    Json(results)
+}
+
+async fn bulk_action_handler(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Json(p): Json<BulkActionParams>,
+) -> Response {
+    if !["start", "stop", "restart"].contains(&p.action.as_str()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("Unsupported action '{}'", p.action),
+        )
+            .into_response();
+    }
+
+    if p.ordered {
+        if p.action != "restart" {
+            return (
+                StatusCode::BAD_REQUEST,
+                "ordered is only supported for restart",
+            )
+                .into_response();
+        }
+        return ordered_restart(&state, &caller, p.services).await;
+    }
+
+    info!(
+        event = "BULK_ACTION_TRIGGERED",
+        action = %p.action,
+        count = p.services.len(),
+        "⚡ Bulk {} requested", p.action
+    );
+
+    let action = p.action.as_str();
+    let results = futures_util::stream::iter(p.services.clone())
+        .map(|svc| {
+            let state = state.clone();
+            let caller = caller.clone();
+            async move {
+                // [ARCH-COMPLIANCE]: İntihar Yasağı. Orchestrator toplu işlemde durdurulamaz.
+                if action != "start" && state.docker.is_self_container(&svc).await {
+                    warn!(event="SELF_UPDATE_PREVENTED", service=%svc, "⚠️ Orchestrator excluded from bulk action.");
+                    return BatchUpdateResult {
+                        service: svc,
+                        result: None,
+                        error: Some("Orchestrator cannot stop or restart itself".into()),
+                    };
+                }
+                let outcome = match action {
+                    "start" => state.docker.start_service(&svc).await.map(|_| "Started"),
+                    "stop" => state.docker.stop_service(&svc).await.map(|_| "Stopped"),
+                    _ => state.docker.restart_service(&svc).await.map(|_| "Restarted"),
+                };
+                audit(
+                    &state,
+                    &caller,
+                    &format!("bulk_{}", action),
+                    Some(&svc),
+                    &outcome,
+                )
+                .await;
+                match outcome {
+                    Ok(m) => BatchUpdateResult {
+                        service: svc,
+                        result: Some(m.to_string()),
+                        error: None,
+                    },
+                    Err(e) => BatchUpdateResult {
+                        service: svc,
+                        result: None,
+                        error: Some(e.to_string()),
+                    },
+                }
+            }
+        })
+        .buffer_unordered(BULK_ACTION_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+
+    Json(results).into_response()
+}
+
+// Orchestrator kendisi sıralı restart'a da dahil edilemez; döngü 409 ile reddedilir.
+async fn ordered_restart(
+    state: &Arc<AppState>,
+    caller: &Caller,
+    services: Vec<String>,
+) -> Response {
+    let mut excluded = Vec::new();
+    let mut targets = Vec::new();
+    for svc in services {
+        if state.docker.is_self_container(&svc).await {
+            warn!(event="SELF_UPDATE_PREVENTED", service=%svc, "⚠️ Orchestrator excluded from bulk action.");
+            excluded.push(BatchUpdateResult {
+                service: svc,
+                result: None,
+                error: Some("Orchestrator cannot stop or restart itself".into()),
+            });
+        } else {
+            targets.push(svc);
+        }
+    }
+
+    match state.docker.restart_ordered(&targets).await {
+        Ok(mut results) => {
+            for r in &results {
+                let outcome = match &r.error {
+                    Some(e) => Err(anyhow::anyhow!("{}", e)),
+                    None => Ok(r.result.clone().unwrap_or_default()),
+                };
+                audit(
+                    state,
+                    caller,
+                    "bulk_restart_ordered",
+                    Some(&r.service),
+                    &outcome,
+                )
+                .await;
+            }
+            results.extend(excluded);
+            Json(results).into_response()
+        }
+        Err(e) if e.is::<DependencyCycle>() => {
+            (StatusCode::CONFLICT, e.to_string()).into_response()
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
 async fn toggle_handler(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Json(p): Json<ToggleParams>,
 ) -> Json<bool> {
     info!(event="AUTOPILOT_TOGGLED", service=%p.service, enabled=%p.enabled, "Auto-pilot toggle");
 
-    state
-        .auto_pilot_config
-        .lock()
-        .await
-        .insert(p.service.clone(), p.enabled);
+    // Kilit kayıt bitene dek tutulur: eşzamanlı iki toggle aynı `.tmp` dosyasına yazıp eski
+    // snapshot'ı en son rename edemez. Audit kaydı kaydın sonucuyla yazılır.
+    let saved = {
+        let mut ap = state.auto_pilot_config.lock().await;
+        ap.insert(p.service.clone(), p.enabled);
+        AutoPilotStore::save(&state.config.autopilot_state_path, &ap).await
+    };
+    if let Err(e) = &saved {
+        warn!(event="AUTOPILOT_STATE_SAVE_FAIL", path=%state.config.autopilot_state_path, error=%e, "⚠️ Failed to persist auto-pilot state.");
+    }
+    let action = if p.enabled {
+        "autopilot_enable"
+    } else {
+        "autopilot_disable"
+    };
+    audit(&state, &caller, action, Some(&p.service), &saved).await;
 
     {
-        let mut cache = state.services_cache.lock().await;
+        let mut cache = state.services_cache.write().await;
         if let Some(svc) = cache.get_mut(&p.service) {
             svc.auto_pilot = p.enabled;
         }
     }
 
-    let cluster_map = state.cluster_cache.lock().await.clone();
-    let _ = state
-        .tx
-        .send(serde_json::json!({ "type": "cluster_update", "data": cluster_map }).to_string());
+    let msg = state.cluster_json.lock().await.clone();
+    if !msg.is_empty() {
+        state.tx.send(msg);
+    }
 
     Json(p.enabled)
 }
 
-async fn start_handler(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> Response {
+async fn create_service_handler(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Json(spec): Json<ServiceSpec>,
+) -> Response {
+    if let Err(e) = Governor::validate_service_spec(&spec, &state.config.create_host_path_allowlist)
+    {
+        return (StatusCode::BAD_REQUEST, e).into_response();
+    }
+    // Aynı isimle eşzamanlı iki kurulum veya kurulum sırasında güncelleme başlatılamaz.
+    if !state.update_locks.lock().await.insert(spec.name.clone()) {
+        return (StatusCode::CONFLICT, "Operation already in progress").into_response();
+    }
+    if state.docker.inspect_service(&spec.name).await.is_ok() {
+        state.update_locks.lock().await.remove(&spec.name);
+        return (
+            StatusCode::CONFLICT,
+            format!("Container '{}' already exists", spec.name),
+        )
+            .into_response();
+    }
+    let result = state.docker.create_service(&spec).await;
+    state.update_locks.lock().await.remove(&spec.name);
+    audit(&state, &caller, "create", Some(&spec.name), &result).await;
+
+    match result {
+        Ok(container_id) => (
+            StatusCode::CREATED,
+            Json(CreateServiceResult {
+                name: spec.name,
+                container_id,
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            let status = match e.downcast_ref::<PullError>().map(|p| p.kind) {
+                Some(PullErrorKind::NotFound) => StatusCode::BAD_REQUEST,
+                Some(PullErrorKind::Auth) => StatusCode::FAILED_DEPENDENCY,
+                Some(PullErrorKind::Registry) => StatusCode::BAD_GATEWAY,
+                None => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, e.to_string()).into_response()
+        }
+    }
+}
+
+async fn start_handler(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Response {
     if id.is_empty() || id == "null" {
         return (StatusCode::BAD_REQUEST, "Invalid ID").into_response();
     }
-    match state.docker.start_service(&id).await {
+    let result = state.docker.start_service(&id).await;
+    audit(&state, &caller, "start", Some(&id), &result).await;
+    match result {
         Ok(_) => (StatusCode::OK, "Started").into_response(),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
 
-async fn stop_handler(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> Response {
+async fn stop_handler(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Response {
     if id.is_empty() || id == "null" {
         return (StatusCode::BAD_REQUEST, "Invalid ID").into_response();
     }
-    match state.docker.stop_service(&id).await {
+    let result = state.docker.stop_service(&id).await;
+    audit(&state, &caller, "stop", Some(&id), &result).await;
+    match result {
         Ok(_) => (StatusCode::OK, "Stopped").into_response(),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
 
-async fn restart_handler(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> Response {
+async fn restart_handler(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Response {
     if id.is_empty() || id == "null" {
         return (StatusCode::BAD_REQUEST, "Invalid ID").into_response();
     }
-    match state.docker.restart_service(&id).await {
+    let result = state.docker.restart_service(&id).await;
+    audit(&state, &caller, "restart", Some(&id), &result).await;
+    match result {
         Ok(_) => (StatusCode::OK, "Restarted").into_response(),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
+
+async fn pause_handler(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Response {
+    if id.is_empty() || id == "null" {
+        return (StatusCode::BAD_REQUEST, "Invalid ID").into_response();
+    }
+    let result = state.docker.pause_service(&id).await;
+    audit(&state, &caller, "pause", Some(&id), &result).await;
+    match result {
+        Ok(_) => {
+            broadcast_pause_state(&state, &id, true).await;
+            (StatusCode::OK, "Paused").into_response()
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+async fn unpause_handler(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Response {
+    if id.is_empty() || id == "null" {
+        return (StatusCode::BAD_REQUEST, "Invalid ID").into_response();
+    }
+    let result = state.docker.unpause_service(&id).await;
+    audit(&state, &caller, "unpause", Some(&id), &result).await;
+    match result {
+        Ok(_) => {
+            broadcast_pause_state(&state, &id, false).await;
+            (StatusCode::OK, "Unpaused").into_response()
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+// UI'ın bir sonraki taramayı beklemeden güncellenmesi için önbellek düzeltilip yayınlanır.
+async fn broadcast_pause_state(state: &Arc<AppState>, id: &str, paused: bool) {
+    let services: Vec<ServiceInstance> = {
+        let mut cache = state.services_cache.write().await;
+        if let Some(svc) = cache
+            .values_mut()
+            .find(|s| s.name == id || s.short_id == id)
+        {
+            let base = svc.status.replace(" (Paused)", "");
+            svc.status = if paused {
+                format!("{} (Paused)", base)
+            } else {
+                base
+            };
+        }
+        cache.values().cloned().collect()
+    };
+    state.tx.send(
+        WsMessage::ServicesUpdate {
+            data: ServicesSnapshot {
+                node: state.config.node_name.clone(),
+                services,
+            },
+        }
+        .encode(),
+    );
+}
+
+pub(crate) async fn audit<T, E: std::fmt::Display>(
+    state: &AppState,
+    caller: &Caller,
+    action: &str,
+    service: Option<&str>,
+    result: &Result<T, E>,
+) {
+    state
+        .audit
+        .record(AuditEntry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            action: action.to_string(),
+            service: service.map(str::to_string),
+            node: state.config.node_name.clone(),
+            source_ip: caller.ip.clone(),
+            scope: caller.scope.map(|s| s.to_string()),
+            result: match result {
+                Ok(_) => "ok".to_string(),
+                Err(e) => format!("error: {}", e),
+            },
+        })
+        .await;
+}
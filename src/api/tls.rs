@@ -0,0 +1,121 @@
+// src/api/tls.rs
+use anyhow::Context;
+use axum::extract::ConnectInfo;
+use axum::{Extension, Router};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto::Builder;
+use hyper_util::service::TowerToHyperService;
+use rustls::server::WebPkiClientVerifier;
+use rustls::ServerConfig;
+use std::io::BufReader;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+use tracing::{debug, info, warn};
+
+use crate::config::AppConfig;
+
+pub(crate) const ACCEPT_ERROR_BACKOFF: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// TLS_CERT/TLS_KEY tanımlıysa HTTP sunucusu HTTPS olarak açılır. TLS_CLIENT_CA verilirse
+/// istemci sertifikası zorunludur (mTLS): sadece bu CA'nın imzaladığı edge node'lar bağlanabilir.
+pub async fn serve(
+    listener: TcpListener,
+    app: Router,
+    cert: &str,
+    key: &str,
+    client_ca: Option<&str>,
+) -> anyhow::Result<()> {
+    let acceptor = TlsAcceptor::from(Arc::new(server_config(cert, key, client_ca)?));
+    info!(
+        event = "HTTPS_LISTENING",
+        mtls = client_ca.is_some(),
+        "🔐 HTTPS server listening on {}",
+        listener.local_addr()?
+    );
+
+    loop {
+        // EMFILE/ECONNABORTED gibi geçici accept hataları sunucuyu düşürmez (axum::serve ile aynı davranış).
+        let (stream, addr) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!(event="HTTPS_ACCEPT_ERROR", error=%e, "⚠️ Failed to accept connection, retrying.");
+                tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                continue;
+            }
+        };
+        let acceptor = acceptor.clone();
+        // Caller extractor'ı TCP sunucusundaki gibi istemci adresini ConnectInfo'dan okur.
+        let service = TowerToHyperService::new(app.clone().layer(Extension(ConnectInfo(addr))));
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(stream).await {
+                Ok(s) => s,
+                Err(e) => {
+                    debug!(event="TLS_HANDSHAKE_FAIL", peer=%addr, error=%e, "TLS handshake failed.");
+                    return;
+                }
+            };
+            if let Err(e) = Builder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .await
+            {
+                debug!(event="HTTPS_CONN_ERROR", error=%e, "HTTPS connection closed with error.");
+            }
+        });
+    }
+}
+
+fn server_config(cert: &str, key: &str, client_ca: Option<&str>) -> anyhow::Result<ServerConfig> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let certs = rustls_pemfile::certs(&mut BufReader::new(
+        std::fs::File::open(cert).with_context(|| format!("TLS_CERT '{}'", cert))?,
+    ))
+    .collect::<Result<Vec<_>, _>>()?;
+    let key = rustls_pemfile::private_key(&mut BufReader::new(
+        std::fs::File::open(key).with_context(|| format!("TLS_KEY '{}'", key))?,
+    ))?
+    .ok_or_else(|| anyhow::anyhow!("No private key found in TLS_KEY"))?;
+
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()?;
+    let builder = match client_ca {
+        Some(ca) => {
+            let mut roots = rustls::RootCertStore::empty();
+            for c in rustls_pemfile::certs(&mut BufReader::new(
+                std::fs::File::open(ca).with_context(|| format!("TLS_CLIENT_CA '{}'", ca))?,
+            )) {
+                roots.add(c?)?;
+            }
+            let verifier =
+                WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider).build()?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut config = builder.with_single_cert(certs, key)?;
+    // WebSocket upgrade'leri HTTP/2 üzerinde desteklenmediği için sadece HTTP/1.1 sunulur.
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+/// Upstream'e (ingest + komut kanalı) giden bağlantılar için TLS ayarı: özel CA ve
+/// opsiyonel istemci sertifikası (mTLS). Hiçbiri tanımlı değilse sistem varsayılanı kullanılır.
+pub fn upstream_connector(cfg: &AppConfig) -> anyhow::Result<Option<native_tls::TlsConnector>> {
+    if cfg.upstream_ca_cert.is_none() && cfg.upstream_client_cert.is_none() {
+        return Ok(None);
+    }
+    let mut builder = native_tls::TlsConnector::builder();
+    if let Some(ca) = &cfg.upstream_ca_cert {
+        let pem = std::fs::read(ca).with_context(|| format!("UPSTREAM_CA_CERT '{}'", ca))?;
+        builder.add_root_certificate(native_tls::Certificate::from_pem(&pem)?);
+    }
+    if let (Some(cert), Some(key)) = (&cfg.upstream_client_cert, &cfg.upstream_client_key) {
+        let cert_pem =
+            std::fs::read(cert).with_context(|| format!("UPSTREAM_CLIENT_CERT '{}'", cert))?;
+        let key_pem =
+            std::fs::read(key).with_context(|| format!("UPSTREAM_CLIENT_KEY '{}'", key))?;
+        builder.identity(native_tls::Identity::from_pkcs8(&cert_pem, &key_pem)?);
+    }
+    Ok(Some(builder.build()?))
+}
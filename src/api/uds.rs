@@ -0,0 +1,40 @@
+// src/api/uds.rs
+use axum::Router;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto::Builder;
+use hyper_util::service::TowerToHyperService;
+use tokio::net::UnixListener;
+use tracing::{debug, info, warn};
+
+use crate::api::tls::ACCEPT_ERROR_BACKOFF;
+
+/// Sidecar kurulumları için HTTP sunucusunu TCP portu yerine unix domain socket üzerinde açar.
+pub async fn serve(path: &str, app: Router) -> anyhow::Result<()> {
+    // Önceki çalıştırmadan kalan socket dosyası bind'i engeller.
+    if std::path::Path::new(path).exists() {
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    info!(event="HTTP_UDS_LISTENING", socket.path=%path, "🔌 HTTP server listening on unix socket: {}", path);
+
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                warn!(event="HTTP_UDS_ACCEPT_ERROR", error=%e, "⚠️ Failed to accept unix socket connection, retrying.");
+                tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                continue;
+            }
+        };
+        let service = TowerToHyperService::new(app.clone());
+        tokio::spawn(async move {
+            // WebSocket upgrade'leri için serve_connection_with_upgrades zorunludur.
+            if let Err(e) = Builder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .await
+            {
+                debug!(event="HTTP_UDS_CONN_ERROR", error=%e, "Unix socket connection closed with error.");
+            }
+        });
+    }
+}
@@ -1,71 +1,558 @@
+use crate::core::domain::{AlertFormat, HealthScoreWeights, JanitorSchedule, MaintenanceWindow};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 
+const DEFAULT_CONFIG_FILE: &str = "/etc/sentiric/orchestrator.toml";
+
 #[derive(Debug, Clone)]
 pub struct AppConfig {
     pub env: String,
+    // Birden fazla bağımsız cluster'ı alarm/dashboard'da ayırt etmek için kimlik
+    pub cluster_name: String,
     pub node_name: String,
     pub host: String,
     pub http_port: u16,
-    pub _grpc_port: u16,
+    pub grpc_port: u16,
+    // GRPC_PORT'ta grpc.reflection.v1alpha sunulur (grpcurl keşfi); varsayılan kapalı
+    pub grpc_reflection: bool,
     pub docker_socket: String,
+    // Monitor, tarama ve upstream rapor aralığı (saniye)
     pub poll_interval: u64,
+    // Bu süre boyunca rapor gelmeyen node OFFLINE sayılır; POLL_INTERVAL'in birkaç katı olmalıdır
+    pub node_offline_secs: i64,
+    // OFFLINE node bu süreyi (saniye) aşınca cluster listesinden tamamen düşürülür
+    pub node_evict_secs: i64,
+    pub watchdog_interval_secs: u64,
     pub auto_pilot_services: Vec<String>,
     pub upstream_url: Option<String>,
+    // Tanımlıysa /api ve /ws uçları bearer token ister (opt-in)
+    pub api_token: Option<String>,
+    // Rol bazlı erişim: viewer sadece okur, admin (veya API_TOKEN) her şeyi yapar
+    pub viewer_token: Option<String>,
+    pub admin_token: Option<String>,
+    // Edge node -> upstream raporları ve komut kanalı için paylaşılan sır (x-node-token)
+    pub node_auth_token: Option<String>,
     // [ARCH-COMPLIANCE] Tenant ID zorunluluğu eklendi
     pub tenant_id: String,
+    // Log WebSocket'lerinin azami açık kalma süresi (0 = sınırsız)
+    pub log_stream_max_secs: u64,
+    // Tanımlıysa HTTP sunucusu TCP yerine bu unix socket üzerinde açılır
+    pub http_uds_path: Option<String>,
+    // HTTPS sunucu sertifikası/anahtarı (varsayılan: düz HTTP); TLS_CLIENT_CA ile mTLS zorunlu olur
+    pub tls_cert: Option<String>,
+    pub tls_key: Option<String>,
+    pub tls_client_ca: Option<String>,
+    // `https://` upstream için özel CA ve opsiyonel istemci sertifikası (mTLS)
+    pub upstream_ca_cert: Option<String>,
+    pub upstream_client_cert: Option<String>,
+    pub upstream_client_key: Option<String>,
+    // Değiştiren aksiyonların JSON-lines denetim kaydı (boş ise kapalı) ve döndürme sınırı
+    pub audit_log_path: Option<String>,
+    pub audit_log_max_bytes: u64,
+    // Tanımlıysa node metrikleri ve servis durum geçişleri SQLite'a yazılır
+    pub database_path: Option<String>,
+    // Node, auto-pilot ve prune alarmlarının POST edileceği webhook (boş ise kapalı) ve gövde biçimi
+    pub alert_webhook_url: Option<String>,
+    pub alert_format: AlertFormat,
+    // Auto-pilot tercihlerinin kalıcı tutulduğu JSON dosyası
+    pub autopilot_state_path: String,
+    // Güncelleme öncesi container konfigürasyon yedekleri (keep = 0 ise kapalı)
+    pub backup_dir: Option<String>,
+    pub backup_keep: usize,
+    // Global güncelleme hook'ları (servis bazında `orch.prehook`/`orch.posthook` label'ları önceliklidir)
+    pub update_prehook: Option<String>,
+    pub update_posthook: Option<String>,
+    // Özel registry kimlik bilgileri (`~/.docker/config.json` ile birlikte kullanılır)
+    pub registry_url: Option<String>,
+    pub registry_username: Option<String>,
+    pub registry_password: Option<String>,
+    // Güvenlik gereği varsayılan kapalı: `/api/service/:id/exec`
+    pub enable_exec: bool,
+    // Label (`sentiric.stop_timeout`) tanımlı değilse stop/restart için graceful süre (saniye)
+    pub stop_timeout: i64,
+    // Güncelleme sonrası sağlık doğrulaması için bekleme süresi (saniye)
+    pub update_health_timeout: u64,
+    // Geçici pull hatalarında toplam deneme sayısı (2s'den başlayan üstel bekleme)
+    pub pull_max_attempts: u32,
+    // Docker bağlantısı bu süre boyunca geri gelmezse süreç sonlanır (varsayılan: kapalı)
+    pub exit_on_docker_loss: bool,
+    pub docker_loss_exit_secs: u64,
+    pub health_score_weights: HealthScoreWeights,
+    // CPU/GPU sıcaklığı bu değeri (°C) aşınca UI'a `temperature_warning` yayınlanır
+    pub temp_warn_c: f32,
+    // Dashboard'u başka origin'den gömmek için izinli origin'ler ("*" = hepsi); boşsa CORS başlığı yok
+    pub cors_allowed_origins: Vec<String>,
+    // X-Forwarded-For sadece bu adreslerden (IP veya CIDR; unix socket için `unix`) gelirse dikkate alınır
+    pub trusted_proxies: Vec<String>,
+    // Değiştiren /api uçları için istemci IP'si başına dakikalık istek sınırı (0 = kapalı)
+    pub rate_limit_per_min: u32,
+    // Zamanlanmış otomatik prune ("daily at 03:00" / "every 6h"); sürpriz silme olmasın diye varsayılan kapalı
+    pub janitor_schedule: JanitorSchedule,
+    // Orchestrator kendi güncellemesini yardımcı container'a devredebilir (varsayılan kapalı)
+    pub allow_self_update: bool,
+    // `GET /api/service/:id/env` yanıtında değeri gizlenen anahtar desenleri (`*` joker)
+    pub env_redact_patterns: Vec<String>,
+    // İmaj platformunu elle sabitler (ör. `linux/arm64`); boşsa daemon'dan tespit edilir
+    pub force_platform: Option<String>,
+    // Auto-pilot sadece bu pencerede güncelleme uygular ("22:00-06:00"); boşsa her zaman açıktır
+    pub autopilot_window: Option<MaintenanceWindow>,
+    // Auto-pilot güncelleme kontrolü aralığı (saniye); varsayılan 12 x POLL_INTERVAL
+    pub autopilot_interval_secs: u64,
+    // Pencere dışında bulunan güncellemelerin restart sonrası kaybolmaması için tutulduğu dosya
+    pub autopilot_pending_path: String,
+    // UI yayın kanalı kapasitesi (mesaj). Kanal her mesajı en yavaş abone okuyana dek tutar:
+    // bellek ≈ kapasite × ortalama mesaj boyu (cluster_update büyük filolarda onlarca KB olabilir).
+    pub broadcast_capacity: usize,
+    // UI/log WebSocket'lerine gönderilen Ping aralığı (0 = kapalı); boşta kalan bağlantıyı
+    // kesen proxy'lere karşı bağlantıyı canlı tutar.
+    pub ws_ping_interval_secs: u64,
+    // Node'un rapor ettiği gruplama etiketleri (ör. "dc-ist,media"); büyük filolarda UI gruplaması için
+    pub node_tags: Vec<String>,
+    // Yeni bağlanan WS/SSE istemcisine tekrar oynatılan son olay sayısı (0 = kapalı)
+    pub event_replay_len: usize,
+    // `/api/service/create` ile bind mount'a izin verilen host yolu önekleri; boşsa sadece named volume
+    pub create_host_path_allowlist: Vec<String>,
 }
 
 impl AppConfig {
     pub fn load() -> Self {
-        let ap_raw = env::var("AUTO_PILOT_SERVICES").unwrap_or_default();
+        let src = ConfigSource::load();
+        let ap_raw = src.var("AUTO_PILOT_SERVICES").unwrap_or_default();
         let ap_list = ap_raw
             .split(',')
             .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty())
             .collect();
 
-        let upstream = env::var("UPSTREAM_ORCHESTRATOR_URL")
+        let upstream = src
+            .var("UPSTREAM_ORCHESTRATOR_URL")
             .ok()
             .filter(|s| !s.trim().is_empty());
 
         // [ARCH-COMPLIANCE] Tenant izolasyon kuralı: Boş olması YASAKTIR.
-        let tenant_id = env::var("TENANT_ID").unwrap_or_default();
+        let tenant_id = src.var("TENANT_ID").unwrap_or_default();
         if tenant_id.trim().is_empty() {
             panic!("[ARCH-COMPLIANCE] TENANT_ID ortam değişkeni ZORUNLUDUR ve boş olamaz. Servis başlatılamaz.");
         }
 
+        let poll_interval: u64 = src
+            .var("POLL_INTERVAL")
+            .unwrap_or("5".to_string())
+            .parse()
+            .unwrap_or(5);
+
         Self {
-            env: env::var("ENV").unwrap_or_else(|_| "production".into()),
-            node_name: env::var("NODE_NAME")
+            env: src.var("ENV").unwrap_or_else(|_| "production".into()),
+            cluster_name: src
+                .var("CLUSTER_NAME")
+                .ok()
+                .filter(|s| !s.trim().is_empty())
+                .unwrap_or_else(|| "default".into()),
+            node_name: src
+                .var("NODE_NAME")
                 .unwrap_or_else(|_| {
                     hostname::get()
                         .map(|h| h.to_string_lossy().into_owned())
                         .unwrap_or("NEXUS-NODE".into())
                 })
                 .to_uppercase(),
-            host: env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
-            http_port: env::var("HTTP_PORT")
+            host: src.var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
+            http_port: src
+                .var("HTTP_PORT")
                 .unwrap_or("11080".to_string())
                 .parse()
                 .unwrap_or(11080),
-            _grpc_port: env::var("GRPC_PORT")
+            grpc_port: src
+                .var("GRPC_PORT")
                 .unwrap_or("11081".to_string())
                 .parse()
                 .unwrap_or(11081),
-            docker_socket: env::var("DOCKER_SOCKET").unwrap_or_else(|_| {
+            grpc_reflection: src
+                .var("GRPC_REFLECTION")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false),
+            docker_socket: src.var("DOCKER_SOCKET").unwrap_or_else(|_| {
                 if cfg!(target_os = "windows") {
                     "//./pipe/docker_engine".into()
                 } else {
                     "/var/run/docker.sock".into()
                 }
             }),
-            poll_interval: env::var("POLL_INTERVAL")
-                .unwrap_or("5".to_string())
+            poll_interval,
+            node_offline_secs: src
+                .var("NODE_OFFLINE_SECS")
+                .unwrap_or("30".to_string())
                 .parse()
-                .unwrap_or(5),
+                .unwrap_or(30),
+            node_evict_secs: src
+                .var("NODE_EVICT_SECS")
+                .unwrap_or("3600".to_string())
+                .parse()
+                .unwrap_or(3600),
+            watchdog_interval_secs: src
+                .var("WATCHDOG_INTERVAL_SECS")
+                .unwrap_or("10".to_string())
+                .parse::<u64>()
+                .unwrap_or(10)
+                .max(1),
             auto_pilot_services: ap_list,
             upstream_url: upstream,
+            api_token: src.var("API_TOKEN").ok().filter(|s| !s.trim().is_empty()),
+            viewer_token: src
+                .var("VIEWER_TOKEN")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            admin_token: src.var("ADMIN_TOKEN").ok().filter(|s| !s.trim().is_empty()),
+            node_auth_token: src
+                .var("NODE_AUTH_TOKEN")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
             tenant_id,
+            log_stream_max_secs: src
+                .var("LOG_STREAM_MAX_SECS")
+                .unwrap_or("1800".to_string())
+                .parse()
+                .unwrap_or(1800),
+            http_uds_path: src
+                .var("HTTP_UDS_PATH")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            tls_cert: src.var("TLS_CERT").ok().filter(|s| !s.trim().is_empty()),
+            tls_key: src.var("TLS_KEY").ok().filter(|s| !s.trim().is_empty()),
+            tls_client_ca: src
+                .var("TLS_CLIENT_CA")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            upstream_ca_cert: src
+                .var("UPSTREAM_CA_CERT")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            upstream_client_cert: src
+                .var("UPSTREAM_CLIENT_CERT")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            upstream_client_key: src
+                .var("UPSTREAM_CLIENT_KEY")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            audit_log_path: Some(
+                src.var("AUDIT_LOG_PATH")
+                    .unwrap_or_else(|_| "/var/lib/sentiric/audit.jsonl".into()),
+            )
+            .filter(|s| !s.trim().is_empty()),
+            audit_log_max_bytes: src
+                .var("AUDIT_LOG_MAX_BYTES")
+                .unwrap_or("10485760".to_string())
+                .parse()
+                .unwrap_or(10_485_760),
+            database_path: src
+                .var("DATABASE_PATH")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            alert_webhook_url: src
+                .var("ALERT_WEBHOOK_URL")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            alert_format: AlertFormat::parse(&src.var("ALERT_FORMAT").unwrap_or_default()),
+            autopilot_state_path: src
+                .var("AUTOPILOT_STATE_PATH")
+                .unwrap_or_else(|_| "/var/lib/sentiric/autopilot.json".into()),
+            backup_dir: Some(
+                src.var("BACKUP_DIR")
+                    .unwrap_or_else(|_| "/var/lib/sentiric/backups".into()),
+            )
+            .filter(|s| !s.trim().is_empty()),
+            backup_keep: src
+                .var("BACKUP_KEEP")
+                .unwrap_or("5".to_string())
+                .parse()
+                .unwrap_or(5),
+            update_prehook: src
+                .var("UPDATE_PREHOOK")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            update_posthook: src
+                .var("UPDATE_POSTHOOK")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            registry_url: src
+                .var("REGISTRY_URL")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            registry_username: src
+                .var("REGISTRY_USERNAME")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            registry_password: src
+                .var("REGISTRY_PASSWORD")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            enable_exec: src
+                .var("ENABLE_EXEC")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false),
+            stop_timeout: src
+                .var("STOP_TIMEOUT")
+                .unwrap_or("10".to_string())
+                .parse()
+                .unwrap_or(10),
+            update_health_timeout: src
+                .var("UPDATE_HEALTH_TIMEOUT")
+                .unwrap_or("15".to_string())
+                .parse()
+                .unwrap_or(15),
+            pull_max_attempts: src
+                .var("PULL_MAX_ATTEMPTS")
+                .unwrap_or("3".to_string())
+                .parse()
+                .unwrap_or(3),
+            exit_on_docker_loss: src
+                .var("EXIT_ON_DOCKER_LOSS")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false),
+            docker_loss_exit_secs: src
+                .var("DOCKER_LOSS_EXIT_SECS")
+                .unwrap_or("300".to_string())
+                .parse()
+                .unwrap_or(300),
+            health_score_weights: Self::parse_health_score_weights(&src),
+            temp_warn_c: src
+                .var("TEMP_WARN_C")
+                .unwrap_or("85".to_string())
+                .parse()
+                .unwrap_or(85.0),
+            cors_allowed_origins: src
+                .var("CORS_ALLOWED_ORIGINS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            trusted_proxies: src
+                .var("TRUSTED_PROXIES")
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            rate_limit_per_min: src
+                .var("RATE_LIMIT_PER_MIN")
+                .unwrap_or("60".to_string())
+                .parse()
+                .unwrap_or(60),
+            janitor_schedule: JanitorSchedule::parse(
+                &src.var("JANITOR_SCHEDULE").unwrap_or_default(),
+            ),
+            allow_self_update: src
+                .var("ALLOW_SELF_UPDATE")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false),
+            env_redact_patterns: src
+                .var("ENV_REDACT_PATTERNS")
+                .unwrap_or_else(|_| "*PASSWORD*,*SECRET*,*TOKEN*,*KEY*".into())
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            force_platform: src
+                .var("FORCE_PLATFORM")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            autopilot_window: src
+                .var("AUTOPILOT_WINDOW")
+                .ok()
+                .filter(|s| !s.trim().is_empty())
+                .map(|w| {
+                    MaintenanceWindow::parse(&w, &src.var("AUTOPILOT_TZ").unwrap_or_default())
+                        .unwrap_or_else(|e| panic!("[CONFIG] {}", e))
+                }),
+            autopilot_interval_secs: src
+                .var("AUTOPILOT_INTERVAL_SECS")
+                .unwrap_or((12 * poll_interval).to_string())
+                .parse()
+                .unwrap_or(12 * poll_interval),
+            autopilot_pending_path: src
+                .var("AUTOPILOT_PENDING_PATH")
+                .unwrap_or_else(|_| "/var/lib/sentiric/autopilot_pending.json".into()),
+            broadcast_capacity: src
+                .var("BROADCAST_CAPACITY")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(100)
+                .max(16),
+            ws_ping_interval_secs: src
+                .var("WS_PING_INTERVAL")
+                .unwrap_or("30".to_string())
+                .parse()
+                .unwrap_or(30),
+            node_tags: src
+                .var("NODE_TAGS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            event_replay_len: src
+                .var("EVENT_REPLAY_LEN")
+                .unwrap_or("50".to_string())
+                .parse()
+                .unwrap_or(50),
+            create_host_path_allowlist: src
+                .var("CREATE_HOST_PATH_ALLOWLIST")
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().trim_end_matches('/').to_string())
+                .filter(|s| s.starts_with('/'))
+                .collect(),
         }
     }
+
+    /// HEALTH_SCORE_WEIGHTS="cpu=0.3,ram=0.4,gpu=0.3" ve HEALTH_SCORE_SERVICE_PENALTY=10
+    fn parse_health_score_weights(src: &ConfigSource) -> HealthScoreWeights {
+        let mut weights = HealthScoreWeights::default();
+        for pair in src
+            .var("HEALTH_SCORE_WEIGHTS")
+            .unwrap_or_default()
+            .split(',')
+        {
+            let Some((k, v)) = pair.split_once('=') else {
+                continue;
+            };
+            let Ok(v) = v.trim().parse::<f64>() else {
+                continue;
+            };
+            match k.trim() {
+                "cpu" => weights.cpu = v,
+                "ram" => weights.ram = v,
+                "gpu" => weights.gpu = v,
+                _ => {}
+            }
+        }
+        if let Ok(p) = src
+            .var("HEALTH_SCORE_SERVICE_PENALTY")
+            .unwrap_or_default()
+            .parse()
+        {
+            weights.service_penalty = p;
+        }
+        weights
+    }
+}
+
+/// CONFIG_FILE (TOML) aynası. Anahtarlar env değişkenlerinin küçük harfli halidir
+/// (ör. `POLL_INTERVAL` -> `poll_interval`); bilinmeyen anahtar başlangıçta hata verir.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct FileConfig {
+    env: Option<String>,
+    cluster_name: Option<String>,
+    node_name: Option<String>,
+    host: Option<String>,
+    http_port: Option<u16>,
+    grpc_port: Option<u16>,
+    grpc_reflection: Option<bool>,
+    docker_socket: Option<String>,
+    poll_interval: Option<u64>,
+    node_offline_secs: Option<i64>,
+    node_evict_secs: Option<i64>,
+    watchdog_interval_secs: Option<u64>,
+    auto_pilot_services: Option<Vec<String>>,
+    upstream_orchestrator_url: Option<String>,
+    api_token: Option<String>,
+    viewer_token: Option<String>,
+    admin_token: Option<String>,
+    node_auth_token: Option<String>,
+    tenant_id: Option<String>,
+    log_stream_max_secs: Option<u64>,
+    http_uds_path: Option<String>,
+    tls_cert: Option<String>,
+    tls_key: Option<String>,
+    tls_client_ca: Option<String>,
+    upstream_ca_cert: Option<String>,
+    upstream_client_cert: Option<String>,
+    upstream_client_key: Option<String>,
+    audit_log_path: Option<String>,
+    audit_log_max_bytes: Option<u64>,
+    database_path: Option<String>,
+    alert_webhook_url: Option<String>,
+    alert_format: Option<String>,
+    autopilot_state_path: Option<String>,
+    backup_dir: Option<String>,
+    backup_keep: Option<usize>,
+    update_prehook: Option<String>,
+    update_posthook: Option<String>,
+    registry_url: Option<String>,
+    registry_username: Option<String>,
+    registry_password: Option<String>,
+    enable_exec: Option<bool>,
+    stop_timeout: Option<i64>,
+    update_health_timeout: Option<u64>,
+    pull_max_attempts: Option<u32>,
+    exit_on_docker_loss: Option<bool>,
+    docker_loss_exit_secs: Option<u64>,
+    health_score_weights: Option<String>,
+    health_score_service_penalty: Option<f64>,
+    temp_warn_c: Option<f32>,
+    cors_allowed_origins: Option<Vec<String>>,
+    trusted_proxies: Option<Vec<String>>,
+    rate_limit_per_min: Option<u32>,
+    janitor_schedule: Option<String>,
+    allow_self_update: Option<bool>,
+    env_redact_patterns: Option<Vec<String>>,
+    force_platform: Option<String>,
+    autopilot_window: Option<String>,
+    autopilot_tz: Option<String>,
+    autopilot_interval_secs: Option<u64>,
+    autopilot_pending_path: Option<String>,
+    broadcast_capacity: Option<usize>,
+    ws_ping_interval: Option<u64>,
+    node_tags: Option<Vec<String>>,
+    event_replay_len: Option<usize>,
+    create_host_path_allowlist: Option<Vec<String>>,
+}
+
+// Env değişkeni her zaman önceliklidir; yoksa dosyadaki değer aynı string biçimiyle döner,
+// böylece tüm ayrıştırma ve varsayılan mantığı tek yerde kalır.
+struct ConfigSource {
+    file: HashMap<String, String>,
+}
+
+impl ConfigSource {
+    fn load() -> Self {
+        let path = env::var("CONFIG_FILE")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_CONFIG_FILE.to_string());
+
+        let raw = match std::fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => panic!("[CONFIG] '{}' okunamadı: {}", path, e),
+        };
+        let parsed: FileConfig =
+            toml::from_str(&raw).unwrap_or_else(|e| panic!("[CONFIG] '{}' geçersiz: {}", path, e));
+
+        let mut file = HashMap::new();
+        if let Ok(serde_json::Value::Object(map)) = serde_json::to_value(parsed) {
+            for (key, value) in map {
+                let value = match value {
+                    serde_json::Value::Null => continue,
+                    serde_json::Value::String(s) => s,
+                    serde_json::Value::Array(items) => items
+                        .iter()
+                        .filter_map(|i| i.as_str())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                    other => other.to_string(),
+                };
+                file.insert(key, value);
+            }
+        }
+        Self { file }
+    }
+
+    fn var(&self, key: &str) -> Result<String, env::VarError> {
+        env::var(key).or_else(|e| self.file.get(&key.to_lowercase()).cloned().ok_or(e))
+    }
 }
@@ -0,0 +1,104 @@
+// src/core/broadcast.rs
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+use crate::core::domain::REPLAY_EVENT_TYPES;
+
+#[derive(Deserialize)]
+struct MessageType<'a> {
+    #[serde(rename = "type", borrow)]
+    kind: &'a str,
+}
+
+/// UI yayın kanalı (/ws, /sse). Tüm üreticiler `send` kullanır: geçici olaylar gönderimle aynı kilit
+/// altında replay tamponuna yazılır. `subscribe_with_replay` de bu kilidi tuttuğu için bir olay ya
+/// tamponda ya da canlı akışta görünür, ikisinde birden asla.
+pub struct Broadcaster {
+    tx: broadcast::Sender<String>,
+    recent: Mutex<VecDeque<String>>,
+    replay_len: usize,
+}
+
+impl Broadcaster {
+    pub fn new(capacity: usize, replay_len: usize) -> Self {
+        Self {
+            tx: broadcast::channel(capacity).0,
+            recent: Mutex::new(VecDeque::with_capacity(replay_len)),
+            replay_len,
+        }
+    }
+
+    pub fn send(&self, msg: String) {
+        let mut recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(tagged) = self.replay_copy(&msg) {
+            if recent.len() >= self.replay_len {
+                recent.pop_front();
+            }
+            recent.push_back(tagged);
+        }
+        let _ = self.tx.send(msg);
+    }
+
+    /// Canlı abonelik ve o ana kadarki geçici olaylar (`replayed: true` ile etiketli).
+    pub fn subscribe_with_replay(&self) -> (broadcast::Receiver<String>, Vec<String>) {
+        let recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        (self.tx.subscribe(), recent.iter().cloned().collect())
+    }
+
+    // Henüz en yavaş abonenin okumadığı mesaj sayısı (/metrics)
+    pub fn queued(&self) -> usize {
+        self.tx.len()
+    }
+
+    pub fn receiver_count(&self) -> usize {
+        self.tx.receiver_count()
+    }
+
+    // Durum taşıyan büyük mesajlar (cluster_update...) sadece `type` alanı okunarak elenir.
+    fn replay_copy(&self, msg: &str) -> Option<String> {
+        if self.replay_len == 0 {
+            return None;
+        }
+        let kind = serde_json::from_str::<MessageType>(msg).ok()?.kind;
+        if !REPLAY_EVENT_TYPES.contains(&kind) {
+            return None;
+        }
+        let serde_json::Value::Object(mut obj) = serde_json::from_str(msg).ok()? else {
+            return None;
+        };
+        obj.insert("replayed".into(), serde_json::Value::Bool(true));
+        Some(serde_json::Value::Object(obj).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_keeps_only_recent_transient_events() {
+        let b = Broadcaster::new(16, 2);
+        b.send(r#"{"v":1,"type":"cluster_update","data":{}}"#.into());
+        for n in 0..3 {
+            b.send(format!(r#"{{"v":1,"type":"prune_result","n":{}}}"#, n));
+        }
+        let (_rx, replay) = b.subscribe_with_replay();
+        assert_eq!(replay.len(), 2);
+        assert!(replay[0].contains(r#""n":1"#) && replay[0].contains(r#""replayed":true"#));
+        assert!(replay[1].contains(r#""n":2"#));
+    }
+
+    #[test]
+    fn event_is_either_replayed_or_live_never_both() {
+        let b = Broadcaster::new(16, 8);
+        b.send(r#"{"v":1,"type":"oom_killed"}"#.into());
+        let (mut rx, replay) = b.subscribe_with_replay();
+        b.send(r#"{"v":1,"type":"disk_warning"}"#.into());
+        assert_eq!(replay.len(), 1);
+        assert!(replay[0].contains("oom_killed"));
+        assert!(rx.try_recv().unwrap().contains("disk_warning"));
+        assert!(rx.try_recv().is_err());
+    }
+}
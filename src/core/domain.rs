@@ -1,5 +1,7 @@
 // src/core/domain.rs
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum HealthStatus {
@@ -13,6 +15,9 @@ pub enum HealthStatus {
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ServiceInstance {
     pub name: String,
+    // `sentiric.display_name` label'ı; sadece gösterim içindir, aksiyonlar `name` ile yapılır
+    #[serde(default)]
+    pub display_name: Option<String>,
     pub image: String,
     pub status: String,
     pub short_id: String,
@@ -33,28 +38,295 @@ pub struct ServiceInstance {
 
     pub health: HealthStatus,
     pub violations: Vec<String>,
+
+    // Docker HEALTHCHECK durumu (none/healthy/unhealthy/starting)
+    #[serde(default)]
+    pub healthcheck: Option<String>,
+    #[serde(default)]
+    pub healthcheck_failing_streak: i64,
+
+    // Docker `RestartCount` ve `State.StartedAt` (RFC3339); uptime sadece çalışan container için dolar
+    #[serde(default)]
+    pub restart_count: u64,
+    #[serde(default)]
+    pub started_at: String,
+    #[serde(default)]
+    pub uptime_secs: u64,
+    // Son tarama penceresinde eşikten fazla yeniden başladı (UI'da vurgulanır)
+    #[serde(default)]
+    pub crash_looping: bool,
+    // Son çıkış kernel OOM killer yüzünden (State.OOMKilled)
+    #[serde(default)]
+    pub oom_killed: bool,
+    // Durmuş container'ın son çıkış kodu (çalışırken 0)
+    #[serde(default)]
+    pub exit_code: i32,
+    // Host'a yayınlanmış portlar (yayın yoksa boş)
+    #[serde(default)]
+    pub ports: Vec<PortMapping>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PortMapping {
+    pub container_port: u16,
+    pub host_port: u16,
+    // "tcp", "udp" veya "sctp"
+    #[serde(default)]
+    pub protocol: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MetricSample {
+    pub ts: String, // ISO8601
+    pub cpu: f64,
+    pub mem: u64, // MB
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct NodeStats {
     pub name: String,
     pub cpu_usage: f32,
+    // Çekirdek bazında kullanım (%); eski edge raporlarında boş gelir
+    #[serde(default)]
+    pub per_core_usage: Vec<f32>,
     pub ram_used: u64,  // MB
     pub ram_total: u64, // MB
+    // Swap kapalıysa 0
+    #[serde(default)]
+    pub swap_used: u64, // MB
+    #[serde(default)]
+    pub swap_total: u64, // MB
 
     // [YENİ] Disk Kapasitesi
     pub disk_used: u64,  // GB
     pub disk_total: u64, // GB
+    // Mount noktası bazında doluluk (toplamlar yukarıdaki alanlardadır)
+    #[serde(default)]
+    pub disks: Vec<DiskStat>,
 
     pub gpu_usage: f32,
     pub gpu_mem_used: u64,
     pub gpu_mem_total: u64,
+    #[serde(default)]
+    pub gpu_count: u32,
+    // 1/5/15 dakikalık yük ortalaması (Windows'ta 0) ve host açık kalma süresi
+    #[serde(default)]
+    pub load_avg: [f64; 3],
+    #[serde(default)]
+    pub uptime_secs: u64,
+    // En sıcak sensör (°C); sensör okunamıyorsa None
+    #[serde(default)]
+    pub cpu_temp_c: Option<f32>,
+    #[serde(default)]
+    pub gpu_temp_c: Option<f32>,
 
     pub net_rx_mbs: f64,
     pub net_tx_mbs: f64,
+    // Fiziksel arayüzlerin toplam hızı (byte/s); eski edge raporlarında 0 gelir
+    #[serde(default)]
+    pub net_rx_bps: u64,
+    #[serde(default)]
+    pub net_tx_bps: u64,
 
     pub last_seen: String, // ISO8601
     pub status: String,
+
+    // Bayat veri teşhisi: son tamamlanan container taraması ve son başarılı upstream raporu (ISO8601)
+    #[serde(default)]
+    pub last_scan: Option<String>,
+    #[serde(default)]
+    pub last_report: Option<String>,
+
+    // 0-100 arası özet sağlık skoru (formül: LOGIC.md §4)
+    #[serde(default)]
+    pub health_score: u32,
+
+    // NODE_TAGS ile verilen gruplama etiketleri; etiketsiz node'lar boş liste raporlar
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct DiskStat {
+    pub mount_point: String,
+    pub file_system: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+impl DiskStat {
+    pub fn used_pct(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 0.0;
+        }
+        self.total_bytes.saturating_sub(self.available_bytes) as f64 / self.total_bytes as f64
+            * 100.0
+    }
+}
+
+/// Node sağlık skoru ağırlıkları ve eşikleri.
+#[derive(Debug, Clone)]
+pub struct HealthScoreWeights {
+    pub cpu: f64,
+    pub ram: f64,
+    pub gpu: f64,
+    pub service_penalty: f64,
+}
+
+impl Default for HealthScoreWeights {
+    fn default() -> Self {
+        Self {
+            cpu: 0.3,
+            ram: 0.4,
+            gpu: 0.3,
+            service_penalty: 10.0,
+        }
+    }
+}
+
+/// Alarm webhook gövde biçimi (ALERT_FORMAT).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AlertFormat {
+    #[default]
+    Raw,
+    Slack,
+    Discord,
+}
+
+impl AlertFormat {
+    pub fn parse(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "slack" => Self::Slack,
+            "discord" => Self::Discord,
+            _ => Self::Raw,
+        }
+    }
+}
+
+/// JANITOR_SCHEDULE: "daily at 03:00" (yerel saat) veya "every 6h"; boş/tanınmayan değer kapalıdır.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum JanitorSchedule {
+    #[default]
+    Disabled,
+    Every(std::time::Duration),
+    DailyAt(chrono::NaiveTime),
+}
+
+impl JanitorSchedule {
+    pub fn parse(s: &str) -> Self {
+        let s = s.trim().to_lowercase();
+        if let Some(at) = s.strip_prefix("daily at ") {
+            return chrono::NaiveTime::parse_from_str(at.trim(), "%H:%M")
+                .map(Self::DailyAt)
+                .unwrap_or_default();
+        }
+        if let Some(every) = s.strip_prefix("every ") {
+            let every = every.trim();
+            let (num, unit) = every.split_at(every.len().saturating_sub(1));
+            let secs = match (num.trim().parse::<u64>(), unit) {
+                (Ok(n), "h") => n * 3600,
+                (Ok(n), "m") => n * 60,
+                _ => 0,
+            };
+            if secs > 0 {
+                return Self::Every(std::time::Duration::from_secs(secs));
+            }
+        }
+        Self::Disabled
+    }
+
+    /// Bir sonraki çalıştırmaya kalan süre (kapalıysa `None`).
+    pub fn next_delay(&self, now: chrono::DateTime<chrono::Local>) -> Option<std::time::Duration> {
+        match *self {
+            Self::Disabled => None,
+            Self::Every(d) => Some(d),
+            Self::DailyAt(at) => {
+                let today = now.date_naive().and_time(at);
+                let next = if today > now.naive_local() {
+                    today
+                } else {
+                    today + chrono::Duration::days(1)
+                };
+                (next - now.naive_local()).to_std().ok()
+            }
+        }
+    }
+}
+
+/// AUTOPILOT_WINDOW: "22:00-06:00" biçiminde, gece yarısını aşabilen bakım penceresi.
+/// `offset` boşsa (AUTOPILOT_TZ=local) sunucunun yerel saati kullanılır.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaintenanceWindow {
+    pub start: chrono::NaiveTime,
+    pub end: chrono::NaiveTime,
+    pub offset: Option<chrono::FixedOffset>,
+}
+
+impl MaintenanceWindow {
+    // Hatalı pencere sessizce "her zaman açık"a dönmemeli (güvenli olmayan varsayılan); hata döner.
+    pub fn parse(window: &str, tz: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "invalid AUTOPILOT_WINDOW '{}', expected HH:MM-HH:MM",
+                window
+            )
+        };
+        let (start, end) = window.trim().split_once('-').ok_or_else(invalid)?;
+        let start =
+            chrono::NaiveTime::parse_from_str(start.trim(), "%H:%M").map_err(|_| invalid())?;
+        let end = chrono::NaiveTime::parse_from_str(end.trim(), "%H:%M").map_err(|_| invalid())?;
+        if start == end {
+            return Err(format!(
+                "AUTOPILOT_WINDOW '{}' is empty (start == end)",
+                window
+            ));
+        }
+        let offset = match tz.trim().to_lowercase().as_str() {
+            "" | "local" => None,
+            "utc" | "z" => chrono::FixedOffset::east_opt(0),
+            other => Some(other.parse::<chrono::FixedOffset>().map_err(|_| {
+                format!(
+                    "invalid AUTOPILOT_TZ '{}', expected UTC, local or ±HH:MM",
+                    tz
+                )
+            })?),
+        };
+        Ok(Self { start, end, offset })
+    }
+
+    pub fn contains(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        let t = match self.offset {
+            Some(o) => now.with_timezone(&o).time(),
+            None => now.with_timezone(&chrono::Local).time(),
+        };
+        if self.start <= self.end {
+            t >= self.start && t < self.end
+        } else {
+            t >= self.start || t < self.end
+        }
+    }
+}
+
+impl std::fmt::Display for MaintenanceWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )?;
+        match self.offset {
+            Some(o) => write!(f, " ({})", o),
+            None => write!(f, " (local)"),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PendingUpdates {
+    pub window: Option<String>,
+    pub in_window: bool,
+    pub services: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -65,17 +337,487 @@ pub struct ClusterReport {
     pub timestamp: String,
 }
 
+/// Node adı -> son rapor. Raporlar `Arc` içinde tutulur: snapshot almak kilit altında sadece
+/// Arc kopyalar, serileştirme kilit dışında yapılır.
+pub type ClusterMap = HashMap<String, Arc<ClusterReport>>;
+
+// --- YAYIN KANALI SÖZLEŞMESİ (/ws, /sse) ---
+/// İstemci bu değer farklıysa mesaj biçiminin değiştiğini anlar; alan eklemek sürümü artırmaz,
+/// alan silmek/yeniden adlandırmak artırır.
+pub const WS_SCHEMA_VERSION: u32 = 1;
+/// Geçici olaylar: yeni bağlanan istemciye `replayed: true` ile tekrar oynatılır. Durum taşıyan
+/// mesajlar (cluster/services/docker_event) snapshot'la zaten güncel geldiği için dahil edilmez.
+pub const REPLAY_EVENT_TYPES: [&str; 7] = [
+    "node_status_changed",
+    "disk_warning",
+    "temperature_warning",
+    "prune_result",
+    "oom_killed",
+    "lifecycle",
+    "auto_pilot_result",
+];
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+// Cluster haritası ödünç alınır; çağıran kilit dışında alınmış bir snapshot verir.
+pub enum WsMessage<'a> {
+    ClusterUpdate {
+        data: &'a ClusterMap,
+    },
+    // Node eklendi/silindi: UI kart listesini bu haritaya göre yeniden kurar
+    NodesListUpdate {
+        data: &'a ClusterMap,
+    },
+    ServicesUpdate {
+        data: ServicesSnapshot,
+    },
+    DockerEvent {
+        action: String,
+        container: String,
+        status: String,
+    },
+    UpdateProgress {
+        data: UpdateProgressData,
+    },
+    DiskWarning {
+        data: DiskWarning,
+    },
+    TemperatureWarning {
+        data: TemperatureWarning,
+    },
+    PruneResult {
+        data: PruneResultData,
+    },
+    // Watchdog node'u OFFLINE işaretledi veya node tekrar rapor verdi
+    NodeStatusChanged {
+        data: NodeStatusChange,
+    },
+    // Container kernel OOM killer tarafından sonlandırıldı (event akışı veya tarama)
+    OomKilled {
+        data: OomKilledData,
+    },
+    Lifecycle {
+        data: LifecycleEvent,
+    },
+    // Auto-pilot turunda güncellenen veya güncellenemeyen servis (güncel olanlar yayınlanmaz)
+    AutoPilotResult {
+        data: AutoPilotResultData,
+    },
+    // İstemci yayını yetiştiremedi; ardından tam cluster görüntüsü gelir
+    Lagged {
+        skipped: u64,
+    },
+}
+
+#[derive(Serialize)]
+struct WsEnvelope<'a> {
+    v: u32,
+    #[serde(flatten)]
+    msg: &'a WsMessage<'a>,
+}
+
+impl WsMessage<'_> {
+    /// `{ "v": WS_SCHEMA_VERSION, "type": ..., ... }` biçiminde tek seferlik serileştirme.
+    pub fn encode(&self) -> String {
+        serde_json::to_string(&WsEnvelope {
+            v: WS_SCHEMA_VERSION,
+            msg: self,
+        })
+        .unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ServicesSnapshot {
+    pub node: String,
+    pub services: Vec<ServiceInstance>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct UpdateProgressData {
+    pub service: String,
+    // `None`: güncelleme bitti, overlay kaldırılır
+    pub progress: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DiskWarning {
+    pub node: String,
+    pub mount_point: String,
+    pub used_pct: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TemperatureWarning {
+    pub node: String,
+    pub sensor: String,
+    pub temp_c: f32,
+    pub threshold_c: f32,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PruneResultData {
+    pub node: String,
+    pub trigger: String,
+    pub success: bool,
+    pub report: Option<PruneReport>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct NodeStatusChange {
+    pub node: String,
+    pub old_status: String,
+    pub new_status: String,
+    pub last_seen: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct OomKilledData {
+    pub node: String,
+    pub service: String,
+    // Canlı `oom` event'inde container henüz çıkmamış olabilir; kod bilinmez
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AutoPilotResultData {
+    pub node: String,
+    pub service: String,
+    pub updated: bool,
+    pub error: Option<String>,
+}
+
+// --- SERVİS GRUPLAMA (CLUSTER) ---
+#[derive(Serialize, Clone, Debug)]
+pub struct ServicePlacement {
+    pub node: String,
+    pub short_id: String,
+    pub status: String,
+    pub health: HealthStatus,
+    pub cpu_usage: f64,
+    pub mem_usage: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ServiceGroup {
+    pub name: String,
+    pub node_count: usize,
+    pub unhealthy_count: usize,
+    pub placements: Vec<ServicePlacement>,
+}
+
+// NOC özet çubuğu için tek çağrılık cluster özeti. Kullanım oranları sadece ONLINE node'lardan,
+// kapasiteyle ağırlıklandırılarak hesaplanır (CPU: çekirdek, RAM: MB, GPU: kart sayısı).
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ClusterSummary {
+    pub cluster_name: String,
+    pub nodes_online: usize,
+    pub nodes_offline: usize,
+    pub cpu_usage: f64, // %
+    pub ram_used: u64,  // MB
+    pub ram_total: u64, // MB
+    pub ram_usage: f64, // %
+    pub gpu_usage: f64, // %
+    pub containers: usize,
+    // Governor::container_state anahtarıyla (running, exited, paused, ...)
+    pub containers_by_status: BTreeMap<String, usize>,
+    pub pending_updates: usize,
+}
+
 #[derive(Deserialize)]
 pub struct ActionParams {
     pub service: String,
 }
 
+// `/api/status?name=<alt dizi>&status=<running|exited|paused|...>&node=<node>&limit=&offset=`
+#[derive(Deserialize, Default)]
+pub struct StatusFilterParams {
+    pub name: Option<String>,
+    pub status: Option<String>,
+    pub node: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+// Sayfalı liste zarfı (`/api/status` sadece limit/offset verilince); `total` filtre sonrası toplam kayıt sayısıdır.
+#[derive(Serialize, Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Deserialize)]
+pub struct NodeListParams {
+    pub tag: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct NodeHistoryParams {
+    pub metric: Option<String>,
+    // Unix epoch (saniye) veya RFC3339
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub points: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct StoredHistoryParams {
+    pub node: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct HistoryPoint {
+    pub t: String, // ISO8601
+    pub value: f64,
+}
+
 #[derive(Deserialize)]
 pub struct ToggleParams {
     pub service: String,
     pub enabled: bool,
 }
 
+// --- ENV PATCH MODELLERİ ---
+// `POST /api/service/create`: sıfırdan container kurulumu için asgari tanım.
+// `volumes` docker CLI biçimindedir: `kaynak:hedef[:ro]` (kaynak volume adı veya CREATE_HOST_PATH_ALLOWLIST altındaki host yolu).
+#[derive(Deserialize, Clone, Debug)]
+pub struct ServiceSpec {
+    pub name: String,
+    pub image: String,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub ports: Vec<PortMapping>,
+    #[serde(default)]
+    pub volumes: Vec<String>,
+    // Boşsa Docker varsayılan bridge ağı kullanılır
+    #[serde(default)]
+    pub network: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CreateServiceResult {
+    pub name: String,
+    pub container_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct EnvPatchParams {
+    #[serde(default)]
+    pub set: HashMap<String, String>,
+    #[serde(default)]
+    pub unset: Vec<String>,
+}
+
+// `GET /api/service/:id/env`: ENV_REDACT_PATTERNS ile eşleşen anahtarların değeri `***` olur
+#[derive(Serialize, Clone, Debug)]
+pub struct EnvVar {
+    pub key: String,
+    pub value: String,
+}
+
+/// Uygulanan env farkı. Hassas anahtarların değerleri maskelenmiş olarak tutulur.
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct EnvDiff {
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl EnvDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.added
+            .iter()
+            .chain(self.changed.iter())
+            .map(|kv| kv.split('=').next().unwrap_or_default().to_string())
+            .chain(self.removed.iter().cloned())
+            .collect()
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EnvPatchResult {
+    pub container_id: String,
+    pub diff: EnvDiff,
+}
+
+// `POST /api/services/action`: {action: "start"|"stop"|"restart", services: [...]}
+#[derive(Deserialize)]
+pub struct BulkActionParams {
+    pub action: String,
+    pub services: Vec<String>,
+    // Sadece restart: `sentiric.depends_on` sırasına göre katman katman, hazır olmayı bekleyerek
+    #[serde(default)]
+    pub ordered: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BatchUpdateResult {
+    pub service: String,
+    pub result: Option<String>,
+    pub error: Option<String>,
+}
+
+// `/api/system/prune?dry_run=true&keep_recent=<saat>`
+#[derive(Deserialize, Default)]
+pub struct PruneParams {
+    #[serde(default)]
+    pub dry_run: bool,
+    // Son N saatte oluşturulan container/imajlara dokunulmaz (Docker `until` filtresi)
+    pub keep_recent: Option<u64>,
+    // Kullanılmayan anonim volume'lar da silinir; veri taşıdıkları için varsayılan kapalı
+    #[serde(default)]
+    pub volumes: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PrunedItem {
+    pub id: String,
+    pub name: String,
+    pub size_bytes: u64,
+}
+
+// `GET /api/images`: prune öncesi hangi imajın ne kadar yer tuttuğunu göstermek için
+#[derive(Serialize, Clone, Debug)]
+pub struct ImageInfo {
+    pub id: String,
+    pub repo_tags: Vec<String>,
+    pub size_bytes: u64,
+    pub created: String, // RFC3339
+    // Çalışan en az bir container bu imajı kullanıyor
+    pub in_use: bool,
+    // Etiketsiz (`<none>:<none>`) imaj; prune'un ilk adayıdır
+    pub dangling: bool,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct PruneReport {
+    pub dry_run: bool,
+    pub containers: Vec<PrunedItem>,
+    pub images: Vec<PrunedItem>,
+    pub volumes_included: bool,
+    pub volumes: Vec<PrunedItem>,
+    // Dry-run'da imaj boyutlarının toplamıdır; paylaşılan katmanlar yüzünden gerçek kazançtan büyük olabilir.
+    pub reclaimed_bytes: u64,
+    pub summary: String,
+}
+
+// `/api/service/:id/inspect?fields=State.Status,Config.Image` (boşsa tüm nesne döner)
+#[derive(Deserialize, Default)]
+pub struct InspectParams {
+    pub fields: Option<String>,
+}
+
+// `/ws/logs/:id?grep=<regex>&since=<rfc3339>`
+#[derive(Deserialize, Default)]
+pub struct LogStreamParams {
+    pub grep: Option<String>,
+    pub since: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct LogDownloadParams {
+    // Satır sayısı ya da "all" (varsayılan)
+    pub tail: Option<String>,
+    // Satırlar RFC3339 zaman damgasıyla başlar (varsayılan kapalı)
+    #[serde(default)]
+    pub timestamps: bool,
+}
+
+// --- LLM TEŞHİS RAPORU ---
+#[derive(Deserialize, Default)]
+pub struct ExportParams {
+    // "markdown" (varsayılan) veya "json"
+    pub format: Option<String>,
+    // Servis başına log satırı (varsayılan 50, 0 = log yok)
+    pub log_lines: Option<usize>,
+    // Servisler arası olay sıralaması için log satırlarına zaman damgası eklenir
+    #[serde(default)]
+    pub timestamps: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DiagnosticService {
+    pub name: String,
+    pub status: String,
+    pub image: String,
+    pub cpu: f64,
+    pub mem: u64,
+    pub recent_logs: String,
+}
+
+// Markdown ve JSON çıktılarının ortak veri kaynağı (tek toplama turu)
+#[derive(Serialize, Clone, Debug)]
+pub struct DiagnosticReport {
+    pub cluster: String,
+    pub node: String,
+    pub generated_at: String,
+    pub log_lines: usize,
+    pub log_timestamps: bool,
+    pub system: NodeStats,
+    // Sadece Markdown (altyapı/drift bölümleri) için; JSON çıktısı yerel node'a odaklıdır
+    #[serde(skip)]
+    pub nodes: ClusterMap,
+    pub services: Vec<DiagnosticService>,
+}
+
+// --- UZAK KOMUT (upstream -> edge) ---
+#[derive(Deserialize)]
+pub struct NodeCommandParams {
+    pub service: String,
+    // start | stop | restart | update
+    pub action: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NodeCommand {
+    pub id: String,
+    pub service: String,
+    pub action: String,
+}
+
+// Edge'de gerçekleşen yaşam döngüsü olayı; komut kanalı üzerinden upstream'e iletilip orada da yayınlanır.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LifecycleEvent {
+    pub node: String,
+    pub service: String,
+    // "update_found", "update_applied", "update_failed", "update_blocked", "started", "stopped", "restarted"
+    pub kind: String,
+    #[serde(default)]
+    pub message: Option<String>,
+    pub ts: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CommandAck {
+    pub id: String,
+    pub success: bool,
+    pub message: String,
+}
+
+// --- EXEC MODELLERİ ---
+#[derive(Deserialize)]
+pub struct ExecParams {
+    pub cmd: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ExecOutput {
+    pub exit_code: Option<i64>,
+    pub stdout: String,
+    pub stderr: String,
+    pub truncated: bool,
+    pub timed_out: bool,
+}
+
 // --- TOPOLOJİ MODELLERİ ---
 #[derive(Serialize, Clone, Debug)]
 pub struct TopologyNode {
@@ -97,3 +839,62 @@ pub struct TopologyMap {
     pub nodes: Vec<TopologyNode>,
     pub edges: Vec<TopologyEdge>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Timelike, Utc};
+
+    fn at(h: u32, m: u32) -> chrono::DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 14, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn window_parse_rejects_empty_and_malformed_input() {
+        assert!(MaintenanceWindow::parse("03:00-03:00", "UTC")
+            .unwrap_err()
+            .contains("empty"));
+        for bad in ["", "03:00", "3-5", "25:00-04:00", "03:00-04:00-05:00"] {
+            assert!(MaintenanceWindow::parse(bad, "UTC").is_err(), "{}", bad);
+        }
+        assert!(MaintenanceWindow::parse("03:00-04:00", "Europe/Istanbul").is_err());
+    }
+
+    #[test]
+    fn window_contains_handles_midnight_crossing() {
+        let w = MaintenanceWindow::parse("22:00-02:00", "UTC").unwrap();
+        assert!(w.contains(at(22, 0)));
+        assert!(w.contains(at(23, 30)));
+        assert!(w.contains(at(1, 59)));
+        assert!(!w.contains(at(2, 0)));
+        assert!(!w.contains(at(12, 0)));
+
+        let w = MaintenanceWindow::parse("02:00-04:00", "utc").unwrap();
+        assert!(w.contains(at(2, 0)) && !w.contains(at(4, 0)) && !w.contains(at(23, 0)));
+    }
+
+    #[test]
+    fn window_applies_fixed_offset() {
+        // +03:00'te 02:00-04:00, UTC'de önceki günün 23:00'ü ile 01:00 arası demektir.
+        let w = MaintenanceWindow::parse("02:00-04:00", "+03:00").unwrap();
+        assert!(w.contains(at(23, 0)));
+        assert!(w.contains(at(0, 59)));
+        assert!(!w.contains(at(1, 0)));
+        assert!(!w.contains(at(2, 30)));
+    }
+
+    #[test]
+    fn window_uses_local_time_without_offset() {
+        let now = at(12, 0);
+        let local = now.with_timezone(&chrono::Local).time();
+        let start = local.with_second(0).unwrap();
+        let end = start + chrono::Duration::hours(1);
+        let spec = format!("{}-{}", start.format("%H:%M"), end.format("%H:%M"));
+        for tz in ["", "local"] {
+            let w = MaintenanceWindow::parse(&spec, tz).unwrap();
+            assert!(w.offset.is_none());
+            assert!(w.contains(now));
+            assert!(!w.contains(now + chrono::Duration::hours(2)));
+        }
+    }
+}
@@ -1,8 +1,27 @@
 // src/core/governor.rs
-use crate::core::domain::HealthStatus;
+use crate::core::domain::{
+    EnvDiff, EnvVar, HealthScoreWeights, HealthStatus, NodeStats, ServiceInstance, ServiceSpec,
+};
+use std::collections::HashMap;
 
 pub struct Governor;
 
+/// `dependency_tiers` döngü bulduğunda döner; API 409'a çevirir. Döngüdeki servisler sıralıdır.
+#[derive(Debug, PartialEq)]
+pub struct DependencyCycle(pub Vec<String>);
+
+impl std::fmt::Display for DependencyCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Dependency cycle detected between: {}",
+            self.0.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for DependencyCycle {}
+
 impl Governor {
     pub fn audit_compliance(service_name: &str, env_vars: &[String]) -> Vec<String> {
         let mut violations = Vec::new();
@@ -50,4 +69,468 @@ impl Governor {
         }
         HealthStatus::Online
     }
+
+    /// POSIX uyumlu env anahtarı: harf/alt çizgi ile başlar, sadece alfanümerik ve `_` içerir.
+    pub fn validate_env_key(key: &str) -> Result<(), String> {
+        let mut chars = key.chars();
+        let valid_head = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+        if !valid_head || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("Invalid environment variable name: '{}'", key));
+        }
+        Ok(())
+    }
+
+    /// Docker container adı kuralı: `[a-zA-Z0-9][a-zA-Z0-9_.-]*`. Env anahtarları, port
+    /// protokolleri ve `kaynak:hedef[:ro|rw]` volume biçimi de burada doğrulanır. Volume kaynağı
+    /// named volume ya da `host_path_allowlist` altındaki bir host yolu olmalıdır; `host` ve
+    /// `container:` ağ modları reddedilir (tek API çağrısı host'u ele geçirmesin).
+    pub fn validate_service_spec(
+        spec: &ServiceSpec,
+        host_path_allowlist: &[String],
+    ) -> Result<(), String> {
+        if !Self::is_valid_docker_name(&spec.name) {
+            return Err(format!("Invalid container name: '{}'", spec.name));
+        }
+        if spec.image.trim().is_empty() || spec.image.contains(char::is_whitespace) {
+            return Err(format!("Invalid image reference: '{}'", spec.image));
+        }
+        for key in spec.env.keys() {
+            Self::validate_env_key(key)?;
+        }
+        for p in &spec.ports {
+            if p.container_port == 0 || p.host_port == 0 {
+                return Err("Port numbers must be between 1 and 65535".into());
+            }
+            if !["", "tcp", "udp", "sctp"].contains(&p.protocol.as_str()) {
+                return Err(format!("Invalid port protocol: '{}'", p.protocol));
+            }
+        }
+        for v in &spec.volumes {
+            let parts: Vec<&str> = v.split(':').collect();
+            let valid = match parts.as_slice() {
+                [src, dst] => !src.is_empty() && dst.starts_with('/'),
+                [src, dst, mode] => {
+                    !src.is_empty() && dst.starts_with('/') && ["ro", "rw"].contains(mode)
+                }
+                _ => false,
+            };
+            if !valid {
+                return Err(format!(
+                    "Invalid volume '{}', expected source:/target[:ro|rw]",
+                    v
+                ));
+            }
+            let src = parts[0];
+            if src.starts_with('/') {
+                if !Self::is_allowed_host_path(src, host_path_allowlist) {
+                    return Err(format!(
+                        "Host path '{}' is not allowed (see CREATE_HOST_PATH_ALLOWLIST)",
+                        src
+                    ));
+                }
+            } else if !Self::is_valid_docker_name(src) {
+                return Err(format!("Invalid volume name: '{}'", src));
+            }
+        }
+        if let Some(net) = spec.network.as_deref() {
+            let net = net.trim().to_lowercase();
+            if net == "host" || net.starts_with("container:") {
+                return Err(format!("Network mode '{}' is not allowed", net));
+            }
+        }
+        Ok(())
+    }
+
+    // Container ve named volume adları aynı kurala uyar: `[a-zA-Z0-9][a-zA-Z0-9_.-]*`.
+    fn is_valid_docker_name(name: &str) -> bool {
+        let mut chars = name.chars();
+        let valid_head = chars.next().is_some_and(|c| c.is_ascii_alphanumeric());
+        valid_head && chars.all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c))
+    }
+
+    // `..` ile izin listesinden kaçılamaz; önek yol bileşeni sınırında eşleşir (`/data` ≠ `/database`).
+    // `/` ve docker socket'i izin listesinde olsa bile reddedilir.
+    fn is_allowed_host_path(path: &str, allowlist: &[String]) -> bool {
+        let path = path.trim_end_matches('/');
+        if path.is_empty() || path.split('/').any(|c| c == "..") || path.ends_with("docker.sock") {
+            return false;
+        }
+        allowlist.iter().any(|prefix| {
+            path == prefix
+                || path
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+
+    /// Hassas anahtar listesi tektir: ENV_REDACT_PATTERNS (env okuma, inspect ve patch farkı aynı desenleri kullanır).
+    pub fn is_sensitive_env_key(key: &str, patterns: &[String]) -> bool {
+        patterns.iter().any(|p| Self::matches_glob(p, key))
+    }
+
+    /// `*PASSWORD*` gibi `*` joker karakterli, büyük/küçük harf duyarsız desen eşleşmesi.
+    pub fn matches_glob(pattern: &str, text: &str) -> bool {
+        let (pattern, text) = (pattern.to_uppercase(), text.to_uppercase());
+        let parts: Vec<&str> = pattern.split('*').collect();
+        let (first, last) = (parts[0], parts[parts.len() - 1]);
+        if parts.len() == 1 {
+            return text == first;
+        }
+        if !text.starts_with(first) || !text[first.len()..].ends_with(last) {
+            return false;
+        }
+        let mut rest = &text[first.len()..text.len() - last.len()];
+        for part in &parts[1..parts.len() - 1] {
+            match rest.find(part) {
+                Some(i) => rest = &rest[i + part.len()..],
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// `KEY=value` listesini, desenlerle eşleşen anahtarların değerlerini gizleyerek ayrıştırır.
+    pub fn redact_env(env: &[String], patterns: &[String]) -> Vec<EnvVar> {
+        env.iter()
+            .map(|entry| {
+                let (key, value) = entry.split_once('=').unwrap_or((entry.as_str(), ""));
+                let redacted = Self::is_sensitive_env_key(key, patterns);
+                EnvVar {
+                    key: key.to_string(),
+                    value: if redacted {
+                        "***".to_string()
+                    } else {
+                        value.to_string()
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Ham `KEY=value` listesini aynı biçimde döndürür; hassas değerler `***` olur (inspect çıktısı için).
+    pub fn redact_env_entries(env: &[String], patterns: &[String]) -> Vec<String> {
+        env.iter()
+            .map(|entry| {
+                let (key, value) = entry.split_once('=').unwrap_or((entry.as_str(), ""));
+                Self::render_env_entry(key, value, patterns)
+            })
+            .collect()
+    }
+
+    fn render_env_entry(key: &str, value: &str, patterns: &[String]) -> String {
+        if Self::is_sensitive_env_key(key, patterns) {
+            format!("{}=***", key)
+        } else {
+            format!("{}={}", key, value)
+        }
+    }
+
+    /// Mevcut env listesine ekleme/silme uygular; sıralamayı korur ve maskelenmiş farkı döner.
+    pub fn apply_env_patch(
+        current: &[String],
+        set: &HashMap<String, String>,
+        unset: &[String],
+        patterns: &[String],
+    ) -> (Vec<String>, EnvDiff) {
+        let mut diff = EnvDiff::default();
+        let mut result = Vec::with_capacity(current.len() + set.len());
+        let mut seen = std::collections::HashSet::new();
+
+        for entry in current {
+            let (key, value) = entry.split_once('=').unwrap_or((entry.as_str(), ""));
+            if unset.iter().any(|k| k == key) {
+                diff.removed.push(key.to_string());
+                continue;
+            }
+            match set.get(key) {
+                Some(new_value) => {
+                    if new_value != value {
+                        diff.changed
+                            .push(Self::render_env_entry(key, new_value, patterns));
+                    }
+                    result.push(format!("{}={}", key, new_value));
+                }
+                None => result.push(entry.clone()),
+            }
+            seen.insert(key.to_string());
+        }
+
+        let mut new_keys: Vec<&String> = set.keys().filter(|k| !seen.contains(*k)).collect();
+        new_keys.sort();
+        for key in new_keys {
+            result.push(format!("{}={}", key, set[key]));
+            diff.added
+                .push(Self::render_env_entry(key, &set[key], patterns));
+        }
+
+        (result, diff)
+    }
+
+    /// `sentiric.depends_on` bağımlılıklarını katmanlara ayırır (Kahn): her katman yalnızca önceki
+    /// katmanlara bağımlıdır. Küme dışındaki bağımlılıklar yok sayılır; döngü varsa hata döner.
+    pub fn dependency_tiers(
+        deps: &HashMap<String, Vec<String>>,
+    ) -> Result<Vec<Vec<String>>, DependencyCycle> {
+        let mut pending: HashMap<&str, Vec<&str>> = deps
+            .iter()
+            .map(|(svc, d)| {
+                let inside = d
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|dep| *dep != svc && deps.contains_key(*dep))
+                    .collect();
+                (svc.as_str(), inside)
+            })
+            .collect();
+
+        let mut tiers = Vec::new();
+        while !pending.is_empty() {
+            let mut ready: Vec<String> = pending
+                .iter()
+                .filter(|(_, d)| d.iter().all(|dep| !pending.contains_key(dep)))
+                .map(|(svc, _)| svc.to_string())
+                .collect();
+            if ready.is_empty() {
+                let mut cycle: Vec<String> = pending.keys().map(|s| s.to_string()).collect();
+                cycle.sort();
+                return Err(DependencyCycle(cycle));
+            }
+            ready.sort();
+            for svc in &ready {
+                pending.remove(svc.as_str());
+            }
+            tiers.push(ready);
+        }
+        Ok(tiers)
+    }
+
+    /// `State.Status,Config.Image` gibi noktalı yolları aynı iç içe yapıyı koruyarak seçer;
+    /// bulunamayan yollar sessizce atlanır.
+    pub fn project_fields(value: &serde_json::Value, fields: &[&str]) -> serde_json::Value {
+        let mut out = serde_json::Value::Object(Default::default());
+        for path in fields {
+            let parts: Vec<&str> = path.split('.').filter(|p| !p.is_empty()).collect();
+            let Some((last, parents)) = parts.split_last() else {
+                continue;
+            };
+            let Some(found) = parts.iter().try_fold(value, |v, key| v.get(key)) else {
+                continue;
+            };
+            // Üst yol zaten yaprak olarak seçildiyse (ör. `State` + `State.Status`) o değer korunur.
+            let mut cursor = Some(&mut out);
+            for key in parents {
+                cursor = cursor.and_then(|c| c.as_object_mut()).map(|obj| {
+                    obj.entry(key.to_string())
+                        .or_insert_with(|| serde_json::Value::Object(Default::default()))
+                });
+            }
+            if let Some(obj) = cursor.and_then(|c| c.as_object_mut()) {
+                obj.entry(last.to_string()).or_insert_with(|| found.clone());
+            }
+        }
+        out
+    }
+
+    /// Docker'ın "Up 5 minutes" gibi zamanla değişen durum metnini kararlı bir anahtara indirger.
+    pub fn status_key(status: &str) -> String {
+        if status.contains("(Paused)") {
+            return "paused".into();
+        }
+        status
+            .split_whitespace()
+            .next()
+            .unwrap_or("unknown")
+            .to_lowercase()
+    }
+
+    /// `/api/status` süzgeci: isim (alt dize) ve durum (`running`, `exited`...) büyük/küçük harf
+    /// duyarsız eşleşir, sonuç isme göre sıralanır. Referans döner; çağıran sadece sayfayı kopyalar.
+    pub fn filter_services<'a>(
+        services: impl Iterator<Item = &'a ServiceInstance>,
+        name: Option<&str>,
+        status: Option<&str>,
+    ) -> Vec<&'a ServiceInstance> {
+        let name = name.map(str::to_lowercase);
+        let status = status.map(str::to_lowercase);
+        let mut matched: Vec<&ServiceInstance> = services
+            .filter(|s| {
+                name.as_deref()
+                    .is_none_or(|n| s.name.to_lowercase().contains(n))
+                    && status
+                        .as_deref()
+                        .is_none_or(|st| Self::container_state(&s.status) == st)
+            })
+            .collect();
+        matched.sort_by(|a, b| a.name.cmp(&b.name));
+        matched
+    }
+
+    /// Filtreleme için container durumu: `status_key` ile aynıdır, sadece "up" -> "running".
+    pub fn container_state(status: &str) -> String {
+        match Self::status_key(status).as_str() {
+            "up" => "running".into(),
+            other => other.to_string(),
+        }
+    }
+
+    /// Docker container olayını UI'ın anlayacağı durum etiketine çevirir (örn. `die` -> `exited`).
+    pub fn container_status_for_event(action: &str) -> String {
+        let base = action.split(':').next().unwrap_or(action).trim();
+        match base {
+            "start" | "unpause" | "restart" => "running",
+            "pause" => "paused",
+            "die" | "stop" | "kill" => "exited",
+            "oom" => "oom",
+            "destroy" => "removed",
+            "create" => "created",
+            "health_status" => action
+                .split(':')
+                .nth(1)
+                .map(|s| s.trim())
+                .unwrap_or("unknown"),
+            other => other,
+        }
+        .to_string()
+    }
+
+    /// Sağlıksız sayılan servis: healthcheck `unhealthy`, OOM riski veya crash-loop (Restarting).
+    pub fn is_service_unhealthy(svc: &ServiceInstance) -> bool {
+        svc.health == HealthStatus::RiskOom
+            || svc.healthcheck.as_deref() == Some("unhealthy")
+            || svc.status.to_lowercase().contains("restarting")
+    }
+
+    /// Skor = 100 - ağırlıklı kaynak baskısı (%) - (sağlıksız servis sayısı x ceza), [0, 100] aralığında.
+    /// GPU bulunmayan node'larda GPU ağırlığı hesaba katılmaz.
+    pub fn node_health_score(
+        stats: &NodeStats,
+        services: &[ServiceInstance],
+        weights: &HealthScoreWeights,
+    ) -> u32 {
+        let pct = |used: u64, total: u64| {
+            if total > 0 {
+                (used as f64 / total as f64 * 100.0).min(100.0)
+            } else {
+                0.0
+            }
+        };
+
+        let mut pressure = weights.cpu * (stats.cpu_usage as f64).min(100.0)
+            + weights.ram * pct(stats.ram_used, stats.ram_total);
+        let mut weight_sum = weights.cpu + weights.ram;
+        if stats.gpu_mem_total > 0 {
+            pressure += weights.gpu * pct(stats.gpu_mem_used, stats.gpu_mem_total);
+            weight_sum += weights.gpu;
+        }
+        let resource_pressure = if weight_sum > 0.0 {
+            pressure / weight_sum
+        } else {
+            0.0
+        };
+
+        let unhealthy = services
+            .iter()
+            .filter(|s| Self::is_service_unhealthy(s))
+            .count();
+        let score = 100.0 - resource_pressure - unhealthy as f64 * weights.service_penalty;
+        score.clamp(0.0, 100.0).round() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(volumes: &[&str], network: Option<&str>) -> ServiceSpec {
+        ServiceSpec {
+            name: "web".into(),
+            image: "nginx:latest".into(),
+            env: Default::default(),
+            ports: Vec::new(),
+            volumes: volumes.iter().map(|v| v.to_string()).collect(),
+            network: network.map(String::from),
+        }
+    }
+
+    #[test]
+    fn create_rejects_host_escape_mounts() {
+        let allow = vec!["/srv/data".to_string()];
+        for v in [
+            "/:/host",
+            "/var/run/docker.sock:/var/run/docker.sock",
+            "/etc:/etc:ro",
+            "/srv/database:/db",
+            "/srv/data/../../etc:/x",
+            "../etc:/x",
+        ] {
+            assert!(
+                Governor::validate_service_spec(&spec(&[v], None), &allow).is_err(),
+                "{}",
+                v
+            );
+        }
+        assert!(Governor::validate_service_spec(&spec(&["pgdata:/var/lib/pg"], None), &[]).is_ok());
+        assert!(
+            Governor::validate_service_spec(&spec(&["/srv/data/app:/data:ro"], None), &allow)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn create_rejects_host_and_container_network_modes() {
+        for n in ["host", "HOST", "container:abc123"] {
+            assert!(Governor::validate_service_spec(&spec(&[], Some(n)), &[]).is_err());
+        }
+        assert!(Governor::validate_service_spec(&spec(&[], Some("backend")), &[]).is_ok());
+    }
+
+    fn deps(pairs: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        pairs
+            .iter()
+            .map(|(svc, d)| (svc.to_string(), d.iter().map(|s| s.to_string()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn dependency_tiers_orders_a_diamond() {
+        let tiers = Governor::dependency_tiers(&deps(&[
+            ("app", &["api", "worker"]),
+            ("api", &["db"]),
+            ("worker", &["db"]),
+            ("db", &[]),
+        ]))
+        .unwrap();
+        assert_eq!(tiers, vec![vec!["db"], vec!["api", "worker"], vec!["app"]]);
+    }
+
+    #[test]
+    fn dependency_tiers_ignores_unknown_and_self_deps() {
+        let tiers = Governor::dependency_tiers(&deps(&[
+            ("api", &["api", "redis", "db"]),
+            ("db", &["external-dns"]),
+        ]))
+        .unwrap();
+        assert_eq!(tiers, vec![vec!["db"], vec!["api"]]);
+    }
+
+    #[test]
+    fn dependency_tiers_rejects_a_cycle() {
+        let err = Governor::dependency_tiers(&deps(&[
+            ("a", &["b"]),
+            ("b", &["c"]),
+            ("c", &["a"]),
+            ("db", &[]),
+        ]))
+        .unwrap_err();
+        assert_eq!(
+            err,
+            DependencyCycle(vec!["a".into(), "b".into(), "c".into()])
+        );
+        assert_eq!(
+            err.to_string(),
+            "Dependency cycle detected between: a, b, c"
+        );
+    }
 }
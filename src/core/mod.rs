@@ -1,2 +1,4 @@
+pub mod broadcast;
 pub mod domain;
 pub mod governor;
+pub mod node_history;
@@ -0,0 +1,111 @@
+// src/core/node_history.rs
+use std::collections::{HashMap, VecDeque};
+
+use crate::core::domain::{HistoryPoint, NodeStats};
+
+// 24 saat, 5 saniye çözünürlük: node başına en fazla 17280 örnek.
+pub const NODE_HISTORY_RESOLUTION_SECS: i64 = 5;
+pub const NODE_HISTORY_LEN: usize = 24 * 3600 / NODE_HISTORY_RESOLUTION_SECS as usize;
+// Bu sayıdan fazla node için yeni geçmiş açılmaz (bellek sınırı)
+const NODE_HISTORY_MAX_NODES: usize = 256;
+
+#[derive(Clone, Debug)]
+struct NodeSample {
+    ts: i64, // Unix epoch (saniye)
+    cpu: f64,
+    ram: f64,
+    gpu: f64,
+    gpu_mem: f64,
+    disk: f64,
+    net_rx: f64,
+    net_tx: f64,
+}
+
+impl NodeSample {
+    fn metric(&self, metric: &str) -> Option<f64> {
+        Some(match metric {
+            "cpu" => self.cpu,
+            "ram" => self.ram,
+            "gpu" => self.gpu,
+            "gpu_mem" => self.gpu_mem,
+            "disk" => self.disk,
+            "net_rx" => self.net_rx,
+            "net_tx" => self.net_tx,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct NodeHistory {
+    nodes: HashMap<String, VecDeque<NodeSample>>,
+}
+
+impl NodeHistory {
+    pub const METRICS: [&'static str; 7] =
+        ["cpu", "ram", "gpu", "gpu_mem", "disk", "net_rx", "net_tx"];
+
+    // Monitor (3s) ve ingest (10s) farklı hızlarda beslediği için aynı 5s kovasına düşen örnek yenisiyle değiştirilir.
+    pub fn record(&mut self, node: &str, stats: &NodeStats, ts: i64) {
+        if !self.nodes.contains_key(node) && self.nodes.len() >= NODE_HISTORY_MAX_NODES {
+            return;
+        }
+        let sample = NodeSample {
+            ts,
+            cpu: stats.cpu_usage as f64,
+            ram: stats.ram_used as f64,
+            gpu: stats.gpu_usage as f64,
+            gpu_mem: stats.gpu_mem_used as f64,
+            disk: stats.disk_used as f64,
+            net_rx: stats.net_rx_mbs,
+            net_tx: stats.net_tx_mbs,
+        };
+        let buf = self.nodes.entry(node.to_string()).or_default();
+        let bucket = ts / NODE_HISTORY_RESOLUTION_SECS;
+        if let Some(last) = buf.back_mut() {
+            if last.ts / NODE_HISTORY_RESOLUTION_SECS == bucket {
+                *last = sample;
+                return;
+            }
+        }
+        if buf.len() >= NODE_HISTORY_LEN {
+            buf.pop_front();
+        }
+        buf.push_back(sample);
+    }
+
+    // Cluster'dan çıkarılan (deregister/eviction) node'un geçmişi de bırakılır.
+    pub fn remove(&mut self, node: &str) {
+        self.nodes.remove(node);
+    }
+
+    // [from, to] aralığını döndürür; `max_points` aşılırsa eşit kovalara bölünüp ortalaması alınır.
+    pub fn query(
+        &self,
+        node: &str,
+        metric: &str,
+        from: i64,
+        to: i64,
+        max_points: usize,
+    ) -> Option<Vec<HistoryPoint>> {
+        let buf = self.nodes.get(node)?;
+        let points: Vec<(i64, f64)> = buf
+            .iter()
+            .filter(|s| s.ts >= from && s.ts <= to)
+            .filter_map(|s| s.metric(metric).map(|v| (s.ts, v)))
+            .collect();
+
+        let chunk = points.len().div_ceil(max_points.max(1)).max(1);
+        Some(
+            points
+                .chunks(chunk)
+                .map(|c| HistoryPoint {
+                    t: chrono::DateTime::from_timestamp(c[0].0, 0)
+                        .map(|d| d.to_rfc3339())
+                        .unwrap_or_default(),
+                    value: c.iter().map(|(_, v)| v).sum::<f64>() / c.len() as f64,
+                })
+                .collect(),
+        )
+    }
+}
@@ -6,21 +6,36 @@ mod core;
 mod telemetry;
 
 use bollard::container::ListContainersOptions;
+use futures_util::StreamExt;
 use reqwest::Client;
 use std::{
-    collections::{HashMap, HashSet},
-    sync::Arc,
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
+    sync::{atomic::AtomicU64, Arc},
     time::{Duration, Instant},
 };
-use tokio::sync::{broadcast, Mutex};
-use tracing::{info, warn}; // [ARCH-COMPLIANCE FIX]: debug eklendi
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, error, info, warn}; // [ARCH-COMPLIANCE FIX]: debug eklendi
 use tracing_subscriber::{fmt, prelude::*, EnvFilter, Registry};
 
-use crate::adapters::docker::DockerAdapter;
+use crate::adapters::audit::AuditLog;
+use crate::adapters::autopilot_store::AutoPilotStore;
+use crate::adapters::docker::{DockerAdapter, PruneBusy, SELF_UPDATE_HELPER_ARG};
+use crate::adapters::notify::Notifier;
+use crate::adapters::registry::RegistryAuth;
+use crate::adapters::storage::StorageAdapter;
 use crate::adapters::system::SystemMonitor;
+use crate::api::agent::AgentRegistry;
+use crate::api::auth::NODE_TOKEN_HEADER;
+use crate::api::ratelimit::RateLimiter;
 use crate::config::AppConfig;
-use crate::core::domain::{ClusterReport, NodeStats, ServiceInstance};
+use crate::core::broadcast::Broadcaster;
+use crate::core::domain::{
+    AutoPilotResultData, ClusterMap, ClusterReport, DiskWarning, JanitorSchedule, MetricSample,
+    NodeStats, OomKilledData, PruneParams, PruneResultData, ServiceInstance, TemperatureWarning,
+    WsMessage,
+};
 use crate::core::governor::Governor;
+use crate::core::node_history::NodeHistory;
 use crate::telemetry::SutsFormatter;
 
 struct ContainerStatsCache {
@@ -34,15 +49,51 @@ struct ContainerStatsCache {
 }
 
 pub struct AppState {
+    pub config: AppConfig,
     pub docker: DockerAdapter,
     pub auto_pilot_config: Mutex<HashMap<String, bool>>,
-    pub services_cache: Mutex<HashMap<String, ServiceInstance>>,
+    pub services_cache: RwLock<HashMap<String, ServiceInstance>>,
     pub node_stats_cache: Mutex<NodeStats>,
-    pub cluster_cache: Mutex<HashMap<String, ClusterReport>>,
-    pub tx: Arc<broadcast::Sender<String>>,
+    pub cluster_cache: Mutex<ClusterMap>,
+    // Son `cluster_update` mesajı; cluster her değiştiğinde bir kez serileştirilir, yeniden
+    // senkronizasyon ve tekrar yayınlar bunu kullanır. Kilit yayın sırasını da korur.
+    pub cluster_json: Mutex<String>,
+    pub tx: Arc<Broadcaster>,
     pub update_locks: Mutex<HashSet<String>>,
+    pub service_history: Mutex<HashMap<String, VecDeque<MetricSample>>>,
+    pub last_scan: Mutex<Option<String>>,
+    pub last_report: Mutex<Option<String>>,
+    pub audit: AuditLog,
+    pub node_history: Mutex<NodeHistory>,
+    pub storage: Option<StorageAdapter>,
+    pub notifier: Notifier,
+    pub agents: AgentRegistry,
+    pub rate_limiter: RateLimiter,
+    // AUTOPILOT_WINDOW dışında bulunup pencerenin açılmasını bekleyen auto-pilot güncellemeleri
+    pub pending_updates: Mutex<BTreeSet<String>>,
+    // Yavaş WS/SSE istemcilerinin kaçırdığı yayın mesajları (toplam) ve Lagged olay sayısı
+    pub broadcast_lagged: AtomicU64,
+    pub broadcast_lag_events: AtomicU64,
 }
 
+// Sparkline geçmişi: servis başına son N örnek, en fazla M servis tutulur.
+const SERVICE_HISTORY_LEN: usize = 60;
+const SERVICE_HISTORY_MAX_SERVICES: usize = 512;
+// Upstream kesintisinde rapor denemeleri arasındaki azami bekleme
+const UPSTREAM_MAX_BACKOFF: Duration = Duration::from_secs(60);
+// Bu pencere içinde CRASH_LOOP_RESTARTS'tan fazla yeniden başlayan servis crash-loop sayılır.
+const CRASH_LOOP_WINDOW: Duration = Duration::from_secs(600);
+const CRASH_LOOP_RESTARTS: u64 = 3;
+// Tarama turunda aynı anda en fazla bu kadar container için stats + inspect isteği açılır.
+const SCAN_STATS_CONCURRENCY: usize = 8;
+
+// `sentiric.ignore=true` olan container'lar (sidecar, tek seferlik job) dashboard'a alınmaz.
+const IGNORE_LABEL: &str = "sentiric.ignore";
+const DISPLAY_NAME_LABEL: &str = "sentiric.display_name";
+
+// Bir mount noktası bu doluluğu aşınca UI'a `disk_warning` yayınlanır.
+const DISK_FULL_WARN_PCT: f64 = 90.0;
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cfg = AppConfig::load();
@@ -77,41 +128,210 @@ async fn main() -> anyhow::Result<()> {
         "💠 SENTIRIC ORCHESTRATOR v6.6.0 (ENTERPRISE SRE GOVERNOR) Booting..."
     );
 
-    let (tx, _) = broadcast::channel::<String>(100);
-    let tx = Arc::new(tx);
+    // Eşik rapor aralığından kısaysa sağlıklı node'lar her turda OFFLINE/ONLINE arasında gidip gelir.
+    if cfg.node_offline_secs < 3 * cfg.poll_interval as i64 {
+        warn!(
+            event = "NODE_OFFLINE_THRESHOLD_TOO_LOW",
+            node_offline_secs = cfg.node_offline_secs,
+            poll_interval = cfg.poll_interval,
+            "⚠️ NODE_OFFLINE_SECS should be at least 3x POLL_INTERVAL, otherwise healthy nodes will flap OFFLINE."
+        );
+    }
+
+    if cfg.node_auth_token.is_none() {
+        warn!(
+            event = "NODE_AUTH_DISABLED",
+            "⚠️ NODE_AUTH_TOKEN is not set: any host that can reach this port may submit node reports."
+        );
+    }
+
+    // Geç bağlanan istemcilere tekrar oynatılacak geçici olaylar ilk üreticiden itibaren tutulur.
+    let tx = Arc::new(Broadcaster::new(
+        cfg.broadcast_capacity,
+        cfg.event_replay_len,
+    ));
 
-    let docker = DockerAdapter::new(&cfg.docker_socket, cfg.node_name.clone(), tx.clone())?;
-    let mut sys_mon = SystemMonitor::new(cfg.node_name.clone());
+    let docker = DockerAdapter::new(&cfg.docker_socket, cfg.node_name.clone(), tx.clone())?
+        .with_config_backups(cfg.backup_dir.clone(), cfg.backup_keep)
+        .with_update_hooks(cfg.update_prehook.clone(), cfg.update_posthook.clone())
+        .with_update_health_timeout(cfg.update_health_timeout)
+        .with_stop_timeout(cfg.stop_timeout)
+        .with_pull_retries(cfg.pull_max_attempts)
+        .with_registry_auth(RegistryAuth::load(
+            cfg.registry_url.clone(),
+            cfg.registry_username.clone(),
+            cfg.registry_password.clone(),
+        ))
+        .with_self_update(cfg.allow_self_update)
+        .with_platform(cfg.force_platform.clone())
+        .negotiate_version()
+        .await
+        .identify_self()
+        .await;
 
-    let mut initial_ap = HashMap::new();
-    for svc in &cfg.auto_pilot_services {
-        initial_ap.insert(svc.clone(), true);
+    // Yardımcı mod: eski orchestrator container'ını yeni imajla değiştirip çıkar.
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some(SELF_UPDATE_HELPER_ARG) {
+        let (Some(target), Some(image)) = (args.get(2), args.get(3)) else {
+            anyhow::bail!("usage: {} <container_id> <image>", SELF_UPDATE_HELPER_ARG);
+        };
+        return docker.complete_self_update(target, image).await;
+    }
+    // sysinfo yenilemesi ve GPU sorgusu (nvidia-smi/rocm-smi) bloklayıcıdır; her tur spawn_blocking'de çalışır.
+    let sys_mon = Arc::new(std::sync::Mutex::new(SystemMonitor::new(
+        cfg.node_name.clone(),
+    )));
+
+    let storage = match &cfg.database_path {
+        Some(path) => match StorageAdapter::open(path) {
+            Ok(s) => Some(s),
+            Err(e) => {
+                error!(event="STORAGE_INIT_FAIL", path=%path, error=%e, "❌ SQLite storage could not be opened, continuing without persistence.");
+                None
+            }
+        },
+        None => None,
+    };
+
+    let initial_ap = AutoPilotStore::load(&cfg.autopilot_state_path, &cfg.auto_pilot_services);
+    let pending_updates = AutoPilotStore::load_pending(&cfg.autopilot_pending_path);
+    if let Some(window) = &cfg.autopilot_window {
+        info!(event="AUTOPILOT_WINDOW", window=%window, pending=pending_updates.len(), "🕙 Auto-pilot updates restricted to maintenance window.");
     }
 
     let state = Arc::new(AppState {
+        config: cfg.clone(),
         docker: docker.clone(),
         auto_pilot_config: Mutex::new(initial_ap),
-        services_cache: Mutex::new(HashMap::new()),
+        services_cache: RwLock::new(HashMap::new()),
         node_stats_cache: Mutex::new(NodeStats::default()),
         cluster_cache: Mutex::new(HashMap::new()),
+        cluster_json: Mutex::new(String::new()),
         tx: tx.clone(),
         update_locks: Mutex::new(HashSet::new()),
+        service_history: Mutex::new(HashMap::new()),
+        last_scan: Mutex::new(None),
+        last_report: Mutex::new(None),
+        node_history: Mutex::new(NodeHistory::default()),
+        storage,
+        notifier: Notifier::new(
+            cfg.alert_webhook_url.clone(),
+            cfg.alert_format,
+            cfg.cluster_name.clone(),
+        ),
+        agents: AgentRegistry::default(),
+        rate_limiter: RateLimiter::new(cfg.rate_limit_per_min),
+        audit: AuditLog::new(cfg.audit_log_path.clone(), cfg.audit_log_max_bytes),
+        pending_updates: Mutex::new(pending_updates),
+        broadcast_lagged: AtomicU64::new(0),
+        broadcast_lag_events: AtomicU64::new(0),
     });
 
     // 1. SYSTEM MONITOR & OTONOM KORUMA
     let mon_state = state.clone();
     let mon_node = cfg.node_name.clone();
     let mon_tx = tx.clone();
+    let mon_interval = cfg.poll_interval;
 
     tokio::spawn(async move {
         // İlk açılışta hemen prune yapmaması için başlangıç süresini 1 saat geriye alıyoruz.
         let mut last_prune_time = Instant::now() - Duration::from_secs(3600);
+        // Eşiği aşmış mount noktaları; uyarı sadece eşik ilk aşıldığında yayınlanır.
+        let mut full_mounts: HashSet<String> = HashSet::new();
+        let mut hot_sensors: HashSet<&'static str> = HashSet::new();
+        let temp_warn_c = mon_state.config.temp_warn_c;
 
         loop {
-            let stats = sys_mon.snapshot();
+            let mon = sys_mon.clone();
+            let mut stats = match tokio::task::spawn_blocking(move || {
+                mon.lock().unwrap_or_else(|e| e.into_inner()).snapshot()
+            })
+            .await
+            {
+                Ok(stats) => stats,
+                Err(e) => {
+                    error!(event="SYSTEM_SNAPSHOT_FAIL", error=%e, "❌ System snapshot task failed.");
+                    tokio::time::sleep(Duration::from_secs(mon_interval)).await;
+                    continue;
+                }
+            };
+            stats.last_scan = mon_state.last_scan.lock().await.clone();
+            stats.last_report = mon_state.last_report.lock().await.clone();
+            stats.tags = mon_state.config.node_tags.clone();
+
+            let svcs: Vec<ServiceInstance> = mon_state
+                .services_cache
+                .read()
+                .await
+                .values()
+                .cloned()
+                .collect();
+            stats.health_score =
+                Governor::node_health_score(&stats, &svcs, &mon_state.config.health_score_weights);
+
             let mut node_cache = mon_state.node_stats_cache.lock().await;
             *node_cache = stats.clone();
             drop(node_cache);
+            if let Some(storage) = &mon_state.storage {
+                storage.record_node(&stats);
+            }
+            mon_state.node_history.lock().await.record(
+                &mon_node,
+                &stats,
+                chrono::Utc::now().timestamp(),
+            );
+
+            for disk in &stats.disks {
+                let pct = disk.used_pct();
+                if pct > DISK_FULL_WARN_PCT {
+                    if full_mounts.insert(disk.mount_point.clone()) {
+                        warn!(event="DISK_NEARLY_FULL", mount=%disk.mount_point, used_pct=%format!("{:.1}", pct), "💽 Disk almost full.");
+                        mon_tx.send(
+                            WsMessage::DiskWarning {
+                                data: DiskWarning {
+                                    node: mon_node.clone(),
+                                    mount_point: disk.mount_point.clone(),
+                                    used_pct: pct,
+                                },
+                            }
+                            .encode(),
+                        );
+                    }
+                } else {
+                    full_mounts.remove(&disk.mount_point);
+                }
+            }
+
+            // Fansız edge kutuları yük altında throttle eder; eşik aşımı bir kez yayınlanır.
+            for (sensor, temp) in [("cpu", stats.cpu_temp_c), ("gpu", stats.gpu_temp_c)] {
+                match temp {
+                    Some(t) if t > temp_warn_c => {
+                        if hot_sensors.insert(sensor) {
+                            warn!(
+                                event = "TEMPERATURE_HIGH",
+                                sensor,
+                                temp_c = t,
+                                threshold_c = temp_warn_c,
+                                "🌡️ Temperature above threshold, throttling likely."
+                            );
+                            mon_tx.send(
+                                WsMessage::TemperatureWarning {
+                                    data: TemperatureWarning {
+                                        node: mon_node.clone(),
+                                        sensor: sensor.to_string(),
+                                        temp_c: t,
+                                        threshold_c: temp_warn_c,
+                                    },
+                                }
+                                .encode(),
+                            );
+                        }
+                    }
+                    _ => {
+                        hot_sensors.remove(sensor);
+                    }
+                }
+            }
 
             // [SRE OTONOM KORUMA]: Disk %85'i geçerse ve son 1 saatte temizlenmediyse Auto-Prune tetikle
             let disk_pct = if stats.disk_total > 0 {
@@ -123,21 +343,26 @@ async fn main() -> anyhow::Result<()> {
             if disk_pct > 85.0 && last_prune_time.elapsed().as_secs() > 3600 {
                 warn!(event="AUTO_PRUNE_TRIGGERED", disk_usage_pct=%disk_pct, "🚨 Disk space critical (>85%). Triggering autonomous system prune.");
 
-                let docker_clone = mon_state.docker.clone();
+                let prune_state = mon_state.clone();
+                let prune_node = mon_node.clone();
                 tokio::spawn(async move {
-                    let _ = docker_clone.prune_system().await;
+                    let result = prune_state.docker.prune_system().await;
+                    // Kilidi kaybetmek hata değildir: süren prune diski zaten temizliyor.
+                    if result.as_ref().is_err_and(|e| e.is::<PruneBusy>()) {
+                        warn!(
+                            event = "AUTO_PRUNE_SKIPPED",
+                            "⏭️ A prune is already in progress, skipping autonomous prune."
+                        );
+                        return;
+                    }
+                    prune_state
+                        .notifier
+                        .prune_result(&prune_node, "auto", &result);
                 });
 
                 last_prune_time = Instant::now();
             }
 
-            let svcs = mon_state
-                .services_cache
-                .lock()
-                .await
-                .values()
-                .cloned()
-                .collect();
             let report = ClusterReport {
                 node: mon_node.clone(),
                 stats,
@@ -149,16 +374,13 @@ async fn main() -> anyhow::Result<()> {
                 .cluster_cache
                 .lock()
                 .await
-                .insert(mon_node.clone(), report);
-            let cluster_map = mon_state.cluster_cache.lock().await.clone();
+                .insert(mon_node.clone(), Arc::new(report));
 
             // [ARCH-COMPLIANCE FIX]: UI broadcast gürültüsünü debug'a çektik (Eğer debug loglarsak görünmez)
-            // Bu zaten JSON publish, loglamaya gerek yok, sadece TX kanalına gönderiliyor.
-            let _ = mon_tx.send(
-                serde_json::json!({ "type": "cluster_update", "data": cluster_map }).to_string(),
-            );
+            // Bu zaten JSON publish, loglamaya gerek yok; tarama başına bir kez serileştirilir.
+            api::routes::publish_cluster(&mon_state).await;
 
-            tokio::time::sleep(Duration::from_secs(3)).await;
+            tokio::time::sleep(Duration::from_secs(mon_interval)).await;
         }
     });
 
@@ -166,16 +388,27 @@ async fn main() -> anyhow::Result<()> {
     let scan_state = state.clone();
     let scan_node = cfg.node_name.clone();
     let poll_interval = cfg.poll_interval;
+    let autopilot_interval = Duration::from_secs(cfg.autopilot_interval_secs);
 
     tokio::spawn(async move {
         let client = scan_state.docker.get_client();
-        let mut loop_counter = 0;
+        // İlk kontrol, eski davranıştaki gibi bir aralık dolduktan sonra yapılır.
+        let mut next_update_check = Instant::now() + autopilot_interval;
         let mut stats_cache: HashMap<String, ContainerStatsCache> = HashMap::new();
         let mut env_cache: HashMap<String, Vec<String>> = HashMap::new();
+        // HostConfig container ömrü boyunca değişmez; GPU isteği bir kez okunur.
+        let mut gpu_cache: HashMap<String, bool> = HashMap::new();
+        // Container başına (zaman, RestartCount) örnekleri; CRASH_LOOP_WINDOW kadar tutulur.
+        let mut restart_history: HashMap<String, VecDeque<(Instant, u64)>> = HashMap::new();
+        // Durmuş container'ın (OOMKilled, ExitCode) bilgisi yeniden başlayana dek değişmez; bir kez inspect edilir.
+        let mut exit_cache: HashMap<String, (bool, i32)> = HashMap::new();
 
         loop {
-            loop_counter += 1;
-            let do_update_check = loop_counter % 12 == 0;
+            let do_update_check = Instant::now() >= next_update_check;
+            if do_update_check {
+                next_update_check = Instant::now() + autopilot_interval;
+            }
+            let scan_started = Instant::now();
             let node_total_ram = scan_state.node_stats_cache.lock().await.ram_total;
 
             if let Ok(containers) = client
@@ -185,10 +418,62 @@ async fn main() -> anyhow::Result<()> {
                 }))
                 .await
             {
+                // Stats ve inspect çağrıları pahalıdır: çalışan (ve crash-loop'ta yeniden başlayan)
+                // container'lar için sınırlı eşzamanlılıkla, kilitler alınmadan önce birlikte toplanır.
+                let mut prefetched = HashMap::new();
+                let mut probe_jobs = tokio::task::JoinSet::new();
+                let probe_targets = containers
+                    .iter()
+                    .filter(|c| {
+                        !c.labels.as_ref().is_some_and(|l| {
+                            l.get(IGNORE_LABEL)
+                                .is_some_and(|v| v.eq_ignore_ascii_case("true"))
+                        })
+                    })
+                    .filter_map(|c| {
+                        let status = c.status.as_deref().unwrap_or_default().to_lowercase();
+                        let is_up = status.contains("up");
+                        let id = c.id.clone()?;
+                        (is_up || status.contains("restarting") || !exit_cache.contains_key(&id))
+                            .then_some((id, is_up))
+                    });
+                for (id, is_up) in probe_targets {
+                    if probe_jobs.len() >= SCAN_STATS_CONCURRENCY {
+                        if let Some(Ok((id, probe))) = probe_jobs.join_next().await {
+                            prefetched.insert(id, probe);
+                        }
+                    }
+                    let docker = scan_state.docker.clone();
+                    probe_jobs.spawn(async move {
+                        let stats = async {
+                            if is_up {
+                                docker.get_container_stats(&id).await.ok()
+                            } else {
+                                None
+                            }
+                        };
+                        let (stats, inspect) = tokio::join!(stats, docker.inspect_service(&id));
+                        (id, (stats, inspect.ok()))
+                    });
+                }
+                while let Some(res) = probe_jobs.join_next().await {
+                    if let Ok((id, probe)) = res {
+                        prefetched.insert(id, probe);
+                    }
+                }
+
+                let (container_count, probed_count) = (containers.len(), prefetched.len());
+                restart_history
+                    .retain(|id, _| containers.iter().any(|c| c.id.as_ref() == Some(id)));
+                exit_cache.retain(|id, _| containers.iter().any(|c| c.id.as_ref() == Some(id)));
                 let ap_guard = scan_state.auto_pilot_config.lock().await;
-                let mut cache = scan_state.services_cache.lock().await;
+                let mut cache = scan_state.services_cache.write().await;
+                let sample_ts = chrono::Utc::now().to_rfc3339();
+                let mut samples = Vec::new();
 
                 for c in containers {
+                    let container_id = c.id.clone().unwrap_or_default();
+                    // İsimsiz (anonim/çökmüş) container'lar kaybolmasın diye kısa ID ile gösterilir.
                     let name = c
                         .names
                         .unwrap_or_default()
@@ -196,12 +481,28 @@ async fn main() -> anyhow::Result<()> {
                         .cloned()
                         .unwrap_or_default()
                         .replace("/", "");
+                    let name = if name.is_empty() {
+                        container_id.chars().take(12).collect()
+                    } else {
+                        name
+                    };
                     if name.is_empty() {
                         continue;
                     }
+                    let labels = c.labels.unwrap_or_default();
+                    if labels
+                        .get(IGNORE_LABEL)
+                        .is_some_and(|v| v.eq_ignore_ascii_case("true"))
+                    {
+                        cache.remove(&name);
+                        continue;
+                    }
+                    let display_name = labels
+                        .get(DISPLAY_NAME_LABEL)
+                        .map(|v| v.trim().to_string())
+                        .filter(|v| !v.is_empty());
 
                     let is_auto_pilot = *ap_guard.get(&name).unwrap_or(&false);
-                    let container_id = c.id.clone().unwrap_or_default();
                     let status_str = c.status.unwrap_or_default();
                     let is_up = status_str.to_lowercase().contains("up");
 
@@ -213,10 +514,9 @@ async fn main() -> anyhow::Result<()> {
                     let mut disk_read_mbs = 0.0;
                     let mut disk_write_mbs = 0.0;
 
+                    let (stats, inspect) = prefetched.remove(&container_id).unwrap_or_default();
                     if is_up {
-                        if let Ok(stats) =
-                            scan_state.docker.get_container_stats(&container_id).await
-                        {
+                        if let Some(stats) = stats {
                             mem_usage_mb = stats.memory_stats.usage.unwrap_or(0) / 1024 / 1024;
 
                             let cpu_total = stats.cpu_stats.cpu_usage.total_usage;
@@ -287,19 +587,76 @@ async fn main() -> anyhow::Result<()> {
                         stats_cache.remove(&container_id);
                     }
 
-                    if !env_cache.contains_key(&container_id) && is_up {
-                        if let Ok(inspect) = client
-                            .inspect_container(
-                                &container_id,
-                                None::<bollard::container::InspectContainerOptions>,
-                            )
-                            .await
-                        {
-                            if let Some(config) = inspect.config {
-                                if let Some(env) = config.env {
+                    // Healthcheck durumu değişken olduğu için çalışan container her turda inspect edilir.
+                    let mut healthcheck = None;
+                    let mut healthcheck_failing_streak = 0;
+                    let mut restart_count = 0;
+                    let mut started_at = String::new();
+                    if let Some(inspect) = &inspect {
+                        restart_count = inspect.restart_count.unwrap_or(0).max(0) as u64;
+                        started_at = inspect
+                            .state
+                            .as_ref()
+                            .and_then(|s| s.started_at.clone())
+                            .unwrap_or_default();
+                    }
+                    let uptime_secs = if is_up {
+                        chrono::DateTime::parse_from_rfc3339(&started_at)
+                            .map(|t| {
+                                (chrono::Utc::now() - t.with_timezone(&chrono::Utc))
+                                    .num_seconds()
+                                    .max(0) as u64
+                            })
+                            .unwrap_or(0)
+                    } else {
+                        0
+                    };
+                    // Pencere içindeki restart artışı eşiği aşarsa servis crash-loop olarak işaretlenir.
+                    let restarts = restart_history.entry(container_id.clone()).or_default();
+                    restarts.push_back((Instant::now(), restart_count));
+                    while restarts
+                        .front()
+                        .is_some_and(|(t, _)| t.elapsed() > CRASH_LOOP_WINDOW)
+                    {
+                        restarts.pop_front();
+                    }
+                    let crash_looping = restarts.front().is_some_and(|(_, first)| {
+                        restart_count.saturating_sub(*first) > CRASH_LOOP_RESTARTS
+                    });
+                    let (oom_killed, exit_code) = if is_up {
+                        exit_cache.remove(&container_id);
+                        (false, 0)
+                    } else if let Some(st) = inspect.as_ref().and_then(|i| i.state.as_ref()) {
+                        let exit = (
+                            st.oom_killed.unwrap_or(false),
+                            st.exit_code.unwrap_or(0) as i32,
+                        );
+                        exit_cache.insert(container_id.clone(), exit);
+                        exit
+                    } else {
+                        exit_cache.get(&container_id).copied().unwrap_or_default()
+                    };
+                    // Sadece gözlenen geçişte alarm verilir; açılışta zaten OOM'lu container'lar sessizce işaretlenir.
+                    if oom_killed && cache.get(&name).is_some_and(|p| !p.oom_killed) {
+                        report_oom_killed(&scan_state, &scan_node, &name, Some(exit_code));
+                    }
+                    if is_up {
+                        if let Some(inspect) = inspect {
+                            if !env_cache.contains_key(&container_id) {
+                                if let Some(env) = inspect.config.and_then(|c| c.env) {
                                     env_cache.insert(container_id.clone(), env);
                                 }
                             }
+                            if let Some(host) = &inspect.host_config {
+                                gpu_cache
+                                    .entry(container_id.clone())
+                                    .or_insert_with(|| DockerAdapter::requests_gpu(host));
+                            }
+                            if let Some(h) = inspect.state.and_then(|s| s.health) {
+                                healthcheck =
+                                    h.status.map(|s| s.to_string()).filter(|s| !s.is_empty());
+                                healthcheck_failing_streak = h.failing_streak.unwrap_or(0);
+                            }
                         }
                     }
 
@@ -328,21 +685,72 @@ async fn main() -> anyhow::Result<()> {
                             let d_adapter = scan_state.docker.clone();
                             let state_clone = scan_state.clone();
 
+                            let svc_node = scan_node.clone();
+
+                            // Manuel /api/update pencereye takılmaz; sadece bu otomatik tur kısıtlanır.
+                            let in_window = scan_state
+                                .config
+                                .autopilot_window
+                                .is_none_or(|w| w.contains(chrono::Utc::now()));
+
                             tokio::spawn(async move {
-                                let _ = d_adapter.check_and_update_service(&svc_name).await;
+                                if in_window {
+                                    let result =
+                                        d_adapter.check_and_update_service(&svc_name, None).await;
+                                    state_clone
+                                        .notifier
+                                        .update_result(&svc_node, &svc_name, &result);
+                                    // Bir servisin hatası sadece kendi task'ını etkiler; tur diğerleri için sürer.
+                                    if !matches!(result, Ok(false)) {
+                                        state_clone.tx.send(
+                                            WsMessage::AutoPilotResult {
+                                                data: AutoPilotResultData {
+                                                    node: svc_node.clone(),
+                                                    service: svc_name.clone(),
+                                                    updated: result.is_ok(),
+                                                    error: result
+                                                        .as_ref()
+                                                        .err()
+                                                        .map(|e| e.to_string()),
+                                                },
+                                            }
+                                            .encode(),
+                                        );
+                                    }
+                                    if result.is_ok() {
+                                        set_pending_update(&state_clone, &svc_name, false).await;
+                                    }
+                                } else {
+                                    match d_adapter.update_available(&svc_name).await {
+                                        Ok(true) => {
+                                            if set_pending_update(&state_clone, &svc_name, true)
+                                                .await
+                                            {
+                                                info!(event="AUTOPILOT_UPDATE_QUEUED", service=%svc_name, "⏳ Update for [{}] queued until the maintenance window opens.", svc_name);
+                                            }
+                                        }
+                                        Ok(false) => {}
+                                        Err(e) => {
+                                            debug!(event="AUTOPILOT_CHECK_FAIL", service=%svc_name, error=%e, "Off-window update check failed for [{}]", svc_name)
+                                        }
+                                    }
+                                }
                                 let mut release_locks = state_clone.update_locks.lock().await;
                                 release_locks.remove(&svc_name);
                             });
                         }
                     }
 
-                    let has_gpu =
-                        name.contains("llm") || name.contains("stt") || name.contains("tts");
+                    let has_gpu = gpu_cache.get(&container_id).copied().unwrap_or(false);
                     let progress = cache.get(&name).and_then(|s| s.update_progress.clone());
 
                     let svc = ServiceInstance {
                         name: name.clone(),
+                        display_name,
                         image: c.image.unwrap_or_default(),
+                        ports: DockerAdapter::published_ports(
+                            c.ports.as_deref().unwrap_or_default(),
+                        ),
                         status: status_str,
                         short_id: container_id.chars().take(12).collect(),
                         auto_pilot: is_auto_pilot,
@@ -358,33 +766,145 @@ async fn main() -> anyhow::Result<()> {
                         update_progress: progress,
                         health,
                         violations,
+                        healthcheck,
+                        healthcheck_failing_streak,
+                        restart_count,
+                        started_at,
+                        uptime_secs,
+                        crash_looping,
+                        oom_killed,
+                        exit_code,
                     };
 
+                    samples.push((
+                        name.clone(),
+                        MetricSample {
+                            ts: sample_ts.clone(),
+                            cpu: svc.cpu_usage,
+                            mem: svc.mem_usage,
+                        },
+                    ));
+                    if let Some(storage) = &scan_state.storage {
+                        let prev = cache.get(&name);
+                        let new_key = Governor::status_key(&svc.status);
+                        let changed = prev.is_none_or(|p| {
+                            Governor::status_key(&p.status) != new_key || p.health != svc.health
+                        });
+                        if changed {
+                            storage.record_service_change(
+                                &scan_node,
+                                &name,
+                                prev.map(|p| Governor::status_key(&p.status)).as_deref(),
+                                &new_key,
+                                &format!("{:?}", svc.health),
+                            );
+                        }
+                    }
                     cache.insert(name, svc);
                 }
+                drop(cache);
+                drop(ap_guard);
+
+                let mut history = scan_state.service_history.lock().await;
+                // Artık var olmayan servislerin geçmişi atılır, böylece bellek sınırlı kalır.
+                history.retain(|svc_name, _| samples.iter().any(|(n, _)| n == svc_name));
+                for (svc_name, sample) in samples {
+                    if !history.contains_key(&svc_name)
+                        && history.len() >= SERVICE_HISTORY_MAX_SERVICES
+                    {
+                        continue;
+                    }
+                    let buf = history.entry(svc_name).or_default();
+                    if buf.len() >= SERVICE_HISTORY_LEN {
+                        buf.pop_front();
+                    }
+                    buf.push_back(sample);
+                }
+                drop(history);
+
+                *scan_state.last_scan.lock().await = Some(chrono::Utc::now().to_rfc3339());
+                debug!(
+                    event = "SCAN_COMPLETED",
+                    containers = container_count,
+                    probed = probed_count,
+                    concurrency = SCAN_STATS_CONCURRENCY,
+                    duration_ms = scan_started.elapsed().as_millis() as u64,
+                    "🔍 Service scan completed."
+                );
             }
             tokio::time::sleep(std::time::Duration::from_secs(poll_interval)).await;
         }
     });
 
     // 3. UPSTREAM LOOP
-    if let Some(upstream_url) = cfg.upstream_url {
+    // Kapanışta upstream'e gönderilecek deregister isteği (sadece edge modunda)
+    let mut deregister_req: Option<reqwest::RequestBuilder> = None;
+    if let Some(upstream_url) = cfg.upstream_url.clone() {
         let up_state = state.clone();
 
+        // Özel CA / istemci sertifikası hatalıysa sessizce düz bağlantıya düşmek yerine açılış durdurulur.
+        let tls_connector = api::tls::upstream_connector(&cfg)?;
+
         // [ARCH-COMPLIANCE FIX]: Timeout Eklendi. İstemcinin sonsuza dek kilitlenmesini önler.
-        let http_client = Client::builder()
-            .timeout(std::time::Duration::from_secs(5))
+        let mut http_client = Client::builder().timeout(std::time::Duration::from_secs(5));
+        if let Some(connector) = tls_connector.clone() {
+            http_client = http_client.use_preconfigured_tls(connector);
+        }
+        let http_client = http_client
             .build()
             .expect("Failed to build robust HTTP client");
 
         let node_name = cfg.node_name.clone();
+        let report_interval = cfg.poll_interval;
+        let node_token = cfg.node_auth_token.clone();
+        // NODE_AUTH_TOKEN varsa edge sadece x-node-token taşır; admin token'ı yalnızca eski
+        // kurulumlarda (node token'sız) upstream'e gönderilir.
+        let upstream_token = match node_token {
+            Some(_) => None,
+            None => cfg.admin_token.clone().or(cfg.api_token.clone()),
+        };
+
+        // Upstream'den gelen uzak komutlar (start/stop/restart/update) için kalıcı kanal
+        match api::agent::agent_url(&upstream_url, &node_name) {
+            Ok(agent_url) => {
+                tokio::spawn(api::agent::run_edge_link(
+                    state.clone(),
+                    agent_url,
+                    tls_connector,
+                    cfg.node_auth_token.clone(),
+                    upstream_token.clone(),
+                ));
+            }
+            Err(e) => {
+                warn!(event="UPSTREAM_COMMAND_LINK_DISABLED", error=%e, "⚠️ Could not derive upstream command link URL.")
+            }
+        }
+
+        match api::agent::deregister_url(&upstream_url, &node_name) {
+            Ok(url) => {
+                let mut req = http_client.delete(url);
+                if let Some(token) = &upstream_token {
+                    req = req.bearer_auth(token);
+                }
+                if let Some(token) = &node_token {
+                    req = req.header(NODE_TOKEN_HEADER, token);
+                }
+                deregister_req = Some(req);
+            }
+            Err(e) => {
+                warn!(event="UPSTREAM_DEREGISTER_DISABLED", error=%e, "⚠️ Could not derive upstream deregister URL.")
+            }
+        }
 
         tokio::spawn(async move {
             info!(event="UPSTREAM_LINK_INIT", url=%upstream_url, "Upstream raporlama başlatılıyor.");
+            // Kesinti boyunca art arda başarısız rapor sayısı; sadece ilk hata loglanır.
+            let mut failed_reports: u32 = 0;
+            let mut backoff = Duration::from_secs(report_interval);
             loop {
                 let svcs: Vec<ServiceInstance> = up_state
                     .services_cache
-                    .lock()
+                    .read()
                     .await
                     .values()
                     .cloned()
@@ -406,22 +926,346 @@ async fn main() -> anyhow::Result<()> {
                 );
 
                 // Info seviyesindeki span gürültüsü engellenir, sessizce iletilir.
-                let _ = http_client
+                let mut req = http_client
                     .post(&upstream_url)
                     .header("x-trace-id", &trace_id)
-                    .json(&payload)
-                    .send()
-                    .await;
+                    .json(&payload);
+                if let Some(token) = &upstream_token {
+                    req = req.bearer_auth(token);
+                }
+                if let Some(token) = &node_token {
+                    req = req.header(NODE_TOKEN_HEADER, token);
+                }
+                let failure = match req.send().await {
+                    Ok(r) if r.status().is_success() => None,
+                    Ok(r) => Some(format!("HTTP {}", r.status())),
+                    Err(e) => Some(e.to_string()),
+                };
+                match failure {
+                    None => {
+                        *up_state.last_report.lock().await = Some(chrono::Utc::now().to_rfc3339());
+                        if failed_reports > 0 {
+                            info!(
+                                event = "UPSTREAM_RECONNECTED",
+                                failed_reports, "🔗 Upstream reconnected."
+                            );
+                        }
+                        failed_reports = 0;
+                        backoff = Duration::from_secs(report_interval);
+                    }
+                    Some(error) => {
+                        if failed_reports == 0 {
+                            warn!(event="UPSTREAM_REPORT_FAIL", error=%error, "⚠️ Upstream unreachable, backing off until it recovers.");
+                        }
+                        failed_reports += 1;
+                        // Kesinti süresince rapor aralığı üstel olarak UPSTREAM_MAX_BACKOFF'a kadar açılır.
+                        backoff = (backoff * 2)
+                            .min(UPSTREAM_MAX_BACKOFF)
+                            .max(Duration::from_secs(report_interval));
+                    }
+                }
 
-                tokio::time::sleep(Duration::from_secs(10)).await;
+                tokio::time::sleep(backoff).await;
             }
         });
     }
 
+    // 4. DOCKER CONNECTIVITY WATCHDOG
+    let dog_state = state.clone();
+    let exit_on_docker_loss = cfg.exit_on_docker_loss;
+    let docker_loss_exit_secs = cfg.docker_loss_exit_secs;
+
+    tokio::spawn(async move {
+        let mut lost_since: Option<Instant> = None;
+        loop {
+            match dog_state.docker.ping().await {
+                Ok(_) => {
+                    if let Some(since) = lost_since.take() {
+                        info!(
+                            event = "DOCKER_CONNECTION_RESTORED",
+                            outage_secs = since.elapsed().as_secs(),
+                            "🐳 Docker connection restored."
+                        );
+                    }
+                }
+                Err(e) => {
+                    let since = *lost_since.get_or_insert_with(|| {
+                        warn!(event="DOCKER_CONNECTION_LOST", error=%e, "🚨 Docker connection lost. Retrying...");
+                        Instant::now()
+                    });
+                    // Fail-fast: dış supervisor (systemd, restart policy) süreci temiz başlatsın.
+                    if exit_on_docker_loss && since.elapsed().as_secs() >= docker_loss_exit_secs {
+                        error!(event="DOCKER_LOSS_EXIT", outage_secs=since.elapsed().as_secs(), "❌ Docker unreachable beyond the allowed window. Exiting for supervisor restart.");
+                        std::process::exit(1);
+                    }
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    });
+
+    // 5. NODE LIVENESS WATCHDOG (Rapor göndermeyi bırakan node'lar OFFLINE işaretlenir, uzun süre
+    // sessiz kalanlar cluster'dan düşürülür)
+    let live_state = state.clone();
+    let node_offline_secs = cfg.node_offline_secs;
+    let node_evict_secs = cfg.node_evict_secs;
+    let local_node = cfg.node_name.clone();
+    let watchdog_interval = Duration::from_secs(cfg.watchdog_interval_secs);
+
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(watchdog_interval).await;
+            let now = chrono::Utc::now();
+            let mut cluster = live_state.cluster_cache.lock().await;
+            let mut changed = false;
+            let mut evicted = Vec::new();
+            for (node, report) in cluster.iter_mut() {
+                let age = chrono::DateTime::parse_from_rfc3339(&report.stats.last_seen)
+                    .map(|t| (now - t.with_timezone(&chrono::Utc)).num_seconds());
+                if *node != local_node && age.as_ref().is_ok_and(|a| *a > node_evict_secs) {
+                    evicted.push(node.clone());
+                    continue;
+                }
+                if report.stats.status != "ONLINE" {
+                    continue;
+                }
+                if age.is_ok_and(|a| a > node_offline_secs) {
+                    warn!(event="NODE_OFFLINE", node=%node, last_seen=%report.stats.last_seen, "📴 Node stopped reporting, marked OFFLINE.");
+                    Arc::make_mut(report).stats.status = "OFFLINE".to_string();
+                    api::routes::node_status_changed(
+                        &live_state,
+                        node,
+                        "ONLINE",
+                        "OFFLINE",
+                        &report.stats.last_seen,
+                    );
+                    changed = true;
+                }
+            }
+            drop(cluster);
+            if changed {
+                api::routes::publish_cluster(&live_state).await;
+            }
+            for node in evicted {
+                if api::routes::remove_node(&live_state, &node).await {
+                    warn!(event="NODE_EVICTED", node=%node, grace_secs=node_evict_secs, "🗑️ Node silent beyond grace period, evicted from cluster.");
+                }
+            }
+        }
+    });
+
+    // 6. DOCKER EVENT STREAM (Anlık durum değişiklikleri; tarama döngüsü uzlaştırma için kalır)
+    let evt_state = state.clone();
+    let evt_tx = tx.clone();
+
+    tokio::spawn(async move {
+        let mut backoff = Duration::from_secs(1);
+        loop {
+            let mut events = evt_state.docker.get_event_stream();
+            while let Some(res) = events.next().await {
+                match res {
+                    Ok(ev) => {
+                        backoff = Duration::from_secs(1);
+                        let action = ev.action.unwrap_or_default();
+                        let actor = ev.actor.unwrap_or_default();
+                        let container = actor
+                            .attributes
+                            .as_ref()
+                            .and_then(|a| a.get("name").cloned())
+                            .or(actor.id)
+                            .unwrap_or_default();
+                        // Canlı `oom` event'i: tarama aynı geçişi tekrar bildirmesin diye önbellek işaretlenir.
+                        if action == "oom" {
+                            let first = evt_state
+                                .services_cache
+                                .write()
+                                .await
+                                .get_mut(&container)
+                                .map(|svc| !std::mem::replace(&mut svc.oom_killed, true))
+                                .unwrap_or(true);
+                            if first {
+                                report_oom_killed(
+                                    &evt_state,
+                                    &evt_state.config.node_name,
+                                    &container,
+                                    None,
+                                );
+                            }
+                        }
+                        evt_tx.send(
+                            WsMessage::DockerEvent {
+                                status: Governor::container_status_for_event(&action),
+                                action,
+                                container,
+                            }
+                            .encode(),
+                        );
+                    }
+                    Err(e) => {
+                        warn!(event="DOCKER_EVENT_STREAM_ERROR", error=%e, "⚠️ Docker event stream error.");
+                        break;
+                    }
+                }
+            }
+            warn!(
+                event = "DOCKER_EVENT_STREAM_LOST",
+                retry_secs = backoff.as_secs(),
+                "🔌 Docker event stream dropped. Reconnecting..."
+            );
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(Duration::from_secs(60));
+        }
+    });
+
+    // 7. ZAMANLANMIŞ JANITOR (JANITOR_SCHEDULE tanımlı değilse hiç başlatılmaz)
+    let schedule = cfg.janitor_schedule;
+    if schedule != JanitorSchedule::Disabled {
+        let jan_state = state.clone();
+        let jan_tx = tx.clone();
+        let jan_node = cfg.node_name.clone();
+        info!(event="JANITOR_SCHEDULED", schedule=?schedule, "🗓️ Scheduled janitor enabled.");
+
+        tokio::spawn(async move {
+            while let Some(delay) = schedule.next_delay(chrono::Local::now()) {
+                tokio::time::sleep(delay).await;
+                let result = jan_state.docker.prune_with(&PruneParams::default()).await;
+                if result.as_ref().is_err_and(|e| e.is::<PruneBusy>()) {
+                    warn!(
+                        event = "JANITOR_SKIPPED",
+                        "⏭️ A prune is already in progress, skipping scheduled run."
+                    );
+                    continue;
+                }
+                let summary = result
+                    .as_ref()
+                    .map(|r| r.summary.clone())
+                    .map_err(|e| anyhow::anyhow!("{}", e));
+                jan_state
+                    .notifier
+                    .prune_result(&jan_node, "scheduled", &summary);
+                jan_tx.send(
+                    WsMessage::PruneResult {
+                        data: PruneResultData {
+                            node: jan_node.clone(),
+                            trigger: "scheduled".to_string(),
+                            success: result.is_ok(),
+                            error: result.as_ref().err().map(|e| e.to_string()),
+                            report: result.ok(),
+                        },
+                    }
+                    .encode(),
+                );
+            }
+        });
+    }
+
+    // 8. gRPC SAĞLIK SERVİSİ (grpc.health.v1, service mesh yoklamaları için; opsiyonel reflection)
+    tokio::spawn(api::grpc::serve(
+        state.clone(),
+        cfg.host.clone(),
+        cfg.grpc_port,
+    ));
+
     let app = api::routes::create_router(state.clone());
-    let addr = format!("{}:{}", cfg.host, cfg.http_port);
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
 
-    Ok(())
+    let server = async move {
+        #[cfg(unix)]
+        if let Some(uds_path) = &cfg.http_uds_path {
+            return api::uds::serve(uds_path, app).await;
+        }
+
+        let addr = format!("{}:{}", cfg.host, cfg.http_port);
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        if let (Some(cert), Some(key)) = (&cfg.tls_cert, &cfg.tls_key) {
+            return api::tls::serve(listener, app, cert, key, cfg.tls_client_ca.as_deref()).await;
+        }
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await?;
+        Ok(())
+    };
+
+    tokio::select! {
+        res = server => res,
+        _ = shutdown_signal() => {
+            info!(event = "SHUTDOWN", "🛑 Shutdown signal received.");
+            // Edge node kapanırken upstream'deki kartı OFFLINE beklemeden kaldırılır.
+            if let Some(req) = deregister_req {
+                match req.send().await {
+                    Ok(r) if r.status().is_success() => {
+                        info!(event = "UPSTREAM_DEREGISTERED", "👋 Deregistered from upstream.")
+                    }
+                    Ok(r) => {
+                        warn!(event="UPSTREAM_DEREGISTER_FAIL", status=%r.status(), "⚠️ Upstream rejected deregistration.")
+                    }
+                    Err(e) => {
+                        warn!(event="UPSTREAM_DEREGISTER_FAIL", error=%e, "⚠️ Upstream unreachable, deregistration skipped.")
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn report_oom_killed(state: &AppState, node: &str, svc: &str, exit_code: Option<i32>) {
+    warn!(event="CONTAINER_OOM_KILLED", service=%svc, exit_code=?exit_code, "💥 [{}] was killed by the kernel OOM killer.", svc);
+    state.notifier.oom_killed(node, svc, exit_code);
+    state.tx.send(
+        WsMessage::OomKilled {
+            data: OomKilledData {
+                node: node.to_string(),
+                service: svc.to_string(),
+                exit_code,
+            },
+        }
+        .encode(),
+    );
+}
+
+// Bekleyen güncelleme kuyruğunu değiştirir ve değiştiyse diske yazar; kuyruk değiştiyse `true` döner.
+async fn set_pending_update(state: &AppState, svc: &str, pending: bool) -> bool {
+    let snapshot = {
+        let mut queue = state.pending_updates.lock().await;
+        let changed = if pending {
+            queue.insert(svc.to_string())
+        } else {
+            queue.remove(svc)
+        };
+        if !changed {
+            return false;
+        }
+        queue.clone()
+    };
+    if let Err(e) =
+        AutoPilotStore::save_pending(&state.config.autopilot_pending_path, &snapshot).await
+    {
+        warn!(event="AUTOPILOT_PENDING_SAVE_FAIL", path=%state.config.autopilot_pending_path, error=%e, "⚠️ Failed to persist pending update queue.");
+    }
+    true
+}
+
+// Ctrl+C veya SIGTERM (docker stop / systemd) ile tetiklenir.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sig) => {
+                sig.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
 }
@@ -2,9 +2,13 @@
 import { WebSocketStream } from './websocket.js';
 import { TopologyMap } from './components/topology.js';
 import { Store } from './store.js';
+import { Auth } from './auth.js';
 
 let isAppPaused = false; 
 
+// Container label'ları ve node etiketleri dışarıdan gelir; innerHTML'e basılmadan önce kaçırılır.
+const escapeHtml = (value) => String(value ?? '').replace(/[&<>"']/g, c => ({ '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;', "'": '&#39;' })[c]);
+
 const ui = {
     grid: document.getElementById('services-grid'),
     clusterList: document.getElementById('cluster-list'),
@@ -28,6 +32,11 @@ const ui = {
                 const vD = document.getElementById('v-badge-desktop');
                 if(vM) vM.innerText = `v${data.version}`;
                 if(vD) vD.innerText = `v${data.version}`;
+                if(data.cluster_name) {
+                    document.title = `SENTIRIC | ${data.cluster_name.toUpperCase()} | NEXUS ORCHESTRATOR`;
+                    const cN = document.getElementById('cluster-name');
+                    if(cN) cN.innerText = data.cluster_name.toUpperCase();
+                }
             })
             .catch(e => console.warn("[UI] Config fetch skipped:", e.message));
 
@@ -145,6 +154,28 @@ const ui = {
             }
         });        
 
+        this.safeClick('btn-update-all', async () => {
+            if(confirm('Pull and update every auto-pilot enabled service now?')) {
+                const btn = document.getElementById('btn-update-all');
+                const origText = btn.innerHTML;
+                btn.innerHTML = "⏳ UPDATING...";
+                btn.disabled = true;
+                try {
+                    const res = await fetch('/api/update-all', { method: 'POST' });
+                    const results = await res.json();
+                    const failed = results.filter(r => r.error);
+                    let msg = `${results.length - failed.length}/${results.length} services processed successfully.`;
+                    if (failed.length) msg += '\n\n' + failed.map(r => `❌ ${r.service}: ${r.error}`).join('\n');
+                    alert(msg);
+                } catch(e) {
+                    alert("❌ Connection error during batch update.");
+                } finally {
+                    btn.innerHTML = origText;
+                    btn.disabled = false;
+                }
+            }
+        });
+
         this.safeClick('btn-export', async () => {
             try {
                 const res = await fetch('/api/export/llm');
@@ -189,9 +220,25 @@ const ui = {
                     } else if (action === 'restart') {
                         fetch(`/api/service/${sname}/restart`, {method:'POST'}).catch(console.error);
                         btnAction.innerHTML = "⏳";
+                    } else if (action === 'pause') {
+                        const op = btnAction.dataset.paused === 'true' ? 'unpause' : 'pause';
+                        fetch(`/api/service/${sname}/${op}`, {method:'POST'}).catch(console.error);
+                        btnAction.innerHTML = "⏳";
                     } else if (action === 'force_pull') {
                         if(confirm(`Force Pull Latest Image & Recreate ${sname}?`)) {
-                            fetch(`/api/update?service=${sname}`, {method:'POST'}).catch(console.error);
+                            // Pull ilerlemesi /ws/update üzerinden akar; soket kapansa da güncelleme sürer.
+                            const upd = new WebSocket(Auth.wsUrl(`/ws/update/${sname}`));
+                            upd.onmessage = (ev) => {
+                                const m = JSON.parse(ev.data);
+                                if (m.type === 'pull' && m.status) {
+                                    btnAction.innerHTML = m.total ? `⬇ ${Math.round(m.current / m.total * 100)}%` : "⏳";
+                                } else if (m.type === 'lifecycle') {
+                                    btnAction.innerHTML = `⏳ ${m.phase.toUpperCase()}`;
+                                } else if (m.type === 'result') {
+                                    if (!m.ok) alert(`❌ Update failed for ${sname}: ${m.error}`);
+                                    upd.close();
+                                }
+                            };
                             btnAction.innerHTML = "⏳";
                         }
                     } else if (action === 'ap') {
@@ -213,27 +260,57 @@ const ui = {
         const nodes = Object.keys(state.cluster).sort();
         if (!state.selectedNode && nodes.length > 0) { Store.dispatch('SELECT_NODE', nodes[0]); return; }
 
+        // Etiketli node varsa ilk etikete göre gruplanır; grup başlığına tıklayınca grup daraltılır.
+        this.collapsedGroups = this.collapsedGroups || new Set();
+        const grouped = nodes.some(n => (state.cluster[n].stats.tags || []).length > 0);
+        const groups = new Map();
+        nodes.forEach(n => {
+            const group = grouped ? ((state.cluster[n].stats.tags || [])[0] || 'untagged') : '';
+            if (!groups.has(group)) groups.set(group, []);
+            groups.get(group).push(n);
+        });
+
         let html = '';
-        nodes.forEach(nodeName => {
-            const data = state.cluster[nodeName];
-            const isActive = nodeName === state.selectedNode ? 'active' : '';
-            const cpu = data.stats.cpu_usage.toFixed(0);
-            const ram = Math.round((data.stats.ram_used / data.stats.ram_total) * 100) || 0;
-
-            html += `
-                <div class="node-item ${isActive}" onclick="window.Store.dispatch('SELECT_NODE', '${nodeName}')">
-                    <div class="node-item-head">
-                        <span class="node-item-name">💠 ${nodeName}</span>
-                        <div class="node-status-dot ${data.stats.status === 'ONLINE' ? 'online' : 'offline'}"></div>
-                    </div>
-                    <div class="mini-stats" style="margin-bottom:0">
-                        <div class="mini-stat-bar"><div style="width:${cpu}%" class="mini-stat-fill cpu"></div></div>
-                        <div class="mini-stat-bar"><div style="width:${ram}%" class="mini-stat-fill ram"></div></div>
+        [...groups.keys()].sort().forEach(group => {
+            const collapsed = this.collapsedGroups.has(group);
+            if (grouped) {
+                html += `<div class="node-group-head" data-group="${escapeHtml(group)}">${collapsed ? '▸' : '▾'} ${escapeHtml(group)} <span>${groups.get(group).length}</span></div>`;
+            }
+            if (collapsed) return;
+            groups.get(group).forEach(nodeName => {
+                const data = state.cluster[nodeName];
+                const isActive = nodeName === state.selectedNode ? 'active' : '';
+                const cpu = data.stats.cpu_usage.toFixed(0);
+                const ram = Math.round((data.stats.ram_used / data.stats.ram_total) * 100) || 0;
+
+                html += `
+                    <div class="node-item ${isActive}" data-node="${escapeHtml(nodeName)}">
+                        <div class="node-item-head">
+                            <span class="node-item-name">💠 ${escapeHtml(nodeName)}</span>
+                            <div class="node-status-dot ${data.stats.status === 'ONLINE' ? 'online' : 'offline'}"></div>
+                        </div>
+                        <div class="mini-stats" style="margin-bottom:0">
+                            <div class="mini-stat-bar"><div style="width:${cpu}%" class="mini-stat-fill cpu"></div></div>
+                            <div class="mini-stat-bar"><div style="width:${ram}%" class="mini-stat-fill ram"></div></div>
+                        </div>
                     </div>
-                </div>
-            `;
+                `;
+            });
         });
         this.clusterList.innerHTML = html;
+        // Etiket ve node adları edge'den gelir; inline onclick yerine data-* üzerinden bağlanır.
+        this.clusterList.querySelectorAll('.node-group-head').forEach(el => {
+            el.addEventListener('click', () => this.toggleNodeGroup(el.dataset.group));
+        });
+        this.clusterList.querySelectorAll('.node-item').forEach(el => {
+            el.addEventListener('click', () => Store.dispatch('SELECT_NODE', el.dataset.node));
+        });
+    },
+
+    toggleNodeGroup(group) {
+        if (this.collapsedGroups.has(group)) this.collapsedGroups.delete(group);
+        else this.collapsedGroups.add(group);
+        this.renderSidebar(Store.state);
     },
 
     updateSelectedNodeDOM(state) {
@@ -274,6 +351,15 @@ const ui = {
         const elHostNetVal = document.getElementById('host-net-val');
         if(elHostNetVal) elHostNetVal.innerText = `${h.net_rx_mbs.toFixed(1)} ↓ | ${h.net_tx_mbs.toFixed(1)} ↑ MB/s`;
 
+        // LOAD AVERAGE & UPTIME
+        const elHostLoadVal = document.getElementById('host-load-val');
+        if(elHostLoadVal) {
+            const load = (h.load_avg || [0, 0, 0]).map(v => v.toFixed(2)).join(' ');
+            const up = h.uptime_secs || 0;
+            const upText = up >= 86400 ? `${Math.floor(up / 86400)}d` : up >= 3600 ? `${Math.floor(up / 3600)}h` : `${Math.floor(up / 60)}m`;
+            elHostLoadVal.innerText = `${load} · up ${upText}`;
+        }
+
         // GPU GİZLE/GÖSTER
         const gpuContainer = document.getElementById('gpu-metrics-container');
         if (h.gpu_mem_total > 0) {
@@ -328,6 +414,7 @@ const ui = {
                         btnStart: cardEl.querySelector('.btn-api-action[data-action="start"]'),
                         btnStop: cardEl.querySelector('.btn-api-action[data-action="stop"]'),
                         btnRestart: cardEl.querySelector('.btn-api-action[data-action="restart"]'),
+                        btnPause: cardEl.querySelector('.btn-api-action[data-action="pause"]'),
                         btnPull: cardEl.querySelector('.btn-api-action[data-action="force_pull"]'),
                         btnAp: cardEl.querySelector('.btn-api-action[data-action="ap"]')
                     }
@@ -342,7 +429,7 @@ const ui = {
         return `
             <div class="overlay-container"></div>
             <div class="svc-header">
-                <div><div class="svc-title"></div><span class="svc-image">${svc.image.split('@')[0]}</span></div>
+                <div><div class="svc-title"></div><span class="svc-image">${escapeHtml(svc.image.split('@')[0])}</span></div>
                 <div class="svc-status" style="color:var(--text-main);"></div>
             </div>
             <div class="svc-metrics">
@@ -363,6 +450,7 @@ const ui = {
                 <button class="btn btn-api-action" data-action="start" data-id="${svc.short_id}" data-name="${svc.name}">▶</button>
                 <button class="btn btn-api-action" data-action="stop" data-id="${svc.short_id}" data-name="${svc.name}">■</button>
                 <button class="btn btn-api-action" data-action="restart" data-id="${svc.short_id}" data-name="${svc.name}">↻</button>
+                <button class="btn btn-api-action" data-action="pause" data-id="${svc.short_id}" data-name="${svc.name}">⏸</button>
                 <button class="btn btn-api-action" data-action="force_pull" data-id="${svc.short_id}" data-name="${svc.name}">⬇ PULL</button>
                 <button class="btn btn-info" data-id="${svc.short_id}" data-name="${svc.name}">INFO</button>
                 <button class="btn btn-api-action" data-action="ap" data-id="${svc.short_id}" data-name="${svc.name}">AP</button>
@@ -387,12 +475,28 @@ const ui = {
         } else if (svc.health === 'Online') {
             statusClass = 'status-online'; statusText = 'RUNNING';
         }
+        if (svc.healthcheck === 'unhealthy') {
+            badgesHtml += `<span class="badge badge-oom">🩺 UNHEALTHY (${svc.healthcheck_failing_streak})</span>`;
+        } else if (svc.healthcheck === 'starting') {
+            badgesHtml += `<span class="badge badge-draining">🩺 STARTING</span>`;
+        }
+        if (svc.crash_looping) {
+            badgesHtml += `<span class="badge badge-oom">🔁 CRASH LOOP (${svc.restart_count})</span>`;
+        } else if (svc.restart_count > 0) {
+            badgesHtml += `<span class="badge badge-draining">🔁 ${svc.restart_count} RESTARTS</span>`;
+        }
+        if (svc.oom_killed) {
+            badgesHtml += `<span class="badge badge-oom">💥 OOM KILLED (${svc.exit_code})</span>`;
+        }
+        (svc.ports || []).filter(p => p.protocol === 'tcp').forEach(p => {
+            badgesHtml += `<a class="badge badge-port" href="${location.protocol}//${location.hostname}:${p.host_port}" target="_blank" rel="noopener" title="${p.container_port}/${p.protocol}">:${p.host_port}</a>`;
+        });
         if (svc.has_gpu) badgesHtml += `<span class="badge badge-gpu">GPU</span>`;
 
         if (cardData.element.className !== `service-card ${statusClass}`) cardData.element.className = `service-card ${statusClass}`;
         
         cardData.ui.statusText.innerText = statusText;
-        cardData.ui.titleGroup.innerHTML = `${svc.name} ${badgesHtml}`;
+        cardData.ui.titleGroup.innerHTML = `${escapeHtml(svc.display_name || svc.name)} ${badgesHtml}`;
         cardData.ui.cpuText.innerText = `${svc.cpu_usage.toFixed(1)}%`;
         cardData.ui.ramText.innerText = `${svc.mem_usage} MB`;
         
@@ -419,9 +523,14 @@ const ui = {
         if (isRemote) {
             cardData.ui.btnStart.style.display = 'none'; cardData.ui.btnStop.style.display = 'none';
             cardData.ui.btnRestart.style.display = 'none'; cardData.ui.btnPull.style.display = 'none'; cardData.ui.btnAp.style.display = 'none';
+            cardData.ui.btnPause.style.display = 'none';
         } else {
             cardData.ui.btnStart.innerHTML = "▶"; cardData.ui.btnStop.innerHTML = "■"; cardData.ui.btnRestart.innerHTML = "↻"; cardData.ui.btnPull.innerHTML = "⬇ PULL";
             cardData.ui.btnStart.disabled = isUp; cardData.ui.btnStop.disabled = btnDisabled; cardData.ui.btnRestart.disabled = btnDisabled; cardData.ui.btnPull.disabled = !isUp;
+            const isPaused = svc.status.toLowerCase().includes('paused');
+            cardData.ui.btnPause.dataset.paused = isPaused;
+            cardData.ui.btnPause.innerHTML = isPaused ? "⏯" : "⏸";
+            cardData.ui.btnPause.disabled = !isUp || svc.update_progress != null;
             if (svc.auto_pilot) cardData.ui.btnAp.classList.add('btn-primary');
             else cardData.ui.btnAp.classList.remove('btn-primary');
         }
@@ -485,9 +594,9 @@ const ui = {
         if(vioView) vioView.classList.add('active');
 
         if(vioOutput) {
-            let html = `<h3 style="color:#fff; margin-bottom:10px;">⚠️ SENTINEL ALERTS: ${svc.name}</h3>`;
+            let html = `<h3 style="color:#fff; margin-bottom:10px;">⚠️ SENTINEL ALERTS: ${escapeHtml(svc.name)}</h3>`;
             html += `<p style="color:#999; margin-bottom:15px;">This service has architectural warnings but is still running.</p><ul style="padding-left:20px;">`;
-            (svc.violations || []).forEach(v => { html += `<li style="margin-bottom:10px;">${v}</li>`; });
+            (svc.violations || []).forEach(v => { html += `<li style="margin-bottom:10px;">${escapeHtml(v)}</li>`; });
             html += `</ul><br><p style="color:#666; font-style:italic;">Action Required: Fix the .env variables to clear these alerts.</p>`;
             vioOutput.innerHTML = html;
         }
@@ -498,7 +607,7 @@ const ui = {
         if (!id || id === 'null') return;
         if (this.logSocket) this.logSocket.close();
         
-        this.logSocket = new WebSocket(`ws://${window.location.host}/ws/logs/${id}`);
+        this.logSocket = new WebSocket(Auth.wsUrl(`/ws/logs/${id}`));
         this.logSocket.onmessage = (e) => {
             const logOutput = document.getElementById('log-output');
             if (logOutput) {
@@ -506,7 +615,7 @@ const ui = {
                     const data = JSON.parse(e.data);
                     const div = document.createElement('div');
                     div.className = "term-row";
-                    div.innerHTML = `<span class="term-time">[${data.ts ? data.ts.substring(11, 19) : ''}]</span> <span class="term-msg">${data.message || JSON.stringify(data)}</span>`;
+                    div.innerHTML = `<span class="term-time">[${escapeHtml(data.ts ? data.ts.substring(11, 19) : '')}]</span> <span class="term-msg">${escapeHtml(data.message || JSON.stringify(data))}</span>`;
                     logOutput.appendChild(div);
                     if(logOutput.childNodes.length > 500) logOutput.removeChild(logOutput.firstChild);
                 } catch(err) {
@@ -518,6 +627,14 @@ const ui = {
                 if(logView) logView.scrollTop = logView.scrollHeight;
             }
         };
+        this.logSocket.onclose = (e) => {
+            const logOutput = document.getElementById('log-output');
+            if (logOutput && e.reason) {
+                const div = document.createElement('div');
+                div.className = "term-row"; div.innerText = `⏹ ${e.reason}`;
+                logOutput.appendChild(div);
+            }
+        };
     },
 
     async loadInspect(id) {
@@ -569,14 +686,52 @@ document.addEventListener("visibilitychange", () => {
 window.addEventListener('load', () => {
     ui.init(); 
 
-    new WebSocketStream(`ws://${window.location.host}/ws`, (msg) => {
+    // Sunucunun yayın şeması (WS_SCHEMA_VERSION) farklıysa bir kez uyarılır.
+    const WS_SCHEMA_VERSION = 1;
+    let schemaWarned = false;
+    new WebSocketStream(Auth.wsUrl(`/ws`), (msg) => {
         ui.updateConnectionStatus(true);
+        if (msg.v !== WS_SCHEMA_VERSION && !schemaWarned) {
+            schemaWarned = true;
+            console.warn(`⚠️ Server message schema v${msg.v} differs from dashboard v${WS_SCHEMA_VERSION}; reload the page.`);
+        }
+        // Bağlanmadan önce yaşanmış olaylar (`replayed`) geçmiş olarak işaretlenip loglanır.
+        const h = msg.replayed ? '🕘 [history] ' : '';
         if (msg.type === 'cluster_update') {
             Store.dispatch('CLUSTER_UPDATE', msg.data);
+        } else if (msg.type === 'nodes_list_update') {
+            Store.dispatch('NODES_LIST_UPDATE', msg.data);
+        } else if (msg.type === 'lagged') {
+            console.warn(`🐢 Live feed lagged, ${msg.skipped} updates skipped. Resyncing.`);
+        } else if (msg.type === 'disk_warning') {
+            console.warn(`${h}💽 ${msg.data.node}: ${msg.data.mount_point} is ${msg.data.used_pct.toFixed(1)}% full`);
+        } else if (msg.type === 'temperature_warning') {
+            console.warn(`${h}🌡️ ${msg.data.node}: ${msg.data.sensor.toUpperCase()} at ${msg.data.temp_c.toFixed(0)}°C (threshold ${msg.data.threshold_c}°C)`);
+        } else if (msg.type === 'prune_result') {
+            const d = msg.data;
+            if (d.success) console.info(`${h}🧹 ${d.node}: ${d.trigger} prune — ${d.report.summary}`);
+            else console.warn(`${h}🧹 ${d.node}: ${d.trigger} prune failed — ${d.error}`);
+        } else if (msg.type === 'oom_killed') {
+            console.error(`${h}💥 ${msg.data.node}: ${msg.data.service} was OOM-killed`);
+        } else if (msg.type === 'lifecycle') {
+            const d = msg.data;
+            console.info(`${h}📋 ${d.node}: ${d.service} ${d.kind}${d.message ? ` — ${d.message}` : ''}`);
+        } else if (msg.type === 'auto_pilot_result') {
+            const d = msg.data;
+            if (d.updated) console.info(`${h}🚀 ${d.node}: auto-pilot updated ${d.service}`);
+            else console.warn(`${h}🚀 ${d.node}: auto-pilot update failed for ${d.service} — ${d.error}`);
+        } else if (msg.type === 'node_status_changed') {
+            const d = msg.data;
+            const log = d.new_status === 'OFFLINE' ? console.error : console.info;
+            log(`${h}🔁 ${d.node}: ${d.old_status} → ${d.new_status} (last seen ${d.last_seen})`);
+        } else if (msg.type === 'docker_event') {
+            Store.dispatch('DOCKER_EVENT', msg);
+        } else if (msg.type === 'services_update') {
+            Store.dispatch('SERVICES_UPDATE', msg.data);
         } else if (msg.type === 'update_progress') {
             Store.dispatch('UPDATE_PROGRESS', msg.data); 
         }
     }, (isOnline) => {
         ui.updateConnectionStatus(isOnline);
-    }).connect();
+    }, Auth.sseUrl('/sse')).connect();
 });
\ No newline at end of file
@@ -0,0 +1,55 @@
+// src/ui/js/auth.js
+// API_TOKEN etkinse tüm /api isteklerine bearer token eklenir; 401 gelince kullanıcıdan istenir.
+// Token sadece sekme oturumu boyunca (sessionStorage) tutulur, tarayıcı kapanınca silinir.
+const TOKEN_KEY = 'nexus_api_token';
+
+export const Auth = {
+    token() {
+        return sessionStorage.getItem(TOKEN_KEY);
+    },
+
+    // Tarayıcı WebSocket'i header gönderemez, token query parametresi ile taşınır.
+    wsUrl(path) {
+        const t = this.token();
+        const url = `ws://${window.location.host}${path}`;
+        return t ? `${url}?token=${encodeURIComponent(t)}` : url;
+    },
+
+    // EventSource da header gönderemez; WebSocket'i bozan proxy'ler için SSE yedeği.
+    sseUrl(path) {
+        const t = this.token();
+        return t ? `${path}?token=${encodeURIComponent(t)}` : path;
+    },
+
+    promptLogin() {
+        if (this._prompting) return;
+        this._prompting = true;
+        const t = prompt('🔒 API token required:');
+        if (t) {
+            sessionStorage.setItem(TOKEN_KEY, t.trim());
+            window.location.reload();
+        }
+        this._prompting = false;
+    },
+
+    install() {
+        const origFetch = window.fetch.bind(window);
+        window.fetch = async (input, init = {}) => {
+            const t = this.token();
+            if (t) {
+                init.headers = new Headers(init.headers || {});
+                init.headers.set('Authorization', `Bearer ${t}`);
+            }
+            const res = await origFetch(input, init);
+            if (res.status === 401) {
+                sessionStorage.removeItem(TOKEN_KEY);
+                this.promptLogin();
+            } else if (res.status === 403) {
+                alert('⛔ Read-only access: this action requires an admin token.');
+            }
+            return res;
+        };
+    }
+};
+
+Auth.install();
@@ -22,6 +22,21 @@ export const Store = {
                 this.updateHistory(payload);
                 this.notify();
                 break;
+            case 'NODES_LIST_UPDATE': {
+                // Bağlantı snapshot'ı ve deregister/eviction: listeden düşen node'un seçimi ve grafik geçmişi temizlenir
+                this.state.cluster = payload;
+                if (!this.state.localNodeName && Object.keys(payload).length > 0) {
+                    this.state.localNodeName = Object.keys(payload)[0];
+                }
+                if (this.state.selectedNode && !payload[this.state.selectedNode]) {
+                    this.state.selectedNode = null;
+                }
+                Object.keys(this.state.history).forEach(id => {
+                    if (!Object.keys(payload).some(n => id.startsWith(`${n}_`))) delete this.state.history[id];
+                });
+                this.notify();
+                break;
+            }
             case 'SELECT_NODE':
                 this.state.selectedNode = payload;
                 this.notify();
@@ -34,6 +49,23 @@ export const Store = {
                 }
                 this.notify();
                 break;
+            case 'SERVICES_UPDATE':
+                if (this.state.cluster[payload.node]) {
+                    this.state.cluster[payload.node].services = payload.services;
+                }
+                this.notify();
+                break;
+            case 'DOCKER_EVENT': {
+                const local = this.state.cluster[this.state.localNodeName];
+                const svc = local && local.services.find(s => s.name === payload.container);
+                if (svc) {
+                    const statusMap = { running: 'Up', paused: 'Up (Paused)', exited: 'Exited', oom: 'Exited (OOM)', removed: 'Removed' };
+                    if (statusMap[payload.status]) svc.status = statusMap[payload.status];
+                    if (payload.action.startsWith('health_status')) svc.healthcheck = payload.status;
+                    this.notify();
+                }
+                break;
+            }
             case 'UPDATE_PROGRESS': // [YENİ]
                 if (this.state.cluster[this.state.localNodeName]) {
                     const svcInfo = this.state.cluster[this.state.localNodeName].services.find(s => s.name === payload.service);
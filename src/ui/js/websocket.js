@@ -1,10 +1,17 @@
 // src/ui/js/websocket.js
 export class WebSocketStream {
-    constructor(url, onMessage, onStatusChange) {
+    // WebSocket hiç açılamazsa (upgrade'i bozan proxy) bu kadar denemeden sonra SSE'ye geçilir.
+    static MAX_WS_FAILURES = 2;
+    static SSE_EVENTS = ['cluster_update', 'nodes_list_update', 'services_update', 'docker_event', 'update_progress', 'disk_warning', 'temperature_warning', 'prune_result', 'auto_pilot_result', 'oom_killed', 'lifecycle', 'node_status_changed', 'lagged'];
+
+    constructor(url, onMessage, onStatusChange, sseUrl = null) {
         this.url = url;
+        this.sseUrl = sseUrl;
         this.onMessage = onMessage;
         this.onStatusChange = onStatusChange;
         this.conn = null;
+        this.everOpened = false;
+        this.failures = 0;
     }
 
     connect() {
@@ -12,18 +19,35 @@ export class WebSocketStream {
         
         this.conn.onopen = () => {
             console.log("💠 Nexus Uplink Established");
+            this.everOpened = true;
             if(this.onStatusChange) this.onStatusChange(true);
         };
         
         this.conn.onclose = () => {
-            console.log("⚠️ Nexus Uplink Lost. Reconnecting...");
             if(this.onStatusChange) this.onStatusChange(false);
+            if (!this.everOpened && this.sseUrl && ++this.failures >= WebSocketStream.MAX_WS_FAILURES) {
+                console.log("⚠️ WebSocket unavailable. Falling back to SSE.");
+                this.connectSse();
+                return;
+            }
+            console.log("⚠️ Nexus Uplink Lost. Reconnecting...");
             setTimeout(() => this.connect(), 3000);
         };
         
-        this.conn.onmessage = (e) => {
-            try { this.onMessage(JSON.parse(e.data)); } 
-            catch(err) { console.error("Parse Error", err); }
-        };
+        this.conn.onmessage = (e) => this.dispatch(e.data);
+    }
+
+    // EventSource yeniden bağlanmayı kendisi yönetir.
+    connectSse() {
+        const es = new EventSource(this.sseUrl);
+        es.onopen = () => { if(this.onStatusChange) this.onStatusChange(true); };
+        es.onerror = () => { if(this.onStatusChange) this.onStatusChange(false); };
+        es.onmessage = (e) => this.dispatch(e.data);
+        WebSocketStream.SSE_EVENTS.forEach(type => es.addEventListener(type, (e) => this.dispatch(e.data)));
+    }
+
+    dispatch(data) {
+        try { this.onMessage(JSON.parse(data)); } 
+        catch(err) { console.error("Parse Error", err); }
     }
-}
\ No newline at end of file
+}